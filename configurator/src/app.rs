@@ -15,8 +15,8 @@ use iced::{Application, Background, Border, Command, Element, Length, Settings,
 use crate::messages::Message;
 use crate::models::{
     BoardModeOption, ColorMode, ColorQuadInput, ColorTripletInput, ConfigDraft, FontStyleOption,
-    FontWeightOption, NamedColorOption, QuadField, StatusPositionOption, TabId, TextField,
-    ToggleField, TripletField,
+    FontWeightOption, NamedColorOption, QuadField, StatusPositionOption, TabId, TextAlignOption,
+    TextEffectOption, TextField, ToggleField, TripletField,
 };
 
 pub fn run() -> iced::Result {
@@ -237,6 +237,16 @@ impl Application for ConfiguratorApp {
                 self.draft.ui_status_position = option;
                 self.refresh_dirty_flag();
             }
+            Message::TextAlignChanged(option) => {
+                self.status = StatusMessage::idle();
+                self.draft.drawing_text_align = option;
+                self.refresh_dirty_flag();
+            }
+            Message::TextEffectChanged(option) => {
+                self.status = StatusMessage::idle();
+                self.draft.drawing_text_effect = option;
+                self.refresh_dirty_flag();
+            }
             Message::BoardModeChanged(option) => {
                 self.status = StatusMessage::idle();
                 self.draft.board_default_mode = option;
@@ -555,11 +565,57 @@ impl ConfiguratorApp {
                 }
             ]
             .spacing(12),
-            checkbox(
-                "Enable text background",
-                self.draft.drawing_text_background_enabled,
-            )
-            .on_toggle(|value| Message::ToggleChanged(ToggleField::DrawingTextBackground, value))
+            row![
+                column![
+                    text("Text alignment").size(14),
+                    pick_list(
+                        TextAlignOption::list(),
+                        Some(self.draft.drawing_text_align),
+                        Message::TextAlignChanged,
+                    )
+                    .width(Length::Fill),
+                ]
+                .spacing(6),
+                labeled_input(
+                    "Wrap width (px, empty = off)",
+                    &self.draft.drawing_text_wrap_width,
+                    TextField::DrawingTextWrapWidth,
+                ),
+                labeled_input(
+                    "Line spacing (x)",
+                    &self.draft.drawing_text_line_spacing,
+                    TextField::DrawingTextLineSpacing,
+                )
+            ]
+            .spacing(12),
+            row![
+                column![
+                    text("Text effect").size(14),
+                    pick_list(
+                        TextEffectOption::list(),
+                        Some(self.draft.drawing_text_effect),
+                        Message::TextEffectChanged,
+                    )
+                    .width(Length::Fill),
+                ]
+                .spacing(6),
+                labeled_input(
+                    "Outline width (px)",
+                    &self.draft.drawing_text_outline_width,
+                    TextField::DrawingTextOutlineWidth,
+                ),
+                labeled_input(
+                    "Shadow offset (px)",
+                    &self.draft.drawing_text_shadow_offset,
+                    TextField::DrawingTextShadowOffset,
+                ),
+                labeled_input(
+                    "Background padding (px)",
+                    &self.draft.drawing_text_background_padding,
+                    TextField::DrawingTextBackgroundPadding,
+                )
+            ]
+            .spacing(12)
         ]
         .spacing(12)
         .width(Length::Fill);
@@ -700,8 +756,8 @@ impl ConfiguratorApp {
 
     fn board_tab(&self) -> Element<'_, Message> {
         let board_mode_pick = pick_list(
-            BoardModeOption::list(),
-            Some(self.draft.board_default_mode),
+            BoardModeOption::list(&self.draft.board_custom),
+            Some(self.draft.board_default_mode.clone()),
             Message::BoardModeChanged,
         );
 
@@ -731,7 +787,22 @@ impl ConfiguratorApp {
                 TripletField::BoardBlackboardPen,
             ),
             checkbox("Auto-adjust pen color", self.draft.board_auto_adjust_pen)
-                .on_toggle(|value| Message::ToggleChanged(ToggleField::BoardAutoAdjust, value),)
+                .on_toggle(|value| Message::ToggleChanged(ToggleField::BoardAutoAdjust, value),),
+            checkbox(
+                "Keep drawings when the overlay closes",
+                self.draft.session_persist
+            )
+            .on_toggle(|value| Message::ToggleChanged(ToggleField::SessionPersist, value),),
+            checkbox(
+                "Autosave drawings for crash recovery",
+                self.draft.session_autosave
+            )
+            .on_toggle(|value| Message::ToggleChanged(ToggleField::SessionAutosave, value),),
+            labeled_input(
+                "Autosave interval (seconds)",
+                &self.draft.session_autosave_interval,
+                TextField::SessionAutosaveInterval,
+            )
         ]
         .spacing(12);
 
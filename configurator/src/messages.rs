@@ -5,7 +5,8 @@ use wayscriber::config::Config;
 
 use crate::models::{
     BoardModeOption, ColorMode, FontStyleOption, FontWeightOption, KeybindingField,
-    NamedColorOption, QuadField, StatusPositionOption, TabId, TextField, ToggleField, TripletField,
+    NamedColorOption, QuadField, StatusPositionOption, TabId, TextAlignOption, TextEffectOption,
+    TextField, ToggleField, TripletField,
 };
 
 #[derive(Debug, Clone)]
@@ -28,4 +29,6 @@ pub enum Message {
     KeybindingChanged(KeybindingField, String),
     FontStyleOptionSelected(FontStyleOption),
     FontWeightOptionSelected(FontWeightOption),
+    TextAlignChanged(TextAlignOption),
+    TextEffectChanged(TextEffectOption),
 }
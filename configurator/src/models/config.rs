@@ -1,10 +1,10 @@
-use wayscriber::config::Config;
+use wayscriber::config::{BoardBackgroundConfig, Config, CustomBoardConfig, PaletteConfig};
 
 use super::color::{ColorInput, ColorQuadInput, ColorTripletInput};
 use super::error::FormError;
 use super::fields::{
-    BoardModeOption, FontStyleOption, FontWeightOption, QuadField, StatusPositionOption, TextField,
-    ToggleField, TripletField,
+    BoardModeOption, FontStyleOption, FontWeightOption, QuadField, StatusPositionOption,
+    TextAlignOption, TextEffectOption, TextField, ToggleField, TripletField,
 };
 use super::keybindings::KeybindingsDraft;
 use super::util::{format_float, parse_f64};
@@ -17,7 +17,15 @@ pub struct ConfigDraft {
     pub drawing_font_family: String,
     pub drawing_font_weight: String,
     pub drawing_font_style: String,
-    pub drawing_text_background_enabled: bool,
+    pub drawing_text_effect: TextEffectOption,
+    pub drawing_text_effect_color: Option<[f64; 4]>,
+    pub drawing_text_outline_width: String,
+    pub drawing_text_shadow_offset: String,
+    pub drawing_text_background_padding: String,
+    pub drawing_text_align: TextAlignOption,
+    pub drawing_text_wrap_width: String,
+    pub drawing_text_line_spacing: String,
+    pub drawing_layers: Vec<String>,
     pub drawing_font_style_option: FontStyleOption,
     pub drawing_font_weight_option: FontWeightOption,
 
@@ -50,6 +58,8 @@ pub struct ConfigDraft {
     pub board_whiteboard_pen: ColorTripletInput,
     pub board_blackboard_pen: ColorTripletInput,
     pub board_auto_adjust_pen: bool,
+    pub board_custom: Vec<CustomBoardConfig>,
+    pub board_backgrounds: Vec<BoardBackgroundConfig>,
 
     pub capture_enabled: bool,
     pub capture_save_directory: String,
@@ -57,7 +67,13 @@ pub struct ConfigDraft {
     pub capture_format: String,
     pub capture_copy_to_clipboard: bool,
 
+    pub session_persist: bool,
+    pub session_autosave: bool,
+    pub session_autosave_interval: String,
+
     pub keybindings: KeybindingsDraft,
+
+    pub palette: PaletteConfig,
 }
 
 impl ConfigDraft {
@@ -72,7 +88,19 @@ impl ConfigDraft {
             drawing_font_family: config.drawing.font_family.clone(),
             drawing_font_weight: weight_value,
             drawing_font_style: style_value,
-            drawing_text_background_enabled: config.drawing.text_background_enabled,
+            drawing_text_effect: TextEffectOption::from_text_effect(config.drawing.text_effect),
+            drawing_text_effect_color: config.drawing.text_effect_color,
+            drawing_text_outline_width: format_float(config.drawing.text_outline_width),
+            drawing_text_shadow_offset: format_float(config.drawing.text_shadow_offset),
+            drawing_text_background_padding: format_float(config.drawing.text_background_padding),
+            drawing_text_align: TextAlignOption::from_text_align(config.drawing.text_align),
+            drawing_text_wrap_width: config
+                .drawing
+                .text_wrap_width
+                .map(format_float)
+                .unwrap_or_default(),
+            drawing_text_line_spacing: format_float(config.drawing.text_line_spacing),
+            drawing_layers: config.drawing.layers.clone(),
             drawing_font_style_option: style_option,
             drawing_font_weight_option: weight_option,
 
@@ -101,13 +129,18 @@ impl ConfigDraft {
             help_text_color: ColorQuadInput::from(config.ui.help_overlay_style.text_color),
 
             board_enabled: config.board.enabled,
-            board_default_mode: BoardModeOption::from_str(&config.board.default_mode)
-                .unwrap_or(BoardModeOption::Transparent),
+            board_default_mode: BoardModeOption::from_str(
+                &config.board.default_mode,
+                &config.board.custom,
+            )
+            .unwrap_or(BoardModeOption::Transparent),
             board_whiteboard_color: ColorTripletInput::from(config.board.whiteboard_color),
             board_blackboard_color: ColorTripletInput::from(config.board.blackboard_color),
             board_whiteboard_pen: ColorTripletInput::from(config.board.whiteboard_pen_color),
             board_blackboard_pen: ColorTripletInput::from(config.board.blackboard_pen_color),
             board_auto_adjust_pen: config.board.auto_adjust_pen,
+            board_custom: config.board.custom.clone(),
+            board_backgrounds: config.board.backgrounds.clone(),
 
             capture_enabled: config.capture.enabled,
             capture_save_directory: config.capture.save_directory.clone(),
@@ -115,7 +148,12 @@ impl ConfigDraft {
             capture_format: config.capture.format.clone(),
             capture_copy_to_clipboard: config.capture.copy_to_clipboard,
 
+            session_persist: config.session.persist,
+            session_autosave: config.session.autosave,
+            session_autosave_interval: config.session.autosave_interval_secs.to_string(),
+
             keybindings: KeybindingsDraft::from_config(&config.keybindings),
+            palette: config.palette.clone(),
         }
     }
 
@@ -142,7 +180,43 @@ impl ConfigDraft {
         config.drawing.font_family = self.drawing_font_family.clone();
         config.drawing.font_weight = self.drawing_font_weight.clone();
         config.drawing.font_style = self.drawing_font_style.clone();
-        config.drawing.text_background_enabled = self.drawing_text_background_enabled;
+        config.drawing.text_effect = self.drawing_text_effect.to_text_effect();
+        // The effect color has no editor yet; carry it over unchanged.
+        config.drawing.text_effect_color = self.drawing_text_effect_color;
+        parse_field(
+            &self.drawing_text_outline_width,
+            "drawing.text_outline_width",
+            &mut errors,
+            |value| config.drawing.text_outline_width = value,
+        );
+        parse_field(
+            &self.drawing_text_shadow_offset,
+            "drawing.text_shadow_offset",
+            &mut errors,
+            |value| config.drawing.text_shadow_offset = value,
+        );
+        parse_field(
+            &self.drawing_text_background_padding,
+            "drawing.text_background_padding",
+            &mut errors,
+            |value| config.drawing.text_background_padding = value,
+        );
+        config.drawing.text_align = self.drawing_text_align.to_text_align();
+        // An empty wrap width means no wrapping
+        if !self.drawing_text_wrap_width.trim().is_empty() {
+            parse_field(
+                &self.drawing_text_wrap_width,
+                "drawing.text_wrap_width",
+                &mut errors,
+                |value| config.drawing.text_wrap_width = Some(value),
+            );
+        }
+        parse_field(
+            &self.drawing_text_line_spacing,
+            "drawing.text_line_spacing",
+            &mut errors,
+            |value| config.drawing.text_line_spacing = value,
+        );
 
         parse_field(&self.arrow_length, "arrow.length", &mut errors, |value| {
             config.arrow.length = value
@@ -276,11 +350,28 @@ impl ConfigDraft {
         config.capture.format = self.capture_format.clone();
         config.capture.copy_to_clipboard = self.capture_copy_to_clipboard;
 
+        config.session.persist = self.session_persist;
+        config.session.autosave = self.session_autosave;
+        match self.session_autosave_interval.trim().parse::<u64>() {
+            Ok(value) => config.session.autosave_interval_secs = value,
+            Err(_) => errors.push(FormError::new(
+                "session.autosave_interval_secs",
+                "Expected a whole number of seconds",
+            )),
+        }
+
         match self.keybindings.to_config() {
             Ok(cfg) => config.keybindings = cfg,
             Err(errs) => errors.extend(errs),
         }
 
+        // Palette entries, layers, custom boards and background images have no
+        // editor yet; carry them over unchanged.
+        config.palette = self.palette.clone();
+        config.drawing.layers = self.drawing_layers.clone();
+        config.board.custom = self.board_custom.clone();
+        config.board.backgrounds = self.board_backgrounds.clone();
+
         if errors.is_empty() {
             Ok(config)
         } else {
@@ -290,15 +381,14 @@ impl ConfigDraft {
 
     pub fn set_toggle(&mut self, field: ToggleField, value: bool) {
         match field {
-            ToggleField::DrawingTextBackground => {
-                self.drawing_text_background_enabled = value;
-            }
             ToggleField::PerformanceVsync => self.performance_enable_vsync = value,
             ToggleField::UiShowStatusBar => self.ui_show_status_bar = value,
             ToggleField::BoardEnabled => self.board_enabled = value,
             ToggleField::BoardAutoAdjust => self.board_auto_adjust_pen = value,
             ToggleField::CaptureEnabled => self.capture_enabled = value,
             ToggleField::CaptureCopyToClipboard => self.capture_copy_to_clipboard = value,
+            ToggleField::SessionPersist => self.session_persist = value,
+            ToggleField::SessionAutosave => self.session_autosave = value,
         }
     }
 
@@ -319,6 +409,11 @@ impl ConfigDraft {
                 self.drawing_font_style = value;
                 self.drawing_font_style_option = FontStyleOption::Custom;
             }
+            TextField::DrawingTextWrapWidth => self.drawing_text_wrap_width = value,
+            TextField::DrawingTextLineSpacing => self.drawing_text_line_spacing = value,
+            TextField::DrawingTextOutlineWidth => self.drawing_text_outline_width = value,
+            TextField::DrawingTextShadowOffset => self.drawing_text_shadow_offset = value,
+            TextField::DrawingTextBackgroundPadding => self.drawing_text_background_padding = value,
             TextField::ArrowLength => self.arrow_length = value,
             TextField::ArrowAngle => self.arrow_angle = value,
             TextField::StatusFontSize => self.status_font_size = value,
@@ -331,6 +426,7 @@ impl ConfigDraft {
             TextField::CaptureSaveDirectory => self.capture_save_directory = value,
             TextField::CaptureFilename => self.capture_filename_template = value,
             TextField::CaptureFormat => self.capture_format = value,
+            TextField::SessionAutosaveInterval => self.session_autosave_interval = value,
         }
     }
 
@@ -1,4 +1,4 @@
-use wayscriber::config::StatusPosition;
+use wayscriber::config::{CustomBoardConfig, StatusPosition, TextAlign, TextEffectStyle};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FontStyleOption {
@@ -169,43 +169,145 @@ impl std::fmt::Display for StatusPositionOption {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlignOption {
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlignOption {
+    pub fn list() -> Vec<Self> {
+        vec![Self::Left, Self::Center, Self::Right]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Left => "Left",
+            Self::Center => "Center",
+            Self::Right => "Right",
+        }
+    }
+
+    pub fn to_text_align(&self) -> TextAlign {
+        match self {
+            Self::Left => TextAlign::Left,
+            Self::Center => TextAlign::Center,
+            Self::Right => TextAlign::Right,
+        }
+    }
+
+    pub fn from_text_align(align: TextAlign) -> Self {
+        match align {
+            TextAlign::Left => Self::Left,
+            TextAlign::Center => Self::Center,
+            TextAlign::Right => Self::Right,
+        }
+    }
+}
+
+impl std::fmt::Display for TextAlignOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEffectOption {
+    None,
+    Background,
+    Outline,
+    Shadow,
+}
+
+impl TextEffectOption {
+    pub fn list() -> Vec<Self> {
+        vec![Self::None, Self::Background, Self::Outline, Self::Shadow]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Background => "Background box",
+            Self::Outline => "Outline",
+            Self::Shadow => "Drop shadow",
+        }
+    }
+
+    pub fn to_text_effect(&self) -> TextEffectStyle {
+        match self {
+            Self::None => TextEffectStyle::None,
+            Self::Background => TextEffectStyle::Background,
+            Self::Outline => TextEffectStyle::Outline,
+            Self::Shadow => TextEffectStyle::Shadow,
+        }
+    }
+
+    pub fn from_text_effect(effect: TextEffectStyle) -> Self {
+        match effect {
+            TextEffectStyle::None => Self::None,
+            TextEffectStyle::Background => Self::Background,
+            TextEffectStyle::Outline => Self::Outline,
+            TextEffectStyle::Shadow => Self::Shadow,
+        }
+    }
+}
+
+impl std::fmt::Display for TextEffectOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BoardModeOption {
     Transparent,
     Whiteboard,
     Blackboard,
+    Custom(String),
 }
 
 impl BoardModeOption {
-    pub fn list() -> Vec<Self> {
-        vec![
+    pub fn list(custom: &[CustomBoardConfig]) -> Vec<Self> {
+        let mut options = vec![
             BoardModeOption::Transparent,
             BoardModeOption::Whiteboard,
             BoardModeOption::Blackboard,
-        ]
+        ];
+        options.extend(
+            custom
+                .iter()
+                .map(|board| BoardModeOption::Custom(board.name.clone())),
+        );
+        options
     }
 
-    pub fn label(&self) -> &'static str {
+    pub fn label(&self) -> &str {
         match self {
             BoardModeOption::Transparent => "Transparent",
             BoardModeOption::Whiteboard => "Whiteboard",
             BoardModeOption::Blackboard => "Blackboard",
+            BoardModeOption::Custom(name) => name,
         }
     }
 
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             BoardModeOption::Transparent => "transparent",
             BoardModeOption::Whiteboard => "whiteboard",
             BoardModeOption::Blackboard => "blackboard",
+            BoardModeOption::Custom(name) => name,
         }
     }
 
-    pub fn from_str(value: &str) -> Option<Self> {
+    pub fn from_str(value: &str, custom: &[CustomBoardConfig]) -> Option<Self> {
         match value {
             "transparent" => Some(BoardModeOption::Transparent),
             "whiteboard" => Some(BoardModeOption::Whiteboard),
             "blackboard" => Some(BoardModeOption::Blackboard),
-            _ => None,
+            _ => custom
+                .iter()
+                .find(|board| board.name.eq_ignore_ascii_case(value))
+                .map(|board| BoardModeOption::Custom(board.name.clone())),
         }
     }
 }
@@ -218,13 +320,14 @@ impl std::fmt::Display for BoardModeOption {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ToggleField {
-    DrawingTextBackground,
     PerformanceVsync,
     UiShowStatusBar,
     BoardEnabled,
     BoardAutoAdjust,
     CaptureEnabled,
     CaptureCopyToClipboard,
+    SessionPersist,
+    SessionAutosave,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -235,6 +338,11 @@ pub enum TextField {
     DrawingFontFamily,
     DrawingFontWeight,
     DrawingFontStyle,
+    DrawingTextWrapWidth,
+    DrawingTextLineSpacing,
+    DrawingTextOutlineWidth,
+    DrawingTextShadowOffset,
+    DrawingTextBackgroundPadding,
     ArrowLength,
     ArrowAngle,
     StatusFontSize,
@@ -247,6 +355,7 @@ pub enum TextField {
     CaptureSaveDirectory,
     CaptureFilename,
     CaptureFormat,
+    SessionAutosaveInterval,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
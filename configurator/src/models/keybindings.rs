@@ -19,23 +19,29 @@ pub enum KeybindingField {
     EnterTextMode,
     ClearCanvas,
     Undo,
+    Redo,
+    StartFresh,
+    ToggleSelectionMode,
+    DeleteSelection,
+    CopyShapes,
+    CutShapes,
+    PasteShapes,
+    BringToFront,
+    SendToBack,
+    GroupShapes,
+    UngroupShapes,
     IncreaseThickness,
     DecreaseThickness,
     IncreaseFontSize,
     DecreaseFontSize,
+    CycleTextAlignment,
     ToggleWhiteboard,
     ToggleBlackboard,
     ReturnToTransparent,
     ToggleHelp,
     OpenConfigurator,
-    SetColorRed,
-    SetColorGreen,
-    SetColorBlue,
-    SetColorYellow,
-    SetColorOrange,
-    SetColorPink,
-    SetColorWhite,
-    SetColorBlack,
+    NextRecentColor,
+    PreviousRecentColor,
     CaptureFullScreen,
     CaptureActiveWindow,
     CaptureSelection,
@@ -45,6 +51,18 @@ pub enum KeybindingField {
     CaptureFileSelection,
     CaptureClipboardRegion,
     CaptureFileRegion,
+    ExportSvg,
+    ExportPdf,
+    SaveDocument,
+    SaveDocumentAs,
+    NextPage,
+    PreviousPage,
+    NewPage,
+    DeletePage,
+    NextLayer,
+    ToggleLayerVisibility,
+    ClearLayer,
+    Peek,
 }
 
 impl KeybindingsDraft {
@@ -94,23 +112,29 @@ impl KeybindingField {
             Self::EnterTextMode,
             Self::ClearCanvas,
             Self::Undo,
+            Self::Redo,
+            Self::StartFresh,
+            Self::ToggleSelectionMode,
+            Self::DeleteSelection,
+            Self::CopyShapes,
+            Self::CutShapes,
+            Self::PasteShapes,
+            Self::BringToFront,
+            Self::SendToBack,
+            Self::GroupShapes,
+            Self::UngroupShapes,
             Self::IncreaseThickness,
             Self::DecreaseThickness,
             Self::IncreaseFontSize,
             Self::DecreaseFontSize,
+            Self::CycleTextAlignment,
             Self::ToggleWhiteboard,
             Self::ToggleBlackboard,
             Self::ReturnToTransparent,
             Self::ToggleHelp,
             Self::OpenConfigurator,
-            Self::SetColorRed,
-            Self::SetColorGreen,
-            Self::SetColorBlue,
-            Self::SetColorYellow,
-            Self::SetColorOrange,
-            Self::SetColorPink,
-            Self::SetColorWhite,
-            Self::SetColorBlack,
+            Self::NextRecentColor,
+            Self::PreviousRecentColor,
             Self::CaptureFullScreen,
             Self::CaptureActiveWindow,
             Self::CaptureSelection,
@@ -120,6 +144,18 @@ impl KeybindingField {
             Self::CaptureFileSelection,
             Self::CaptureClipboardRegion,
             Self::CaptureFileRegion,
+            Self::ExportSvg,
+            Self::ExportPdf,
+            Self::SaveDocument,
+            Self::SaveDocumentAs,
+            Self::NextPage,
+            Self::PreviousPage,
+            Self::NewPage,
+            Self::DeletePage,
+            Self::NextLayer,
+            Self::ToggleLayerVisibility,
+            Self::ClearLayer,
+            Self::Peek,
         ]
     }
 
@@ -129,23 +165,29 @@ impl KeybindingField {
             Self::EnterTextMode => "Enter text mode",
             Self::ClearCanvas => "Clear canvas",
             Self::Undo => "Undo",
+            Self::Redo => "Redo",
+            Self::StartFresh => "Start fresh",
+            Self::ToggleSelectionMode => "Toggle selection mode",
+            Self::DeleteSelection => "Delete selection",
+            Self::CopyShapes => "Copy shapes",
+            Self::CutShapes => "Cut shapes",
+            Self::PasteShapes => "Paste shapes",
+            Self::BringToFront => "Bring to front",
+            Self::SendToBack => "Send to back",
+            Self::GroupShapes => "Group shapes",
+            Self::UngroupShapes => "Ungroup shapes",
             Self::IncreaseThickness => "Increase thickness",
             Self::DecreaseThickness => "Decrease thickness",
             Self::IncreaseFontSize => "Increase font size",
             Self::DecreaseFontSize => "Decrease font size",
+            Self::CycleTextAlignment => "Cycle text alignment",
             Self::ToggleWhiteboard => "Toggle whiteboard",
             Self::ToggleBlackboard => "Toggle blackboard",
             Self::ReturnToTransparent => "Return to transparent",
             Self::ToggleHelp => "Toggle help",
             Self::OpenConfigurator => "Open configurator",
-            Self::SetColorRed => "Color: red",
-            Self::SetColorGreen => "Color: green",
-            Self::SetColorBlue => "Color: blue",
-            Self::SetColorYellow => "Color: yellow",
-            Self::SetColorOrange => "Color: orange",
-            Self::SetColorPink => "Color: pink",
-            Self::SetColorWhite => "Color: white",
-            Self::SetColorBlack => "Color: black",
+            Self::NextRecentColor => "Next recent color",
+            Self::PreviousRecentColor => "Previous recent color",
             Self::CaptureFullScreen => "Capture full screen",
             Self::CaptureActiveWindow => "Capture active window",
             Self::CaptureSelection => "Capture selection",
@@ -155,6 +197,18 @@ impl KeybindingField {
             Self::CaptureFileSelection => "File selection",
             Self::CaptureClipboardRegion => "Clipboard region",
             Self::CaptureFileRegion => "File region",
+            Self::ExportSvg => "Export SVG",
+            Self::ExportPdf => "Export PDF",
+            Self::SaveDocument => "Save document",
+            Self::SaveDocumentAs => "Save document as new file",
+            Self::NextPage => "Next page",
+            Self::PreviousPage => "Previous page",
+            Self::NewPage => "New page",
+            Self::DeletePage => "Delete page",
+            Self::NextLayer => "Next layer",
+            Self::ToggleLayerVisibility => "Toggle layer visibility",
+            Self::ClearLayer => "Clear layer",
+            Self::Peek => "Peek (hide annotations)",
         }
     }
 
@@ -164,23 +218,29 @@ impl KeybindingField {
             Self::EnterTextMode => "enter_text_mode",
             Self::ClearCanvas => "clear_canvas",
             Self::Undo => "undo",
+            Self::Redo => "redo",
+            Self::StartFresh => "start_fresh",
+            Self::ToggleSelectionMode => "toggle_selection_mode",
+            Self::DeleteSelection => "delete_selection",
+            Self::CopyShapes => "copy_shapes",
+            Self::CutShapes => "cut_shapes",
+            Self::PasteShapes => "paste_shapes",
+            Self::BringToFront => "bring_to_front",
+            Self::SendToBack => "send_to_back",
+            Self::GroupShapes => "group_shapes",
+            Self::UngroupShapes => "ungroup_shapes",
             Self::IncreaseThickness => "increase_thickness",
             Self::DecreaseThickness => "decrease_thickness",
             Self::IncreaseFontSize => "increase_font_size",
             Self::DecreaseFontSize => "decrease_font_size",
+            Self::CycleTextAlignment => "cycle_text_alignment",
             Self::ToggleWhiteboard => "toggle_whiteboard",
             Self::ToggleBlackboard => "toggle_blackboard",
             Self::ReturnToTransparent => "return_to_transparent",
             Self::ToggleHelp => "toggle_help",
             Self::OpenConfigurator => "open_configurator",
-            Self::SetColorRed => "set_color_red",
-            Self::SetColorGreen => "set_color_green",
-            Self::SetColorBlue => "set_color_blue",
-            Self::SetColorYellow => "set_color_yellow",
-            Self::SetColorOrange => "set_color_orange",
-            Self::SetColorPink => "set_color_pink",
-            Self::SetColorWhite => "set_color_white",
-            Self::SetColorBlack => "set_color_black",
+            Self::NextRecentColor => "next_recent_color",
+            Self::PreviousRecentColor => "previous_recent_color",
             Self::CaptureFullScreen => "capture_full_screen",
             Self::CaptureActiveWindow => "capture_active_window",
             Self::CaptureSelection => "capture_selection",
@@ -190,6 +250,18 @@ impl KeybindingField {
             Self::CaptureFileSelection => "capture_file_selection",
             Self::CaptureClipboardRegion => "capture_clipboard_region",
             Self::CaptureFileRegion => "capture_file_region",
+            Self::ExportSvg => "export_svg",
+            Self::ExportPdf => "export_pdf",
+            Self::SaveDocument => "save_document",
+            Self::SaveDocumentAs => "save_document_as",
+            Self::NextPage => "next_page",
+            Self::PreviousPage => "previous_page",
+            Self::NewPage => "new_page",
+            Self::DeletePage => "delete_page",
+            Self::NextLayer => "next_layer",
+            Self::ToggleLayerVisibility => "toggle_layer_visibility",
+            Self::ClearLayer => "clear_layer",
+            Self::Peek => "peek",
         }
     }
 
@@ -199,23 +271,29 @@ impl KeybindingField {
             Self::EnterTextMode => &config.enter_text_mode,
             Self::ClearCanvas => &config.clear_canvas,
             Self::Undo => &config.undo,
+            Self::Redo => &config.redo,
+            Self::StartFresh => &config.start_fresh,
+            Self::ToggleSelectionMode => &config.toggle_selection_mode,
+            Self::DeleteSelection => &config.delete_selection,
+            Self::CopyShapes => &config.copy_shapes,
+            Self::CutShapes => &config.cut_shapes,
+            Self::PasteShapes => &config.paste_shapes,
+            Self::BringToFront => &config.bring_to_front,
+            Self::SendToBack => &config.send_to_back,
+            Self::GroupShapes => &config.group_shapes,
+            Self::UngroupShapes => &config.ungroup_shapes,
             Self::IncreaseThickness => &config.increase_thickness,
             Self::DecreaseThickness => &config.decrease_thickness,
             Self::IncreaseFontSize => &config.increase_font_size,
             Self::DecreaseFontSize => &config.decrease_font_size,
+            Self::CycleTextAlignment => &config.cycle_text_alignment,
             Self::ToggleWhiteboard => &config.toggle_whiteboard,
             Self::ToggleBlackboard => &config.toggle_blackboard,
             Self::ReturnToTransparent => &config.return_to_transparent,
             Self::ToggleHelp => &config.toggle_help,
             Self::OpenConfigurator => &config.open_configurator,
-            Self::SetColorRed => &config.set_color_red,
-            Self::SetColorGreen => &config.set_color_green,
-            Self::SetColorBlue => &config.set_color_blue,
-            Self::SetColorYellow => &config.set_color_yellow,
-            Self::SetColorOrange => &config.set_color_orange,
-            Self::SetColorPink => &config.set_color_pink,
-            Self::SetColorWhite => &config.set_color_white,
-            Self::SetColorBlack => &config.set_color_black,
+            Self::NextRecentColor => &config.next_recent_color,
+            Self::PreviousRecentColor => &config.previous_recent_color,
             Self::CaptureFullScreen => &config.capture_full_screen,
             Self::CaptureActiveWindow => &config.capture_active_window,
             Self::CaptureSelection => &config.capture_selection,
@@ -225,6 +303,18 @@ impl KeybindingField {
             Self::CaptureFileSelection => &config.capture_file_selection,
             Self::CaptureClipboardRegion => &config.capture_clipboard_region,
             Self::CaptureFileRegion => &config.capture_file_region,
+            Self::ExportSvg => &config.export_svg,
+            Self::ExportPdf => &config.export_pdf,
+            Self::SaveDocument => &config.save_document,
+            Self::SaveDocumentAs => &config.save_document_as,
+            Self::NextPage => &config.next_page,
+            Self::PreviousPage => &config.previous_page,
+            Self::NewPage => &config.new_page,
+            Self::DeletePage => &config.delete_page,
+            Self::NextLayer => &config.next_layer,
+            Self::ToggleLayerVisibility => &config.toggle_layer_visibility,
+            Self::ClearLayer => &config.clear_layer,
+            Self::Peek => &config.peek,
         }
     }
 
@@ -234,23 +324,29 @@ impl KeybindingField {
             Self::EnterTextMode => config.enter_text_mode = value,
             Self::ClearCanvas => config.clear_canvas = value,
             Self::Undo => config.undo = value,
+            Self::Redo => config.redo = value,
+            Self::StartFresh => config.start_fresh = value,
+            Self::ToggleSelectionMode => config.toggle_selection_mode = value,
+            Self::DeleteSelection => config.delete_selection = value,
+            Self::CopyShapes => config.copy_shapes = value,
+            Self::CutShapes => config.cut_shapes = value,
+            Self::PasteShapes => config.paste_shapes = value,
+            Self::BringToFront => config.bring_to_front = value,
+            Self::SendToBack => config.send_to_back = value,
+            Self::GroupShapes => config.group_shapes = value,
+            Self::UngroupShapes => config.ungroup_shapes = value,
             Self::IncreaseThickness => config.increase_thickness = value,
             Self::DecreaseThickness => config.decrease_thickness = value,
             Self::IncreaseFontSize => config.increase_font_size = value,
             Self::DecreaseFontSize => config.decrease_font_size = value,
+            Self::CycleTextAlignment => config.cycle_text_alignment = value,
             Self::ToggleWhiteboard => config.toggle_whiteboard = value,
             Self::ToggleBlackboard => config.toggle_blackboard = value,
             Self::ReturnToTransparent => config.return_to_transparent = value,
             Self::ToggleHelp => config.toggle_help = value,
             Self::OpenConfigurator => config.open_configurator = value,
-            Self::SetColorRed => config.set_color_red = value,
-            Self::SetColorGreen => config.set_color_green = value,
-            Self::SetColorBlue => config.set_color_blue = value,
-            Self::SetColorYellow => config.set_color_yellow = value,
-            Self::SetColorOrange => config.set_color_orange = value,
-            Self::SetColorPink => config.set_color_pink = value,
-            Self::SetColorWhite => config.set_color_white = value,
-            Self::SetColorBlack => config.set_color_black = value,
+            Self::NextRecentColor => config.next_recent_color = value,
+            Self::PreviousRecentColor => config.previous_recent_color = value,
             Self::CaptureFullScreen => config.capture_full_screen = value,
             Self::CaptureActiveWindow => config.capture_active_window = value,
             Self::CaptureSelection => config.capture_selection = value,
@@ -260,6 +356,18 @@ impl KeybindingField {
             Self::CaptureFileSelection => config.capture_file_selection = value,
             Self::CaptureClipboardRegion => config.capture_clipboard_region = value,
             Self::CaptureFileRegion => config.capture_file_region = value,
+            Self::ExportSvg => config.export_svg = value,
+            Self::ExportPdf => config.export_pdf = value,
+            Self::SaveDocument => config.save_document = value,
+            Self::SaveDocumentAs => config.save_document_as = value,
+            Self::NextPage => config.next_page = value,
+            Self::PreviousPage => config.previous_page = value,
+            Self::NewPage => config.new_page = value,
+            Self::DeletePage => config.delete_page = value,
+            Self::NextLayer => config.next_layer = value,
+            Self::ToggleLayerVisibility => config.toggle_layer_visibility = value,
+            Self::ClearLayer => config.clear_layer = value,
+            Self::Peek => config.peek = value,
         }
     }
 }
@@ -9,8 +9,8 @@ pub mod util;
 pub use color::{ColorMode, ColorQuadInput, ColorTripletInput, NamedColorOption};
 pub use config::ConfigDraft;
 pub use fields::{
-    BoardModeOption, FontStyleOption, FontWeightOption, QuadField, StatusPositionOption, TextField,
-    ToggleField, TripletField,
+    BoardModeOption, FontStyleOption, FontWeightOption, QuadField, StatusPositionOption,
+    TextAlignOption, TextEffectOption, TextField, ToggleField, TripletField,
 };
 pub use keybindings::KeybindingField;
 pub use tab::TabId;
@@ -1,24 +1,178 @@
 use anyhow::Result;
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::fd::{AsFd, BorrowedFd};
+use std::os::raw::c_int;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::document::Document;
+use crate::export::ExportFormat;
 
 pub mod wayland;
 
 // Removed: Backend trait - no longer needed with single backend
 // Removed: BackendChoice enum - Wayland is the only backend
 
+/// Requests made of the overlay from other threads (daemon, tray, signals).
+///
+/// The overlay's event loop sleeps until the compositor sends events, so
+/// every request also wakes it through a socket polled next to the Wayland
+/// connection.
+#[derive(Debug)]
+pub struct OverlayControl {
+    click_through_toggle: Arc<AtomicBool>,
+    wake_tx: UnixStream,
+    wake_rx: UnixStream,
+}
+
+impl OverlayControl {
+    /// Creates a control with no pending requests.
+    pub fn new() -> io::Result<Self> {
+        let (wake_tx, wake_rx) = UnixStream::pair()?;
+        wake_tx.set_nonblocking(true)?;
+        wake_rx.set_nonblocking(true)?;
+        Ok(Self {
+            click_through_toggle: Arc::new(AtomicBool::new(false)),
+            wake_tx,
+            wake_rx,
+        })
+    }
+
+    /// Asks the overlay to toggle click-through mode.
+    pub fn toggle_click_through(&self) {
+        self.click_through_toggle.store(true, Ordering::Release);
+        self.wake();
+    }
+
+    /// Makes `signal` toggle click-through mode.
+    ///
+    /// The request is made from the signal handler itself, so no thread has
+    /// to wait for the signal.
+    pub fn toggle_click_through_on(&self, signal: c_int) -> io::Result<()> {
+        // Handlers run in registration order: set the flag before waking
+        signal_hook::flag::register(signal, self.click_through_toggle.clone())?;
+        signal_hook::low_level::pipe::register(signal, self.wake_tx.try_clone()?)?;
+        Ok(())
+    }
+
+    /// Wakes the overlay's event loop without a request, e.g. once a
+    /// background task has finished.
+    pub fn wake(&self) {
+        // A full socket already holds a pending wakeup
+        if let Err(e) = (&self.wake_tx).write(&[1])
+            && e.kind() != ErrorKind::WouldBlock
+        {
+            log::warn!("Failed to wake the overlay: {}", e);
+        }
+    }
+
+    /// Takes the pending click-through request, if any.
+    pub fn take_click_through_toggle(&self) -> bool {
+        self.click_through_toggle.swap(false, Ordering::Acquire)
+    }
+
+    /// Drops requests and wakeups left from before the overlay started.
+    pub fn clear(&self) {
+        self.click_through_toggle.store(false, Ordering::Release);
+        self.drain_wakeups();
+    }
+
+    /// Returns a descriptor that is readable while a wakeup is pending.
+    pub fn wake_fd(&self) -> BorrowedFd<'_> {
+        self.wake_rx.as_fd()
+    }
+
+    /// Consumes pending wakeups so the next poll sleeps again.
+    pub fn drain_wakeups(&self) {
+        let mut buf = [0u8; 64];
+        loop {
+            match (&self.wake_rx).read(&mut buf) {
+                Ok(0) => break,
+                Ok(_) => continue,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    log::warn!("Failed to read overlay wakeups: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
 /// Run Wayland backend with full event loop
 ///
 /// # Arguments
 /// * `initial_mode` - Optional board mode to start in (overrides config default)
-pub fn run_wayland(initial_mode: Option<String>) -> Result<()> {
-    let mut backend = wayland::WaylandBackend::new(initial_mode)?;
+/// * `control` - Requests made of the overlay from other threads
+pub fn run_wayland(initial_mode: Option<String>, control: Arc<OverlayControl>) -> Result<()> {
+    let mut backend = wayland::WaylandBackend::new(initial_mode)?.with_control(control);
     backend.init()?;
     backend.show()?; // show() calls run() internally
     backend.hide()?;
     Ok(())
 }
 
+/// Run Wayland backend once, optionally opening a document and exporting the
+/// boards when the overlay closes
+///
+/// # Arguments
+/// * `initial_mode` - Optional board mode to start in (overrides config default)
+/// * `document` - Optional document to open, with the file it was loaded from
+/// * `exports` - Formats to export the boards in on close
+/// * `control` - Requests made of the overlay from other threads
+///
+/// # Returns
+/// Paths of the exported files
+pub fn run_wayland_oneshot(
+    initial_mode: Option<String>,
+    document: Option<(PathBuf, Document)>,
+    exports: Vec<ExportFormat>,
+    control: Arc<OverlayControl>,
+) -> Result<Vec<PathBuf>> {
+    let mut backend = wayland::WaylandBackend::new(initial_mode)?
+        .with_exports_on_exit(exports)
+        .with_control(control);
+    if let Some((path, document)) = document {
+        backend = backend.with_document(path, document);
+    }
+    backend.init()?;
+    backend.show()?;
+    backend.hide()?;
+    Ok(backend.take_exported())
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use nix::poll::{PollFd, PollFlags, poll};
+
+    fn wake_pending(control: &OverlayControl) -> bool {
+        let mut fds = [PollFd::new(control.wake_fd(), PollFlags::POLLIN)];
+        poll(&mut fds, 0u8).unwrap() > 0
+    }
+
+    #[test]
+    fn requests_wake_the_overlay_until_drained() {
+        let control = OverlayControl::new().unwrap();
+        assert!(!wake_pending(&control));
+
+        control.toggle_click_through();
+        control.toggle_click_through();
+        assert!(wake_pending(&control));
+        control.drain_wakeups();
+        assert!(!wake_pending(&control));
+        assert!(control.take_click_through_toggle());
+        assert!(!control.take_click_through_toggle());
+
+        control.toggle_click_through();
+        control.clear();
+        assert!(!wake_pending(&control));
+        assert!(!control.take_click_through_toggle());
+    }
+
     #[test]
     #[ignore]
     fn wayland_backend_smoke_test() {
@@ -26,6 +180,7 @@ mod tests {
             eprintln!("WAYLAND_DISPLAY not set; skipping Wayland smoke test");
             return;
         }
-        super::run_wayland(None).expect("Wayland backend should start");
+        let control = Arc::new(OverlayControl::new().unwrap());
+        super::run_wayland(None, control).expect("Wayland backend should start");
     }
 }
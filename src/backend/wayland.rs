@@ -1,8 +1,10 @@
 // Wayland backend using wlr-layer-shell for overlay
 use anyhow::{Context, Result};
 use log::{debug, info, warn};
+use nix::errno::Errno;
+use nix::poll::{PollFd, PollFlags, PollTimeout, poll};
 use smithay_client_toolkit::{
-    compositor::{CompositorHandler, CompositorState},
+    compositor::{CompositorHandler, CompositorState, Region},
     delegate_compositor, delegate_keyboard, delegate_layer, delegate_output, delegate_pointer,
     delegate_registry, delegate_seat, delegate_shm,
     output::{OutputHandler, OutputState},
@@ -23,21 +25,49 @@ use smithay_client_toolkit::{
     shm::{Shm, ShmHandler, slot::SlotPool},
 };
 use wayland_client::{
-    Connection, Dispatch, QueueHandle,
+    Connection, Dispatch, EventQueue, QueueHandle,
+    backend::WaylandError,
     globals::registry_queue_init,
     protocol::{wl_buffer, wl_keyboard, wl_output, wl_pointer, wl_seat, wl_shm, wl_surface},
 };
-// Removed: Arc, Mutex - not needed after removing WaylandBackend.inner
+use wayland_protocols::wp::text_input::zv3::client::{
+    zwp_text_input_manager_v3::ZwpTextInputManagerV3,
+    zwp_text_input_v3::{self, ContentHint, ContentPurpose, ZwpTextInputV3},
+};
+// Removed: Mutex - not needed after removing WaylandBackend.inner
 
-use crate::capture::{CaptureDestination, CaptureManager, CaptureOutcome};
+use crate::capture::{CaptureDestination, CaptureError, CaptureManager, CaptureOutcome};
 use crate::config::{Action, Config, ConfigSource};
-use crate::input::{InputState, Key, MouseButton};
+use crate::document::{self, Document, SaveMode};
+use crate::draw::cache::ShapeCache;
+use crate::draw::damage::{Damage, shape_damage_bounds};
+use crate::draw::handles::SelectionHandle;
+use crate::draw::{BackgroundImages, BoundingBox, TextFormat};
+use crate::export::ExportFormat;
+use crate::input::clipboard::{SHAPES_MIME_TYPE, deserialize_shapes, serialize_shapes};
+use crate::input::{BoardMode, InputState, Key, MouseButton};
 use crate::legacy;
+use crate::session;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use super::OverlayControl;
 
 /// Wayland backend state
 pub struct WaylandBackend {
     // Removed: inner Arc<Mutex> was unused - WaylandState is created and used directly in run()
     initial_mode: Option<String>,
+    /// Document to open instead of the saved session, and the file it came from
+    document: Option<(PathBuf, Document)>,
+    /// Formats the session is exported in when the overlay closes
+    exports_on_exit: Vec<ExportFormat>,
+    /// Files written by the exports on exit
+    exported: Vec<PathBuf>,
+    /// Requests made of the overlay from other threads (daemon, tray, signals)
+    control: Arc<OverlayControl>,
     /// Tokio runtime for async capture operations
     tokio_runtime: tokio::runtime::Runtime,
 }
@@ -58,6 +88,14 @@ struct WaylandState {
     width: u32,
     height: u32,
     configured: bool,
+    click_through: bool,
+
+    // Committed shapes rendered offscreen, and what is drawn over them
+    shape_cache: Option<ShapeCache>,
+    rendered_board: Option<BoardMode>,
+    peek_rendered: bool,
+    background_images: BackgroundImages,
+    overlays: Vec<BoundingBox>,
 
     // Frame synchronization
     frame_callback_pending: bool,
@@ -77,8 +115,47 @@ struct WaylandState {
     capture_in_progress: bool,
     overlay_hidden_for_capture: bool,
 
+    // Shapes being read from the system clipboard for a paste
+    clipboard_read: Option<mpsc::Receiver<Result<Vec<u8>, CaptureError>>>,
+
+    // Wakes the event loop when the clipboard read finishes
+    control: Arc<OverlayControl>,
+
     // Tokio runtime handle for async operations
     tokio_handle: tokio::runtime::Handle,
+
+    // File the document is saved to (set by --open or the first save)
+    document_path: Option<PathBuf>,
+
+    // Crash recovery: periodic journal, and a crashed session awaiting an
+    // answer to the restore prompt (with the prompt's message)
+    autosave: Option<session::Autosave>,
+    recovery: Option<(String, Document)>,
+
+    // Input method support for text mode
+    ime: ImeState,
+}
+
+/// Input method (zwp_text_input_v3) state.
+///
+/// The text input is only enabled while in text mode, so input methods stay
+/// out of the way of drawing shortcuts.
+#[derive(Default)]
+struct ImeState {
+    /// Text input manager, if the compositor supports the protocol
+    manager: Option<ZwpTextInputManagerV3>,
+    /// Text input object for the keyboard seat
+    text_input: Option<ZwpTextInputV3>,
+    /// Whether the text input has entered our surface
+    focused: bool,
+    /// Whether we have enabled the text input
+    enabled: bool,
+    /// Caret rectangle last sent to the compositor
+    cursor_rect: Option<(i32, i32, i32, i32)>,
+    /// Preedit string and cursor received since the last `done` event
+    pending_preedit: Option<(String, Option<usize>)>,
+    /// Commit string received since the last `done` event
+    pending_commit: Option<String>,
 }
 
 impl WaylandBackend {
@@ -87,10 +164,43 @@ impl WaylandBackend {
             .context("Failed to create Tokio runtime for capture operations")?;
         Ok(Self {
             initial_mode,
+            document: None,
+            exports_on_exit: Vec::new(),
+            exported: Vec::new(),
+            control: Arc::new(
+                OverlayControl::new().context("Failed to create overlay control socket")?,
+            ),
             tokio_runtime,
         })
     }
 
+    /// Acts on requests made through `control` while the overlay runs.
+    ///
+    /// Requests made before the overlay starts are ignored.
+    pub fn with_control(mut self, control: Arc<OverlayControl>) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// Opens `document` (loaded from `path`) when the overlay starts.
+    ///
+    /// Saving overwrites `path` until a new file is saved.
+    pub fn with_document(mut self, path: PathBuf, document: Document) -> Self {
+        self.document = Some((path, document));
+        self
+    }
+
+    /// Exports the active board in each format when the overlay closes.
+    pub fn with_exports_on_exit(mut self, formats: Vec<ExportFormat>) -> Self {
+        self.exports_on_exit = formats;
+        self
+    }
+
+    /// Takes the paths of files written by the exports on exit.
+    pub fn take_exported(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.exported)
+    }
+
     pub fn run(&mut self) -> Result<()> {
         info!("Starting Wayland backend");
 
@@ -122,6 +232,16 @@ impl WaylandBackend {
         let seat_state = SeatState::new(&globals, &qh);
         debug!("Initialized seat state");
 
+        // Input methods are optional; text mode falls back to plain key events
+        let text_input_manager = globals
+            .bind::<ZwpTextInputManagerV3, _, _>(&qh, 1..=1, ())
+            .ok();
+        if text_input_manager.is_some() {
+            debug!("Bound text input manager");
+        } else {
+            info!("zwp_text_input_manager_v3 not available; input method support disabled");
+        }
+
         let registry_state = RegistryState::new(&globals);
 
         // Load configuration
@@ -166,7 +286,6 @@ impl WaylandBackend {
 
         // Build keybinding action map
         let action_map = config
-            .keybindings
             .build_action_map()
             .expect("Failed to build keybinding action map");
 
@@ -176,12 +295,24 @@ impl WaylandBackend {
             config.drawing.default_thickness,
             config.drawing.default_font_size,
             font_descriptor,
-            config.drawing.text_background_enabled,
+            config.drawing.text_effect(),
+            TextFormat {
+                align: config.drawing.text_align,
+                wrap_width: config.drawing.text_wrap_width,
+                line_spacing: config.drawing.text_line_spacing,
+            },
             config.arrow.length,
             config.arrow.angle_degrees,
             config.board.clone(),
+            config.palette.clone(),
             action_map,
         );
+        input_state
+            .canvas_set
+            .set_history_limit(config.drawing.undo_history);
+        input_state
+            .canvas_set
+            .set_layer_names(&config.drawing.layers);
 
         // Apply initial mode from CLI (if provided) or config default (only if board modes enabled)
         if config.board.enabled {
@@ -190,16 +321,16 @@ impl WaylandBackend {
                 .clone()
                 .unwrap_or_else(|| config.board.default_mode.clone());
 
-            if let Ok(mode) = initial_mode_str.parse::<crate::input::BoardMode>() {
-                if mode != crate::input::BoardMode::Transparent {
-                    info!("Starting in {} mode", initial_mode_str);
-                    input_state.canvas_set.switch_mode(mode);
+            if let Some(mode) = BoardMode::from_name(&initial_mode_str, &config.board) {
+                if mode != BoardMode::Transparent {
+                    info!("Starting in {} mode", mode);
                     // Apply auto-color adjustment if enabled
                     if config.board.auto_adjust_pen
                         && let Some(default_color) = mode.default_pen_color(&config.board)
                     {
                         input_state.current_color = default_color;
                     }
+                    input_state.canvas_set.switch_mode(mode);
                 }
             } else if !initial_mode_str.is_empty() {
                 warn!(
@@ -211,6 +342,60 @@ impl WaylandBackend {
             warn!("Board modes disabled in config, ignoring --mode flag");
         }
 
+        // Open the requested document, or bring back what was drawn before
+        // the overlay last closed
+        let restore_mode = config.board.enabled && self.initial_mode.is_none();
+        let document_path = if let Some((path, opened)) = self.document.take() {
+            info!("Opening document {}", path.display());
+            opened.restore(&mut input_state, restore_mode);
+            Some(path)
+        } else {
+            None
+        };
+        if document_path.is_none()
+            && config.session.persist
+            && let Some(path) = session::session_path()
+        {
+            match session::load(&path) {
+                Ok(Some(saved)) => {
+                    info!("Restoring session from {}", path.display());
+                    saved.restore(&mut input_state, restore_mode);
+                }
+                Ok(None) => debug!("No saved session at {}", path.display()),
+                Err(e) => warn!("Failed to restore session: {:#}", e),
+            }
+        }
+
+        // A leftover autosave journal means the last session crashed
+        let journal_path = if config.session.autosave {
+            session::journal_path()
+        } else {
+            None
+        };
+        let recovery = journal_path
+            .as_deref()
+            .and_then(|path| match session::load(path) {
+                Ok(Some(journal)) if journal.shape_count() > 0 => {
+                    info!("Found unsaved session in {}", path.display());
+                    Some((recovery_message(path, &journal), journal))
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    warn!("Failed to read autosave journal: {:#}", e);
+                    None
+                }
+            });
+        if recovery.is_some() {
+            input_state.show_recovery_prompt();
+        }
+        let autosave = journal_path.map(|path| {
+            let interval = Duration::from_secs(config.session.autosave_interval_secs);
+            session::Autosave::new(path, interval, &input_state, Instant::now())
+        });
+
+        // Decode background images now rather than on the first frame
+        let background_images = BackgroundImages::preload(&config.board);
+
         // Create capture manager with runtime handle
         let capture_manager = CaptureManager::new(self.tokio_runtime.handle());
         info!("Capture manager initialized");
@@ -231,6 +416,12 @@ impl WaylandBackend {
             width: 0,
             height: 0,
             configured: false,
+            click_through: false,
+            shape_cache: None,
+            rendered_board: None,
+            peek_rendered: false,
+            background_images,
+            overlays: Vec::new(),
             frame_callback_pending: false,
             config,
             input_state,
@@ -239,7 +430,16 @@ impl WaylandBackend {
             capture_manager,
             capture_in_progress: false,
             overlay_hidden_for_capture: false,
+            clipboard_read: None,
+            control: self.control.clone(),
             tokio_handle,
+            document_path,
+            autosave,
+            recovery,
+            ime: ImeState {
+                manager: text_input_manager,
+                ..ImeState::default()
+            },
         };
 
         // Create layer shell surface
@@ -272,6 +472,9 @@ impl WaylandBackend {
         let mut consecutive_render_failures = 0u32;
         const MAX_RENDER_FAILURES: u32 = 10;
 
+        // Requests made while the overlay was hidden are stale
+        self.control.clear();
+
         // Main event loop
         let mut loop_error: Option<anyhow::Error> = None;
         loop {
@@ -281,8 +484,10 @@ impl WaylandBackend {
                 break;
             }
 
-            // Dispatch all pending events (blocking) but check should_exit after each batch
-            match event_queue.blocking_dispatch(&mut state) {
+            // Dispatch all pending events, also waking up for requests from
+            // other threads or a due autosave, and check should_exit after each batch
+            let timeout = state.autosave_due_in();
+            match dispatch_with_timeout(&mut event_queue, &mut state, &self.control, timeout) {
                 Ok(_) => {
                     // Check immediately after dispatch returns
                     if state.input_state.should_exit {
@@ -297,6 +502,10 @@ impl WaylandBackend {
                 }
             }
 
+            if self.control.take_click_through_toggle() {
+                state.set_click_through(!state.click_through);
+            }
+
             // Check for completed capture operations
             if state.capture_in_progress
                 && let Some(outcome) = state.capture_manager.try_take_result()
@@ -352,6 +561,15 @@ impl WaylandBackend {
                 }
             }
 
+            // Paste shapes once the system clipboard has been read
+            state.finish_clipboard_read();
+
+            // Keep the input method in step with text mode
+            state.sync_text_input();
+
+            // Journal the session in case we crash
+            state.autosave();
+
             // Render if configured and needs redraw, but only if no frame callback pending
             // This throttles rendering to display refresh rate (when vsync is enabled)
             let can_render = state.configured
@@ -406,6 +624,34 @@ impl WaylandBackend {
 
         info!("Wayland backend exiting");
 
+        if state.config.session.persist
+            && let Some(path) = session::session_path()
+        {
+            match session::save(&path, &Document::capture(&state.input_state, true)) {
+                Ok(()) => info!("Session saved to {}", path.display()),
+                Err(e) => warn!("Failed to save session: {:#}", e),
+            }
+        }
+
+        // Closing normally makes the journal obsolete, unless the crashed
+        // session it holds was never restored or discarded
+        if loop_error.is_none()
+            && state.recovery.is_none()
+            && let Some(autosave) = &state.autosave
+            && let Err(e) = autosave.remove_journal()
+        {
+            warn!("Failed to remove autosave journal: {:#}", e);
+        }
+
+        if loop_error.is_none() {
+            for &format in &self.exports_on_exit {
+                let path = state
+                    .export_boards(format)
+                    .with_context(|| format!("Failed to export board as {}", format))?;
+                self.exported.push(path);
+            }
+        }
+
         // Return error if loop exited due to error, otherwise success
         match loop_error {
             Some(e) => Err(e),
@@ -414,14 +660,144 @@ impl WaylandBackend {
     }
 }
 
+/// Converts a wait into a poll timeout, rounding up so the loop doesn't wake
+/// just before the wait is over.
+fn poll_timeout(wait: Option<Duration>) -> PollTimeout {
+    wait.map_or(PollTimeout::NONE, |wait| {
+        PollTimeout::try_from(wait.as_nanos().div_ceil(1_000_000)).unwrap_or(PollTimeout::MAX)
+    })
+}
+
+/// Dispatches pending Wayland events, sleeping until some arrive, `control`
+/// is woken or `timeout` (if any) expires.
+///
+/// Works like `EventQueue::blocking_dispatch`, but also returns when another
+/// thread wakes the overlay or a timer is due, so the event loop can act on it.
+fn dispatch_with_timeout(
+    event_queue: &mut EventQueue<WaylandState>,
+    state: &mut WaylandState,
+    control: &OverlayControl,
+    timeout: Option<Duration>,
+) -> Result<usize> {
+    let dispatched = event_queue.dispatch_pending(state)?;
+    if dispatched > 0 {
+        return Ok(dispatched);
+    }
+
+    event_queue.flush()?;
+
+    if let Some(guard) = event_queue.prepare_read() {
+        let (ready, woken) = {
+            let mut fds = [
+                PollFd::new(
+                    guard.connection_fd(),
+                    PollFlags::POLLIN | PollFlags::POLLERR,
+                ),
+                PollFd::new(control.wake_fd(), PollFlags::POLLIN),
+            ];
+            match poll(&mut fds, poll_timeout(timeout)) {
+                Ok(_) => (fds[0].any().unwrap_or(false), fds[1].any().unwrap_or(false)),
+                Err(Errno::EINTR) => (false, false),
+                Err(e) => return Err(e.into()),
+            }
+        };
+        if woken {
+            control.drain_wakeups();
+        }
+        // Dropping the guard without reading cancels the read
+        if ready {
+            match guard.read() {
+                Ok(_) => {}
+                Err(WaylandError::Io(e)) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    Ok(event_queue.dispatch_pending(state)?)
+}
+
 impl WaylandState {
+    /// Enables the input method while in text mode and keeps its caret rectangle current.
+    fn sync_text_input(&mut self) {
+        let Some(text_input) = self.ime.text_input.as_ref() else {
+            return;
+        };
+        if !self.ime.focused {
+            return;
+        }
+
+        let caret_rect = match (&self.input_state.state, self.input_state.text_input_shape()) {
+            (
+                crate::input::DrawingState::TextInput { buffer, .. },
+                Some(crate::draw::Shape::Text {
+                    x,
+                    y,
+                    size,
+                    font_descriptor,
+                    format,
+                    ..
+                }),
+            ) => crate::draw::text::text_caret_rect(
+                x,
+                y,
+                &buffer.display_text(),
+                buffer.display_caret(),
+                size,
+                &font_descriptor,
+                &format,
+            )
+            .map(|rect| {
+                (
+                    rect.x as i32,
+                    rect.y as i32,
+                    rect.width.ceil() as i32,
+                    rect.height.ceil() as i32,
+                )
+            }),
+            _ => None,
+        };
+
+        if matches!(
+            self.input_state.state,
+            crate::input::DrawingState::TextInput { .. }
+        ) {
+            let mut changed = false;
+            if !self.ime.enabled {
+                text_input.enable();
+                text_input.set_content_type(ContentHint::Multiline, ContentPurpose::Normal);
+                self.ime.enabled = true;
+                self.ime.cursor_rect = None;
+                changed = true;
+                debug!("Enabled input method for text mode");
+            }
+            if let Some(rect) = caret_rect
+                && self.ime.cursor_rect != Some(rect)
+            {
+                text_input.set_cursor_rectangle(rect.0, rect.1, rect.2, rect.3);
+                self.ime.cursor_rect = Some(rect);
+                changed = true;
+            }
+            if changed {
+                text_input.commit();
+            }
+        } else if self.ime.enabled {
+            text_input.disable();
+            text_input.commit();
+            self.ime.enabled = false;
+            self.ime.cursor_rect = None;
+            debug!("Disabled input method");
+        }
+    }
+
     fn render(&mut self, _qh: &QueueHandle<Self>) -> Result<()> {
         debug!("=== RENDER START ===");
-        let layer_surface = self
+        let wl_surface = self
             .layer_surface
             .as_ref()
-            .context("Layer surface not created")?;
-        let wl_surface = layer_surface.wl_surface();
+            .context("Layer surface not created")?
+            .wl_surface()
+            .clone();
 
         // Create pool if needed
         if self.pool.is_none() {
@@ -455,14 +831,46 @@ impl WaylandState {
             .context("Failed to create buffer")?;
         debug!("Buffer acquired from pool");
 
+        // Bring the cached image of committed shapes up to date
+        let board_mode = self.input_state.board_mode();
+        if self.shape_cache.as_ref().is_none_or(|cache| {
+            cache.width() != self.width as i32 || cache.height() != self.height as i32
+        }) {
+            let cache = ShapeCache::new(self.width as i32, self.height as i32)
+                .context("Failed to create shape cache")?;
+            self.shape_cache = Some(cache);
+            self.rendered_board = None;
+        }
+        let shape_cache = self
+            .shape_cache
+            .as_mut()
+            .context("Shape cache not initialized")?;
+        if self.rendered_board.as_ref() != Some(board_mode) {
+            // Another board has its own background and shapes
+            self.rendered_board = Some(board_mode.clone());
+            shape_cache.set_background(
+                self.background_images
+                    .background(board_mode, &self.input_state.board_config),
+            );
+            shape_cache.invalidate();
+        }
+        let shape_damage = shape_cache
+            .update(self.input_state.canvas_set.active_frame())
+            .context("Failed to update shape cache")?;
+        debug!(
+            "Shape cache updated ({:?}, {} shapes redrawn)",
+            shape_damage,
+            shape_cache.shapes_rendered()
+        );
+
         // Create Cairo surface from the buffer
         // SAFETY: This unsafe block creates a Cairo surface from raw memory buffer.
         // Safety invariants that must be maintained:
         // 1. `canvas` is a valid mutable slice from SlotPool with exactly (width * height * 4) bytes
         // 2. The buffer format ARgb32 matches the allocation (4 bytes per pixel: alpha, red, green, blue)
         // 3. The stride (width * 4) correctly represents the number of bytes per row
-        // 4. `cairo_surface` and `ctx` are explicitly dropped (lines 315-316) before the buffer
-        //    is committed to Wayland, ensuring Cairo doesn't access memory after ownership transfers
+        // 4. `cairo_surface` and `ctx` are explicitly dropped before the buffer is committed
+        //    to Wayland, ensuring Cairo doesn't access memory after ownership transfers
         // 5. No other references to this memory exist during Cairo's usage
         // 6. The buffer remains valid throughout Cairo's usage (enforced by Rust's borrow checker
         //    since `canvas` is borrowed until buffer.damage_buffer() call)
@@ -480,102 +888,253 @@ impl WaylandState {
         // Render using Cairo
         let ctx = cairo::Context::new(&cairo_surface).context("Failed to create Cairo context")?;
 
-        // Clear with fully transparent background
-        debug!("Clearing background");
-        ctx.set_operator(cairo::Operator::Clear);
-        ctx.paint().context("Failed to clear background")?;
-        ctx.set_operator(cairo::Operator::Over);
-
-        // Render board background if in board mode (whiteboard/blackboard)
-        crate::draw::render_board_background(
-            &ctx,
-            self.input_state.board_mode(),
-            &self.input_state.board_config,
-        );
+        // Everything but the cached shapes is drawn on top each frame; remember
+        // where, so the compositor also refreshes those areas once they move or
+        // disappear
+        let previous_overlays = std::mem::take(&mut self.overlays);
 
-        // Render all completed shapes from active frame
-        debug!(
-            "Rendering {} completed shapes",
-            self.input_state.canvas_set.active_frame().shapes.len()
-        );
-        crate::draw::render_shapes(&ctx, &self.input_state.canvas_set.active_frame().shapes);
+        let peeking = self.input_state.is_peeking();
+        if peeking {
+            // Peeking shows the bare screen: no board, shapes or UI
+            ctx.set_operator(cairo::Operator::Clear);
+            ctx.paint().context("Failed to clear buffer")?;
+        } else {
+            // Start from the cached board background and committed shapes
+            debug!("Copying shape cache");
+            shape_cache
+                .paint(&ctx)
+                .context("Failed to paint shape cache")?;
+            self.render_overlays(&ctx);
+        }
+
+        // Flush Cairo
+        debug!("Flushing Cairo surface");
+        cairo_surface.flush();
+        drop(ctx);
+        drop(cairo_surface);
 
+        // Attach buffer and damage only what changed
+        debug!("Attaching buffer and committing surface");
+        wl_surface.attach(Some(buffer.wl_buffer()), 0, 0);
+        // Starting or stopping a peek changes the whole screen
+        let damage = if std::mem::replace(&mut self.peek_rendered, peeking) != peeking {
+            Damage::Full
+        } else {
+            shape_damage
+                .with_overlays(&previous_overlays, self.width, self.height)
+                .with_overlays(&self.overlays, self.width, self.height)
+        };
+        match damage {
+            Damage::Full => {
+                wl_surface.damage_buffer(0, 0, self.width as i32, self.height as i32);
+            }
+            Damage::Regions(regions) => {
+                for region in regions {
+                    wl_surface.damage_buffer(
+                        region.x as i32,
+                        region.y as i32,
+                        region.width as i32,
+                        region.height as i32,
+                    );
+                }
+            }
+        }
+
+        // Only request frame callback if vsync is enabled
+        // This throttles rendering to display refresh rate
+        if self.config.performance.enable_vsync {
+            debug!("Requesting frame callback (vsync enabled)");
+            wl_surface.frame(_qh, wl_surface.clone());
+        } else {
+            debug!("Skipping frame callback (vsync disabled - allows back-to-back renders)");
+        }
+
+        wl_surface.commit();
+        debug!("=== RENDER COMPLETE ===");
+
+        Ok(())
+    }
+
+    /// Draws everything shown over the cached shapes: the shape being drawn,
+    /// selection, text input, status bar, help and recovery prompt.
+    ///
+    /// The area of each is added to `self.overlays`.
+    fn render_overlays(&mut self, ctx: &cairo::Context) {
         // Render provisional shape if actively drawing
         // Use optimized method that avoids cloning for freehand
+        if let Some(bounds) = self
+            .input_state
+            .provisional_bounds(self.current_mouse_x, self.current_mouse_y)
+        {
+            self.overlays.push(bounds);
+        }
         if self.input_state.render_provisional_shape(
-            &ctx,
+            ctx,
             self.current_mouse_x,
             self.current_mouse_y,
         ) {
             debug!("Rendered provisional shape");
         }
 
-        // Render text cursor/buffer if in text mode
-        if let crate::input::DrawingState::TextInput { x, y, buffer } = &self.input_state.state {
-            let preview_text = if buffer.is_empty() {
-                "_".to_string() // Show cursor when buffer is empty
-            } else {
-                // Show buffer with cursor at end (handles newlines naturally)
-                format!("{}_", buffer)
-            };
-            crate::draw::render_text(
-                &ctx,
-                *x,
-                *y,
-                &preview_text,
-                self.input_state.current_color,
-                self.input_state.current_font_size,
-                &self.input_state.font_descriptor,
-                self.input_state.text_background_enabled,
-            );
+        // Render selection outline and rubber band when using the selection tool
+        if let Some(bounds) = self.input_state.selection_bounds() {
+            crate::draw::render_selection_handles(ctx, &bounds);
+            self.overlays.push(SelectionHandle::extent(&bounds));
+        }
+        if let Some(region) = self
+            .input_state
+            .selection_region(self.current_mouse_x, self.current_mouse_y)
+        {
+            crate::draw::render_selection_region(ctx, &region);
+            self.overlays.push(region);
+        }
+
+        // Render text buffer and caret if in text mode
+        if let crate::input::DrawingState::TextInput { buffer, .. } = &self.input_state.state
+            && let Some(mut shape) = self.input_state.text_input_shape()
+        {
+            // Show any input method preedit inline at the caret
+            if let crate::draw::Shape::Text { text, .. } = &mut shape {
+                *text = buffer.display_text().into_owned();
+            }
+            if let crate::draw::Shape::Text {
+                x,
+                y,
+                text,
+                color,
+                size,
+                font_descriptor,
+                format,
+                rotation,
+                ..
+            } = &shape
+            {
+                if !text.is_empty() {
+                    crate::draw::render_shape(ctx, &shape);
+                }
+                let preedit = buffer.preedit_range();
+                // Draw the caret in the text's rotated frame
+                ctx.save().ok();
+                ctx.translate(*x as f64, *y as f64);
+                ctx.rotate(*rotation);
+                ctx.translate(-*x as f64, -*y as f64);
+                crate::draw::render_text_caret(
+                    ctx,
+                    *x,
+                    *y,
+                    text,
+                    buffer.display_caret(),
+                    if preedit.is_some() {
+                        None
+                    } else {
+                        buffer.selection()
+                    },
+                    preedit,
+                    *color,
+                    *size,
+                    font_descriptor,
+                    format,
+                );
+                ctx.restore().ok();
+
+                // The caret of empty text sits around the anchor point
+                let anchor = BoundingBox::from_corners(*x as f64, *y as f64, *x as f64, *y as f64)
+                    .inflate(*size);
+                self.overlays.push(
+                    shape_damage_bounds(&shape).map_or(anchor, |bounds| bounds.union(&anchor)),
+                );
+            }
         }
 
         // Render status bar if enabled
-        if self.config.ui.show_status_bar {
-            crate::ui::render_status_bar(
-                &ctx,
+        if self.config.ui.show_status_bar
+            && let Some(bounds) = crate::ui::render_status_bar(
+                ctx,
                 &self.input_state,
                 self.config.ui.status_bar_position,
                 &self.config.ui.status_bar_style,
                 self.width,
                 self.height,
-            );
+            )
+        {
+            self.overlays.push(bounds);
         }
 
         // Render help overlay if toggled
         if self.input_state.show_help {
             crate::ui::render_help_overlay(
-                &ctx,
+                ctx,
                 &self.config.ui.help_overlay_style,
                 self.width,
                 self.height,
             );
+            self.overlays.push(BoundingBox::from_corners(
+                0.0,
+                0.0,
+                self.width as f64,
+                self.height as f64,
+            ));
         }
 
-        // Flush Cairo
-        debug!("Flushing Cairo surface");
-        cairo_surface.flush();
-        drop(ctx);
-        drop(cairo_surface);
+        // Render the crash recovery prompt over everything else
+        if let Some((message, _)) = &self.recovery
+            && self.input_state.recovery_prompt_visible()
+        {
+            crate::ui::render_recovery_prompt(
+                ctx,
+                &self.config.ui.help_overlay_style,
+                message,
+                self.width,
+                self.height,
+            );
+            self.overlays.push(BoundingBox::from_corners(
+                0.0,
+                0.0,
+                self.width as f64,
+                self.height as f64,
+            ));
+        }
+    }
 
-        // Attach buffer and commit
-        debug!("Attaching buffer and committing surface");
-        wl_surface.attach(Some(buffer.wl_buffer()), 0, 0);
-        wl_surface.damage_buffer(0, 0, self.width as i32, self.height as i32);
+    /// Turns click-through mode on or off.
+    ///
+    /// In click-through mode the surface has an empty input region and no
+    /// keyboard focus, so pointer and keyboard input go to the windows below
+    /// while the annotations stay on screen.
+    fn set_click_through(&mut self, enabled: bool) {
+        if self.click_through == enabled {
+            return;
+        }
+        let Some(layer_surface) = &self.layer_surface else {
+            return;
+        };
 
-        // Only request frame callback if vsync is enabled
-        // This throttles rendering to display refresh rate
-        if self.config.performance.enable_vsync {
-            debug!("Requesting frame callback (vsync enabled)");
-            wl_surface.frame(_qh, wl_surface.clone());
+        if enabled {
+            // The region can go right away; the surface keeps its own copy
+            let region = match Region::new(&self.compositor_state) {
+                Ok(region) => region,
+                Err(e) => {
+                    warn!("Cannot enable click-through: {}", e);
+                    return;
+                }
+            };
+            layer_surface
+                .wl_surface()
+                .set_input_region(Some(region.wl_region()));
+            layer_surface.set_keyboard_interactivity(KeyboardInteractivity::None);
         } else {
-            debug!("Skipping frame callback (vsync disabled - allows back-to-back renders)");
+            // No input region means the whole surface takes input again
+            layer_surface.wl_surface().set_input_region(None);
+            layer_surface.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
         }
+        layer_surface.commit();
 
-        wl_surface.commit();
-        debug!("=== RENDER COMPLETE ===");
-
-        Ok(())
+        info!(
+            "Click-through {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        self.click_through = enabled;
+        self.input_state.set_click_through(enabled);
     }
 
     /// Temporarily hide the overlay for screenshot capture.
@@ -625,14 +1184,261 @@ impl WaylandState {
 
         self.overlay_hidden_for_capture = false;
 
-        // Force a redraw to show the overlay again
+        // Force a full redraw to show the overlay again
+        if let Some(cache) = &mut self.shape_cache {
+            cache.invalidate();
+        }
         self.input_state.needs_redraw = true;
     }
 
+    /// Moves copied shapes to and from the system clipboard.
+    ///
+    /// Copied shapes are written in the background. A paste with an empty
+    /// internal clipboard reads shapes from the system clipboard, which lets
+    /// shapes travel between wayscriber sessions.
+    fn handle_clipboard_requests(&mut self) {
+        if let Some(shapes) = self.input_state.take_pending_clipboard_export() {
+            match serialize_shapes(&shapes) {
+                Ok(data) => {
+                    std::thread::spawn(move || {
+                        if let Err(err) = crate::capture::clipboard::copy_data_to_clipboard(
+                            &data,
+                            SHAPES_MIME_TYPE,
+                        ) {
+                            log::warn!("Failed to copy shapes to the system clipboard: {}", err);
+                        }
+                    });
+                }
+                Err(err) => log::warn!("Failed to serialize copied shapes: {}", err),
+            }
+        }
+
+        if self.input_state.take_pending_clipboard_import() {
+            // Read on a helper thread so a stuck clipboard owner can't freeze
+            // the overlay; the shapes are pasted once the read finishes
+            let (tx, rx) = mpsc::channel();
+            let control = self.control.clone();
+            std::thread::spawn(move || {
+                let _ = tx.send(crate::capture::clipboard::paste_from_clipboard(
+                    SHAPES_MIME_TYPE,
+                ));
+                control.wake();
+            });
+            if self.clipboard_read.replace(rx).is_some() {
+                log::debug!("Abandoning the previous clipboard read for a new paste");
+            }
+        }
+    }
+
+    /// Pastes the shapes read from the system clipboard, if the read finished.
+    fn finish_clipboard_read(&mut self) {
+        let Some(rx) = &self.clipboard_read else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.clipboard_read = None;
+                return;
+            }
+        };
+        self.clipboard_read = None;
+
+        match result {
+            Ok(data) => match deserialize_shapes(&data) {
+                Ok(shapes) => self.input_state.paste_shapes(shapes),
+                Err(err) => log::warn!("Clipboard does not hold valid shapes: {}", err),
+            },
+            Err(err) => log::debug!("No shapes on the system clipboard: {}", err),
+        }
+    }
+
+    /// Exports the session in the given format to the capture directory.
+    ///
+    /// SVG holds the active board; PDF holds every board with shapes, one
+    /// per page. Files are named with the capture filename template, so
+    /// exports sit next to screenshots.
+    fn export_boards(&mut self, format: ExportFormat) -> Result<PathBuf> {
+        use crate::capture::file::{FileSaveConfig, save_screenshot};
+        use crate::util::expand_tilde;
+
+        let data = match format {
+            ExportFormat::Svg => {
+                let frame = self.input_state.canvas_set.active_frame();
+                let background = self.background_images.background(
+                    self.input_state.board_mode(),
+                    &self.input_state.board_config,
+                );
+                crate::export::svg::frame_to_svg(frame, &background, self.width, self.height)
+                    .into_bytes()
+            }
+            ExportFormat::Pdf => crate::export::pdf::canvas_to_pdf(
+                &self.input_state.canvas_set,
+                &self.input_state.board_config,
+                &mut self.background_images,
+                self.width,
+                self.height,
+            )
+            .context("Failed to render PDF")?,
+        };
+
+        let save_config = FileSaveConfig {
+            save_directory: expand_tilde(&self.config.capture.save_directory),
+            filename_template: self.config.capture.filename_template.clone(),
+            format: format.extension().to_string(),
+        };
+        Ok(save_screenshot(&data, &save_config)?)
+    }
+
+    /// Writes the autosave journal if it is due.
+    fn autosave(&mut self) {
+        // The journal still holds the crashed session until the user decides
+        if self.recovery.is_some() {
+            return;
+        }
+        let Some(autosave) = &mut self.autosave else {
+            return;
+        };
+        // Every edit asks for a redraw
+        if self.input_state.needs_redraw {
+            autosave.note_change();
+        }
+        match autosave.tick(&self.input_state, Instant::now()) {
+            Ok(true) => debug!("Autosaved session to {}", autosave.path().display()),
+            Ok(false) => {}
+            Err(e) => warn!("Autosave failed: {:#}", e),
+        }
+    }
+
+    /// Returns how long the event loop may sleep before the autosave journal
+    /// is due, or `None` if nothing needs journaling.
+    fn autosave_due_in(&self) -> Option<Duration> {
+        if self.recovery.is_some() {
+            return None;
+        }
+        self.autosave.as_ref()?.next_check_in(Instant::now())
+    }
+
+    /// Restores or discards the crashed session once the prompt is answered.
+    fn handle_recovery_answer(&mut self) {
+        let Some(restore) = self.input_state.take_recovery_answer() else {
+            return;
+        };
+        let Some((_, journal)) = self.recovery.take() else {
+            return;
+        };
+
+        if restore {
+            info!("Restoring unsaved session");
+            journal.restore(&mut self.input_state, self.config.board.enabled);
+            if let Some(cache) = &mut self.shape_cache {
+                cache.invalidate();
+            }
+        } else if let Some(autosave) = &self.autosave {
+            info!("Discarding unsaved session");
+            if let Err(e) = autosave.remove_journal() {
+                warn!("Failed to remove autosave journal: {:#}", e);
+            }
+        }
+        self.input_state.needs_redraw = true;
+    }
+
+    /// Saves the boards as a document.
+    ///
+    /// New documents are named with the capture filename template and saved
+    /// to the capture directory, next to screenshots and exports.
+    fn save_document(&mut self, mode: SaveMode) -> Result<PathBuf> {
+        use crate::capture::file::{FileSaveConfig, save_screenshot};
+        use crate::util::expand_tilde;
+
+        let saved = Document::capture(&self.input_state, false);
+        if mode == SaveMode::Current
+            && let Some(path) = &self.document_path
+        {
+            document::save(path, &saved)?;
+            return Ok(path.clone());
+        }
+
+        let save_config = FileSaveConfig {
+            save_directory: expand_tilde(&self.config.capture.save_directory),
+            filename_template: self.config.capture.filename_template.clone(),
+            format: document::DOCUMENT_EXTENSION.to_string(),
+        };
+        let path = save_screenshot(&saved.to_json()?, &save_config)?;
+        self.document_path = Some(path.clone());
+        Ok(path)
+    }
+
+    /// Saves the document if a save action was triggered.
+    fn handle_document_save_request(&mut self) {
+        let Some(mode) = self.input_state.take_pending_document_save() else {
+            return;
+        };
+
+        match self.save_document(mode) {
+            Ok(path) => {
+                log::info!("Document saved to: {}", path.display());
+                let filename = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                crate::notification::send_notification_async(
+                    &self.tokio_handle,
+                    "Document Saved".to_string(),
+                    format!("Saved as {}", filename),
+                    Some("document-save".to_string()),
+                );
+            }
+            Err(e) => {
+                log::warn!("Document save failed: {:#}", e);
+                crate::notification::send_notification_async(
+                    &self.tokio_handle,
+                    "Save Failed".to_string(),
+                    format!("{:#}", e),
+                    Some("dialog-error".to_string()),
+                );
+            }
+        }
+    }
+
+    /// Exports the active board if an export action was triggered.
+    fn handle_export_request(&mut self) {
+        let Some(format) = self.input_state.take_pending_export() else {
+            return;
+        };
+
+        match self.export_boards(format) {
+            Ok(path) => {
+                log::info!("Board exported to: {}", path.display());
+                let filename = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                crate::notification::send_notification_async(
+                    &self.tokio_handle,
+                    "Board Exported".to_string(),
+                    format!("Saved as {}", filename),
+                    Some("document-save".to_string()),
+                );
+            }
+            Err(e) => {
+                log::warn!("Board export failed: {:#}", e);
+                crate::notification::send_notification_async(
+                    &self.tokio_handle,
+                    "Export Failed".to_string(),
+                    format!("{:#}", e),
+                    Some("dialog-error".to_string()),
+                );
+            }
+        }
+    }
+
     /// Handles capture actions by delegating to the CaptureManager.
     fn handle_capture_action(&mut self, action: Action) {
-        use crate::capture::file::{FileSaveConfig, expand_tilde};
+        use crate::capture::file::FileSaveConfig;
         use crate::capture::types::CaptureType;
+        use crate::util::expand_tilde;
 
         if !self.config.capture.enabled {
             log::warn!("Capture action triggered but capture is disabled in config");
@@ -945,6 +1751,13 @@ impl SeatHandler for WaylandState {
             if self.seat_state.get_keyboard(qh, &seat, None).is_ok() {
                 debug!("Keyboard initialized");
             }
+
+            if let Some(manager) = &self.ime.manager
+                && self.ime.text_input.is_none()
+            {
+                self.ime.text_input = Some(manager.get_text_input(&seat, qh, ()));
+                debug!("Text input initialized");
+            }
         }
 
         if capability == Capability::Pointer {
@@ -1009,15 +1822,28 @@ impl KeyboardHandler for WaylandState {
         _serial: u32,
         event: KeyEvent,
     ) {
-        let key = keysym_to_key(event.keysym);
+        let key = keysym_to_key(event.keysym, event.utf8.as_deref());
         debug!("Key pressed: {:?}", key);
-        self.input_state.on_key_press(key);
+        self.input_state
+            .on_key_press_with_text(key, event.utf8.as_deref());
         self.input_state.needs_redraw = true;
 
         // Check for pending capture actions
         if let Some(action) = self.input_state.take_pending_capture_action() {
             self.handle_capture_action(action);
         }
+
+        // Check for shapes to exchange with the system clipboard
+        self.handle_clipboard_requests();
+
+        // Check for a requested board export
+        self.handle_export_request();
+
+        // Check for an answer to the recovery prompt
+        self.handle_recovery_answer();
+
+        // Check for a requested document save
+        self.handle_document_save_request();
     }
 
     fn release_key(
@@ -1028,7 +1854,7 @@ impl KeyboardHandler for WaylandState {
         _serial: u32,
         event: KeyEvent,
     ) {
-        let key = keysym_to_key(event.keysym);
+        let key = keysym_to_key(event.keysym, event.utf8.as_deref());
         debug!("Key released: {:?}", key);
         self.input_state.on_key_release(key);
     }
@@ -1058,9 +1884,10 @@ impl KeyboardHandler for WaylandState {
         event: KeyEvent,
     ) {
         // Handle key repeat - treat like a regular key press
-        let key = keysym_to_key(event.keysym);
+        let key = keysym_to_key(event.keysym, event.utf8.as_deref());
         debug!("Key repeated: {:?}", key);
-        self.input_state.on_key_press(key);
+        self.input_state
+            .on_key_press_with_text(key, event.utf8.as_deref());
         self.input_state.needs_redraw = true;
     }
 }
@@ -1215,12 +2042,115 @@ impl Dispatch<wl_buffer::WlBuffer, ()> for WaylandState {
     }
 }
 
+// The text input manager has no events
+impl Dispatch<ZwpTextInputManagerV3, ()> for WaylandState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpTextInputManagerV3,
+        _event: <ZwpTextInputManagerV3 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+// Input method events: preedit and commit strings are applied together on `done`
+impl Dispatch<ZwpTextInputV3, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZwpTextInputV3,
+        event: zwp_text_input_v3::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwp_text_input_v3::Event::Enter { surface } => {
+                let ours = state
+                    .layer_surface
+                    .as_ref()
+                    .is_some_and(|layer| *layer.wl_surface() == surface);
+                if ours {
+                    debug!("Input method focus entered");
+                    state.ime.focused = true;
+                }
+            }
+            zwp_text_input_v3::Event::Leave { .. } => {
+                debug!("Input method focus left");
+                // Leaving implicitly disables the text input
+                state.ime.focused = false;
+                state.ime.enabled = false;
+                state.ime.cursor_rect = None;
+                state.input_state.on_ime_preedit("", None);
+            }
+            zwp_text_input_v3::Event::PreeditString {
+                text,
+                cursor_begin,
+                cursor_end: _,
+            } => {
+                let cursor = usize::try_from(cursor_begin).ok();
+                state.ime.pending_preedit = Some((text.unwrap_or_default(), cursor));
+            }
+            zwp_text_input_v3::Event::CommitString { text } => {
+                state.ime.pending_commit = text;
+            }
+            zwp_text_input_v3::Event::DeleteSurroundingText { .. } => {
+                // We never send surrounding text, so there is nothing to delete
+                debug!("Ignoring delete_surrounding_text from input method");
+            }
+            zwp_text_input_v3::Event::Done { .. } => {
+                if let Some(text) = state.ime.pending_commit.take() {
+                    state.input_state.on_ime_commit(&text);
+                }
+                // A batch without a preedit event means the preedit is now empty
+                let (preedit, cursor) = state.ime.pending_preedit.take().unwrap_or_default();
+                state.input_state.on_ime_preedit(&preedit, cursor);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Describes a crashed session for the recovery prompt.
+fn recovery_message(journal_path: &std::path::Path, journal: &Document) -> String {
+    let shapes = journal.shape_count();
+    let noun = if shapes == 1 { "shape" } else { "shapes" };
+    let saved_at = std::fs::metadata(journal_path)
+        .and_then(|metadata| metadata.modified())
+        .map(chrono::DateTime::<chrono::Local>::from);
+    match saved_at {
+        Ok(time) => format!(
+            "wayscriber closed unexpectedly. Last autosave: {}, {} {}.",
+            time.format("%H:%M"),
+            shapes,
+            noun
+        ),
+        Err(_) => format!(
+            "wayscriber closed unexpectedly. Last autosave: {} {}.",
+            shapes, noun
+        ),
+    }
+}
+
 // Convert Wayland keysym to our Key enum
-fn keysym_to_key(keysym: Keysym) -> Key {
+//
+// `utf8` is the text xkbcommon produced for the key, after compose sequences and
+// dead keys. It is used for characters that have no keysym mapping of their own.
+fn keysym_to_key(keysym: Keysym, utf8: Option<&str>) -> Key {
     match keysym {
         Keysym::Escape => Key::Escape,
         Keysym::Return => Key::Return,
         Keysym::BackSpace => Key::Backspace,
+        Keysym::Delete | Keysym::KP_Delete => Key::Delete,
+        Keysym::Left | Keysym::KP_Left => Key::Left,
+        Keysym::Right | Keysym::KP_Right => Key::Right,
+        Keysym::Up | Keysym::KP_Up => Key::Up,
+        Keysym::Down | Keysym::KP_Down => Key::Down,
+        Keysym::Home | Keysym::KP_Home => Key::Home,
+        Keysym::End | Keysym::KP_End => Key::End,
+        Keysym::Page_Up | Keysym::KP_Page_Up => Key::PageUp,
+        Keysym::Page_Down | Keysym::KP_Page_Down => Key::PageDown,
         Keysym::Tab => Key::Tab,
         Keysym::space => Key::Space,
         Keysym::Shift_L | Keysym::Shift_R => Key::Shift,
@@ -1257,9 +2187,14 @@ fn keysym_to_key(keysym: Keysym) -> Key {
             // Use the raw value to determine if it's ASCII printable
             let raw = keysym.raw();
             if (0x20..=0x7E).contains(&raw) {
-                Key::Char(raw as u8 as char)
-            } else {
-                Key::Unknown
+                return Key::Char(raw as u8 as char);
+            }
+
+            // Otherwise use the character the keyboard layout produced, if any
+            let mut chars = utf8.unwrap_or_default().chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if !c.is_control() => Key::Char(c),
+                _ => Key::Unknown,
             }
         }
     }
@@ -0,0 +1,7 @@
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    let schema = wayscriber::document::Document::json_schema();
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
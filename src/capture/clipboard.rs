@@ -1,8 +1,9 @@
-//! Clipboard integration for copying screenshots.
+//! Clipboard integration for copying screenshots and shapes.
 
 use super::types::CaptureError;
 use std::process::{Command, Stdio};
 use wl_clipboard_rs::copy::{MimeType, Options, Source};
+use wl_clipboard_rs::paste;
 
 /// Copy image data to the Wayland clipboard.
 ///
@@ -19,9 +20,15 @@ pub fn copy_to_clipboard(image_data: &[u8]) -> Result<(), CaptureError> {
         "Attempting to copy screenshot to clipboard ({} bytes)",
         image_data.len()
     );
+    copy_data_to_clipboard(image_data, "image/png")
+}
 
+/// Copy data of the given MIME type to the Wayland clipboard.
+///
+/// Uses the wl-copy command if available, falling back to wl-clipboard-rs.
+pub fn copy_data_to_clipboard(data: &[u8], mime_type: &str) -> Result<(), CaptureError> {
     // Prefer wl-copy CLI (provided by wl-clipboard package); fall back to library if unavailable.
-    match copy_via_command(image_data) {
+    match copy_via_command(data, mime_type) {
         Ok(()) => {
             log::info!("Successfully copied to clipboard via wl-copy command");
             Ok(())
@@ -31,7 +38,7 @@ pub fn copy_to_clipboard(image_data: &[u8]) -> Result<(), CaptureError> {
                 "wl-copy command path failed ({}). Falling back to wl-clipboard-rs",
                 cmd_err
             );
-            match copy_via_library(image_data) {
+            match copy_via_library(data, mime_type) {
                 Ok(()) => {
                     log::info!("Successfully copied to clipboard via wl-clipboard-rs fallback");
                     Ok(())
@@ -49,7 +56,7 @@ pub fn copy_to_clipboard(image_data: &[u8]) -> Result<(), CaptureError> {
 }
 
 /// Copy to clipboard using wl-clipboard-rs library.
-fn copy_via_library(image_data: &[u8]) -> Result<(), CaptureError> {
+fn copy_via_library(data: &[u8], mime_type: &str) -> Result<(), CaptureError> {
     use wl_clipboard_rs::copy::ServeRequests;
 
     let mut opts = Options::new();
@@ -59,8 +66,8 @@ fn copy_via_library(image_data: &[u8]) -> Result<(), CaptureError> {
     opts.serve_requests(ServeRequests::Only(1)); // Serve one paste then exit
 
     opts.copy(
-        Source::Bytes(image_data.into()),
-        MimeType::Specific("image/png".to_string()),
+        Source::Bytes(data.into()),
+        MimeType::Specific(mime_type.to_string()),
     )
     .map_err(|e| CaptureError::ClipboardError(format!("wl-clipboard-rs error: {}", e)))?;
 
@@ -68,12 +75,12 @@ fn copy_via_library(image_data: &[u8]) -> Result<(), CaptureError> {
 }
 
 /// Copy to clipboard by shelling out to wl-copy command.
-fn copy_via_command(image_data: &[u8]) -> Result<(), CaptureError> {
+fn copy_via_command(data: &[u8], mime_type: &str) -> Result<(), CaptureError> {
     use std::io::Write;
 
     let mut child = Command::new("wl-copy")
         .arg("--type")
-        .arg("image/png")
+        .arg(mime_type)
         .stdin(Stdio::piped())
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
@@ -85,9 +92,9 @@ fn copy_via_command(image_data: &[u8]) -> Result<(), CaptureError> {
             ))
         })?;
 
-    // Write image data to stdin
+    // Write data to stdin
     if let Some(mut stdin) = child.stdin.take() {
-        stdin.write_all(image_data).map_err(|e| {
+        stdin.write_all(data).map_err(|e| {
             CaptureError::ClipboardError(format!("Failed to write to wl-copy stdin: {}", e))
         })?;
     }
@@ -109,6 +116,72 @@ fn copy_via_command(image_data: &[u8]) -> Result<(), CaptureError> {
     Ok(())
 }
 
+/// Read data of the given MIME type from the Wayland clipboard.
+///
+/// Uses the wl-paste command if available, falling back to wl-clipboard-rs.
+/// Fails if the clipboard is empty or does not offer `mime_type`.
+pub fn paste_from_clipboard(mime_type: &str) -> Result<Vec<u8>, CaptureError> {
+    match paste_via_command(mime_type) {
+        Ok(data) => Ok(data),
+        Err(cmd_err) => {
+            log::debug!(
+                "wl-paste command path failed ({}). Falling back to wl-clipboard-rs",
+                cmd_err
+            );
+            paste_via_library(mime_type).map_err(|lib_err| {
+                CaptureError::ClipboardError(format!(
+                    "wl-paste failed: {} ; wl-clipboard-rs failed: {}",
+                    cmd_err, lib_err
+                ))
+            })
+        }
+    }
+}
+
+/// Read from clipboard using wl-clipboard-rs library.
+fn paste_via_library(mime_type: &str) -> Result<Vec<u8>, CaptureError> {
+    use std::io::Read;
+
+    let (mut pipe, _) = paste::get_contents(
+        paste::ClipboardType::Regular,
+        paste::Seat::Unspecified,
+        paste::MimeType::Specific(mime_type),
+    )
+    .map_err(|e| CaptureError::ClipboardError(format!("wl-clipboard-rs error: {}", e)))?;
+
+    let mut data = Vec::new();
+    pipe.read_to_end(&mut data).map_err(|e| {
+        CaptureError::ClipboardError(format!("Failed to read clipboard contents: {}", e))
+    })?;
+    Ok(data)
+}
+
+/// Read from clipboard by shelling out to wl-paste command.
+fn paste_via_command(mime_type: &str) -> Result<Vec<u8>, CaptureError> {
+    let output = Command::new("wl-paste")
+        .arg("--no-newline")
+        .arg("--type")
+        .arg(mime_type)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| {
+            CaptureError::ClipboardError(format!(
+                "Failed to run wl-paste (is it installed?): {}",
+                e
+            ))
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CaptureError::ClipboardError(format!(
+            "wl-paste failed: {}",
+            stderr.trim()
+        )));
+    }
+
+    Ok(output.stdout)
+}
+
 /// Check if clipboard functionality is available.
 ///
 /// Tests if wl-copy command exists as a basic availability check.
@@ -108,16 +108,6 @@ pub fn save_screenshot(
     Ok(file_path)
 }
 
-/// Expand tilde (~) in path strings.
-pub fn expand_tilde(path: &str) -> PathBuf {
-    if let Some(stripped) = path.strip_prefix("~/")
-        && let Some(home) = dirs::home_dir()
-    {
-        return home.join(stripped);
-    }
-    PathBuf::from(path)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,15 +121,6 @@ mod tests {
         assert!(filename.contains("202")); // Assuming we're in the 2020s
     }
 
-    #[test]
-    fn test_expand_tilde() {
-        let expanded = expand_tilde("~/Pictures");
-        assert!(!expanded.to_string_lossy().starts_with("~"));
-
-        let no_tilde = expand_tilde("/absolute/path");
-        assert_eq!(no_tilde, PathBuf::from("/absolute/path"));
-    }
-
     #[test]
     fn test_default_config() {
         let config = FileSaveConfig::default();
@@ -637,8 +637,7 @@ mod tests {
         ) -> Result<PathBuf, CaptureError> {
             *self.calls.lock().unwrap() += 1;
             if self.should_fail {
-                Err(CaptureError::SaveError(std::io::Error::new(
-                    std::io::ErrorKind::Other,
+                Err(CaptureError::SaveError(std::io::Error::other(
                     "save failed",
                 )))
             } else {
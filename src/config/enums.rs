@@ -21,6 +21,61 @@ pub enum StatusPosition {
     BottomRight,
 }
 
+/// Horizontal alignment of text annotations.
+///
+/// Lines are aligned against each other (or the wrap width, if set), and the
+/// text is anchored at its left edge, center or right edge accordingly.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextAlign {
+    /// Left-aligned (anchored at the left edge)
+    #[default]
+    Left,
+    /// Centered (anchored at the center)
+    Center,
+    /// Right-aligned (anchored at the right edge)
+    Right,
+}
+
+impl TextAlign {
+    /// Returns the next alignment in the left → center → right cycle.
+    pub fn next(self) -> Self {
+        match self {
+            TextAlign::Left => TextAlign::Center,
+            TextAlign::Center => TextAlign::Right,
+            TextAlign::Right => TextAlign::Left,
+        }
+    }
+}
+
+/// Readability effect drawn around text annotations.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextEffectStyle {
+    /// Plain text with no effect
+    None,
+    /// Semi-transparent box behind the text
+    Background,
+    /// Contrasting outline around each glyph
+    #[default]
+    Outline,
+    /// Offset drop shadow
+    Shadow,
+}
+
+/// How a background image is scaled to the screen.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImageFit {
+    /// Scale to show the whole image, leaving bars of background color
+    #[default]
+    Fit,
+    /// Scale to cover the whole screen, cropping the image edges
+    Fill,
+    /// Keep the image at its own size, centered
+    Center,
+}
+
 /// Color specification - either a named color or RGB values.
 ///
 /// # Examples
@@ -31,7 +86,7 @@ pub enum StatusPosition {
 /// # Custom RGB color (0-255 per component)
 /// default_color = [255, 128, 0]  # Orange
 /// ```
-#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 #[serde(untagged)]
 pub enum ColorSpec {
     /// Named color: red, green, blue, yellow, orange, pink, white, black
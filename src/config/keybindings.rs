@@ -3,6 +3,7 @@
 //! This module defines the configurable keybinding system that allows users
 //! to customize keyboard shortcuts for all actions in the application.
 
+use super::types::{CustomBoardConfig, PaletteEntry};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -18,6 +19,19 @@ pub enum Action {
     EnterTextMode,
     ClearCanvas,
     Undo,
+    Redo,
+    StartFresh,
+
+    // Selection tool
+    ToggleSelectionMode,
+    DeleteSelection,
+    CopyShapes,
+    CutShapes,
+    PasteShapes,
+    BringToFront,
+    SendToBack,
+    GroupShapes,
+    UngroupShapes,
 
     // Thickness controls
     IncreaseThickness,
@@ -25,10 +39,15 @@ pub enum Action {
     IncreaseFontSize,
     DecreaseFontSize,
 
+    // Text formatting
+    CycleTextAlignment,
+
     // Board mode toggles
     ToggleWhiteboard,
     ToggleBlackboard,
     ReturnToTransparent,
+    // Index into the configured `[[board.custom]]` entries
+    ToggleCustomBoard(usize),
 
     // UI toggles
     ToggleHelp,
@@ -36,15 +55,12 @@ pub enum Action {
     // Configurator
     OpenConfigurator,
 
-    // Color selections (using char to represent the color)
-    SetColorRed,
-    SetColorGreen,
-    SetColorBlue,
-    SetColorYellow,
-    SetColorOrange,
-    SetColorPink,
-    SetColorWhite,
-    SetColorBlack,
+    // Color selections (index into the configured `[palette]` entries)
+    SetColor(usize),
+
+    // Recent color history
+    NextRecentColor,
+    PreviousRecentColor,
 
     // Screenshot capture actions
     CaptureFullScreen,
@@ -56,6 +72,20 @@ pub enum Action {
     CaptureFileSelection,
     CaptureClipboardRegion,
     CaptureFileRegion,
+
+    // Board export actions
+    ExportSvg,
+    ExportPdf,
+    SaveDocument,
+    SaveDocumentAs,
+    NextPage,
+    PreviousPage,
+    NewPage,
+    DeletePage,
+    NextLayer,
+    ToggleLayerVisibility,
+    ClearLayer,
+    Peek,
 }
 
 /// A single keybinding: a key character with optional modifiers.
@@ -165,6 +195,39 @@ pub struct KeybindingsConfig {
     #[serde(default = "default_undo")]
     pub undo: Vec<String>,
 
+    #[serde(default = "default_redo")]
+    pub redo: Vec<String>,
+
+    #[serde(default = "default_start_fresh")]
+    pub start_fresh: Vec<String>,
+
+    #[serde(default = "default_toggle_selection_mode")]
+    pub toggle_selection_mode: Vec<String>,
+
+    #[serde(default = "default_delete_selection")]
+    pub delete_selection: Vec<String>,
+
+    #[serde(default = "default_copy_shapes")]
+    pub copy_shapes: Vec<String>,
+
+    #[serde(default = "default_cut_shapes")]
+    pub cut_shapes: Vec<String>,
+
+    #[serde(default = "default_paste_shapes")]
+    pub paste_shapes: Vec<String>,
+
+    #[serde(default = "default_bring_to_front")]
+    pub bring_to_front: Vec<String>,
+
+    #[serde(default = "default_send_to_back")]
+    pub send_to_back: Vec<String>,
+
+    #[serde(default = "default_group_shapes")]
+    pub group_shapes: Vec<String>,
+
+    #[serde(default = "default_ungroup_shapes")]
+    pub ungroup_shapes: Vec<String>,
+
     #[serde(default = "default_increase_thickness")]
     pub increase_thickness: Vec<String>,
 
@@ -177,6 +240,9 @@ pub struct KeybindingsConfig {
     #[serde(default = "default_decrease_font_size")]
     pub decrease_font_size: Vec<String>,
 
+    #[serde(default = "default_cycle_text_alignment")]
+    pub cycle_text_alignment: Vec<String>,
+
     #[serde(default = "default_toggle_whiteboard")]
     pub toggle_whiteboard: Vec<String>,
 
@@ -192,29 +258,11 @@ pub struct KeybindingsConfig {
     #[serde(default = "default_open_configurator")]
     pub open_configurator: Vec<String>,
 
-    #[serde(default = "default_set_color_red")]
-    pub set_color_red: Vec<String>,
-
-    #[serde(default = "default_set_color_green")]
-    pub set_color_green: Vec<String>,
-
-    #[serde(default = "default_set_color_blue")]
-    pub set_color_blue: Vec<String>,
-
-    #[serde(default = "default_set_color_yellow")]
-    pub set_color_yellow: Vec<String>,
+    #[serde(default = "default_next_recent_color")]
+    pub next_recent_color: Vec<String>,
 
-    #[serde(default = "default_set_color_orange")]
-    pub set_color_orange: Vec<String>,
-
-    #[serde(default = "default_set_color_pink")]
-    pub set_color_pink: Vec<String>,
-
-    #[serde(default = "default_set_color_white")]
-    pub set_color_white: Vec<String>,
-
-    #[serde(default = "default_set_color_black")]
-    pub set_color_black: Vec<String>,
+    #[serde(default = "default_previous_recent_color")]
+    pub previous_recent_color: Vec<String>,
 
     #[serde(default = "default_capture_full_screen")]
     pub capture_full_screen: Vec<String>,
@@ -242,6 +290,42 @@ pub struct KeybindingsConfig {
 
     #[serde(default = "default_capture_file_region")]
     pub capture_file_region: Vec<String>,
+
+    #[serde(default = "default_export_svg")]
+    pub export_svg: Vec<String>,
+
+    #[serde(default = "default_export_pdf")]
+    pub export_pdf: Vec<String>,
+
+    #[serde(default = "default_save_document")]
+    pub save_document: Vec<String>,
+
+    #[serde(default = "default_save_document_as")]
+    pub save_document_as: Vec<String>,
+
+    #[serde(default = "default_next_page")]
+    pub next_page: Vec<String>,
+
+    #[serde(default = "default_previous_page")]
+    pub previous_page: Vec<String>,
+
+    #[serde(default = "default_new_page")]
+    pub new_page: Vec<String>,
+
+    #[serde(default = "default_delete_page")]
+    pub delete_page: Vec<String>,
+
+    #[serde(default = "default_next_layer")]
+    pub next_layer: Vec<String>,
+
+    #[serde(default = "default_toggle_layer_visibility")]
+    pub toggle_layer_visibility: Vec<String>,
+
+    #[serde(default = "default_clear_layer")]
+    pub clear_layer: Vec<String>,
+
+    #[serde(default = "default_peek")]
+    pub peek: Vec<String>,
 }
 
 impl Default for KeybindingsConfig {
@@ -251,23 +335,29 @@ impl Default for KeybindingsConfig {
             enter_text_mode: default_enter_text_mode(),
             clear_canvas: default_clear_canvas(),
             undo: default_undo(),
+            redo: default_redo(),
+            start_fresh: default_start_fresh(),
+            toggle_selection_mode: default_toggle_selection_mode(),
+            delete_selection: default_delete_selection(),
+            copy_shapes: default_copy_shapes(),
+            cut_shapes: default_cut_shapes(),
+            paste_shapes: default_paste_shapes(),
+            bring_to_front: default_bring_to_front(),
+            send_to_back: default_send_to_back(),
+            group_shapes: default_group_shapes(),
+            ungroup_shapes: default_ungroup_shapes(),
             increase_thickness: default_increase_thickness(),
             decrease_thickness: default_decrease_thickness(),
             increase_font_size: default_increase_font_size(),
             decrease_font_size: default_decrease_font_size(),
+            cycle_text_alignment: default_cycle_text_alignment(),
             toggle_whiteboard: default_toggle_whiteboard(),
             toggle_blackboard: default_toggle_blackboard(),
             return_to_transparent: default_return_to_transparent(),
             toggle_help: default_toggle_help(),
             open_configurator: default_open_configurator(),
-            set_color_red: default_set_color_red(),
-            set_color_green: default_set_color_green(),
-            set_color_blue: default_set_color_blue(),
-            set_color_yellow: default_set_color_yellow(),
-            set_color_orange: default_set_color_orange(),
-            set_color_pink: default_set_color_pink(),
-            set_color_white: default_set_color_white(),
-            set_color_black: default_set_color_black(),
+            next_recent_color: default_next_recent_color(),
+            previous_recent_color: default_previous_recent_color(),
             capture_full_screen: default_capture_full_screen(),
             capture_active_window: default_capture_active_window(),
             capture_selection: default_capture_selection(),
@@ -277,6 +367,18 @@ impl Default for KeybindingsConfig {
             capture_file_selection: default_capture_file_selection(),
             capture_clipboard_region: default_capture_clipboard_region(),
             capture_file_region: default_capture_file_region(),
+            export_svg: default_export_svg(),
+            export_pdf: default_export_pdf(),
+            save_document: default_save_document(),
+            save_document_as: default_save_document_as(),
+            next_page: default_next_page(),
+            previous_page: default_previous_page(),
+            new_page: default_new_page(),
+            delete_page: default_delete_page(),
+            next_layer: default_next_layer(),
+            toggle_layer_visibility: default_toggle_layer_visibility(),
+            clear_layer: default_clear_layer(),
+            peek: default_peek(),
         }
     }
 }
@@ -284,7 +386,32 @@ impl Default for KeybindingsConfig {
 impl KeybindingsConfig {
     /// Build a lookup map from keybindings to actions for efficient matching.
     /// Returns an error if any keybinding string is invalid or if duplicates are detected.
+    ///
+    /// Palette color keys are not included; use [`Self::build_action_map_with_palette`]
+    /// to bind them alongside the regular actions.
     pub fn build_action_map(&self) -> Result<HashMap<KeyBinding, Action>, String> {
+        self.build_action_map_with_palette(&[])
+    }
+
+    /// Build the action map including the keys of each palette entry.
+    ///
+    /// Each palette entry's keys map to [`Action::SetColor`] with the entry's index.
+    /// Duplicates between palette keys and regular keybindings are reported as errors.
+    pub fn build_action_map_with_palette(
+        &self,
+        palette: &[PaletteEntry],
+    ) -> Result<HashMap<KeyBinding, Action>, String> {
+        self.build_action_map_with(palette, &[])
+    }
+
+    /// Build the action map including the keys of each palette entry and custom board.
+    ///
+    /// Custom board keys map to [`Action::ToggleCustomBoard`] with the board's index.
+    pub fn build_action_map_with(
+        &self,
+        palette: &[PaletteEntry],
+        boards: &[CustomBoardConfig],
+    ) -> Result<HashMap<KeyBinding, Action>, String> {
         let mut map = HashMap::new();
 
         // Helper closure to insert and check for duplicates
@@ -315,6 +442,50 @@ impl KeybindingsConfig {
             insert_binding(binding_str, Action::Undo)?;
         }
 
+        for binding_str in &self.redo {
+            insert_binding(binding_str, Action::Redo)?;
+        }
+
+        for binding_str in &self.start_fresh {
+            insert_binding(binding_str, Action::StartFresh)?;
+        }
+
+        for binding_str in &self.toggle_selection_mode {
+            insert_binding(binding_str, Action::ToggleSelectionMode)?;
+        }
+
+        for binding_str in &self.delete_selection {
+            insert_binding(binding_str, Action::DeleteSelection)?;
+        }
+
+        for binding_str in &self.copy_shapes {
+            insert_binding(binding_str, Action::CopyShapes)?;
+        }
+
+        for binding_str in &self.cut_shapes {
+            insert_binding(binding_str, Action::CutShapes)?;
+        }
+
+        for binding_str in &self.paste_shapes {
+            insert_binding(binding_str, Action::PasteShapes)?;
+        }
+
+        for binding_str in &self.bring_to_front {
+            insert_binding(binding_str, Action::BringToFront)?;
+        }
+
+        for binding_str in &self.send_to_back {
+            insert_binding(binding_str, Action::SendToBack)?;
+        }
+
+        for binding_str in &self.group_shapes {
+            insert_binding(binding_str, Action::GroupShapes)?;
+        }
+
+        for binding_str in &self.ungroup_shapes {
+            insert_binding(binding_str, Action::UngroupShapes)?;
+        }
+
         for binding_str in &self.increase_thickness {
             insert_binding(binding_str, Action::IncreaseThickness)?;
         }
@@ -331,6 +502,10 @@ impl KeybindingsConfig {
             insert_binding(binding_str, Action::DecreaseFontSize)?;
         }
 
+        for binding_str in &self.cycle_text_alignment {
+            insert_binding(binding_str, Action::CycleTextAlignment)?;
+        }
+
         for binding_str in &self.toggle_whiteboard {
             insert_binding(binding_str, Action::ToggleWhiteboard)?;
         }
@@ -351,36 +526,12 @@ impl KeybindingsConfig {
             insert_binding(binding_str, Action::OpenConfigurator)?;
         }
 
-        for binding_str in &self.set_color_red {
-            insert_binding(binding_str, Action::SetColorRed)?;
-        }
-
-        for binding_str in &self.set_color_green {
-            insert_binding(binding_str, Action::SetColorGreen)?;
+        for binding_str in &self.next_recent_color {
+            insert_binding(binding_str, Action::NextRecentColor)?;
         }
 
-        for binding_str in &self.set_color_blue {
-            insert_binding(binding_str, Action::SetColorBlue)?;
-        }
-
-        for binding_str in &self.set_color_yellow {
-            insert_binding(binding_str, Action::SetColorYellow)?;
-        }
-
-        for binding_str in &self.set_color_orange {
-            insert_binding(binding_str, Action::SetColorOrange)?;
-        }
-
-        for binding_str in &self.set_color_pink {
-            insert_binding(binding_str, Action::SetColorPink)?;
-        }
-
-        for binding_str in &self.set_color_white {
-            insert_binding(binding_str, Action::SetColorWhite)?;
-        }
-
-        for binding_str in &self.set_color_black {
-            insert_binding(binding_str, Action::SetColorBlack)?;
+        for binding_str in &self.previous_recent_color {
+            insert_binding(binding_str, Action::PreviousRecentColor)?;
         }
 
         for binding_str in &self.capture_full_screen {
@@ -419,6 +570,66 @@ impl KeybindingsConfig {
             insert_binding(binding_str, Action::CaptureFileRegion)?;
         }
 
+        for binding_str in &self.export_svg {
+            insert_binding(binding_str, Action::ExportSvg)?;
+        }
+
+        for binding_str in &self.export_pdf {
+            insert_binding(binding_str, Action::ExportPdf)?;
+        }
+
+        for binding_str in &self.save_document {
+            insert_binding(binding_str, Action::SaveDocument)?;
+        }
+
+        for binding_str in &self.save_document_as {
+            insert_binding(binding_str, Action::SaveDocumentAs)?;
+        }
+
+        for binding_str in &self.next_page {
+            insert_binding(binding_str, Action::NextPage)?;
+        }
+
+        for binding_str in &self.previous_page {
+            insert_binding(binding_str, Action::PreviousPage)?;
+        }
+
+        for binding_str in &self.new_page {
+            insert_binding(binding_str, Action::NewPage)?;
+        }
+
+        for binding_str in &self.delete_page {
+            insert_binding(binding_str, Action::DeletePage)?;
+        }
+
+        for binding_str in &self.next_layer {
+            insert_binding(binding_str, Action::NextLayer)?;
+        }
+
+        for binding_str in &self.toggle_layer_visibility {
+            insert_binding(binding_str, Action::ToggleLayerVisibility)?;
+        }
+
+        for binding_str in &self.clear_layer {
+            insert_binding(binding_str, Action::ClearLayer)?;
+        }
+
+        for binding_str in &self.peek {
+            insert_binding(binding_str, Action::Peek)?;
+        }
+
+        for (index, entry) in palette.iter().enumerate() {
+            for binding_str in &entry.keys {
+                insert_binding(binding_str, Action::SetColor(index))?;
+            }
+        }
+
+        for (index, board) in boards.iter().enumerate() {
+            for binding_str in &board.keys {
+                insert_binding(binding_str, Action::ToggleCustomBoard(index))?;
+            }
+        }
+
         Ok(map)
     }
 }
@@ -443,6 +654,50 @@ fn default_undo() -> Vec<String> {
     vec!["Ctrl+Z".to_string()]
 }
 
+fn default_redo() -> Vec<String> {
+    vec!["Ctrl+Shift+Z".to_string(), "Ctrl+Y".to_string()]
+}
+
+fn default_start_fresh() -> Vec<String> {
+    vec!["Ctrl+Shift+N".to_string()]
+}
+
+fn default_toggle_selection_mode() -> Vec<String> {
+    vec!["S".to_string()]
+}
+
+fn default_delete_selection() -> Vec<String> {
+    vec!["Delete".to_string(), "Backspace".to_string()]
+}
+
+fn default_copy_shapes() -> Vec<String> {
+    vec!["Ctrl+Alt+C".to_string()]
+}
+
+fn default_cut_shapes() -> Vec<String> {
+    vec!["Ctrl+X".to_string()]
+}
+
+fn default_paste_shapes() -> Vec<String> {
+    vec!["Ctrl+V".to_string()]
+}
+
+fn default_bring_to_front() -> Vec<String> {
+    vec!["Ctrl+]".to_string()]
+}
+
+fn default_send_to_back() -> Vec<String> {
+    vec!["Ctrl+[".to_string()]
+}
+
+fn default_group_shapes() -> Vec<String> {
+    vec!["Ctrl+G".to_string()]
+}
+
+fn default_ungroup_shapes() -> Vec<String> {
+    vec!["Ctrl+Shift+G".to_string()]
+}
+
 fn default_increase_thickness() -> Vec<String> {
     vec!["+".to_string(), "=".to_string()]
 }
@@ -459,6 +714,10 @@ fn default_decrease_font_size() -> Vec<String> {
     vec!["Ctrl+Shift+-".to_string(), "Ctrl+Shift+_".to_string()]
 }
 
+fn default_cycle_text_alignment() -> Vec<String> {
+    vec!["Ctrl+Shift+A".to_string()]
+}
+
 fn default_toggle_whiteboard() -> Vec<String> {
     vec!["Ctrl+W".to_string()]
 }
@@ -479,36 +738,12 @@ fn default_open_configurator() -> Vec<String> {
     vec!["F11".to_string()]
 }
 
-fn default_set_color_red() -> Vec<String> {
-    vec!["R".to_string()]
-}
-
-fn default_set_color_green() -> Vec<String> {
-    vec!["G".to_string()]
-}
-
-fn default_set_color_blue() -> Vec<String> {
-    vec!["B".to_string()]
-}
-
-fn default_set_color_yellow() -> Vec<String> {
-    vec!["Y".to_string()]
-}
-
-fn default_set_color_orange() -> Vec<String> {
-    vec!["O".to_string()]
+fn default_next_recent_color() -> Vec<String> {
+    vec!["C".to_string()]
 }
 
-fn default_set_color_pink() -> Vec<String> {
-    vec!["P".to_string()]
-}
-
-fn default_set_color_white() -> Vec<String> {
-    vec!["W".to_string()]
-}
-
-fn default_set_color_black() -> Vec<String> {
-    vec!["K".to_string()]
+fn default_previous_recent_color() -> Vec<String> {
+    vec!["Shift+C".to_string()]
 }
 
 fn default_capture_full_screen() -> Vec<String> {
@@ -547,6 +782,54 @@ fn default_capture_file_region() -> Vec<String> {
     vec!["Ctrl+Shift+6".to_string()]
 }
 
+fn default_export_svg() -> Vec<String> {
+    vec!["Ctrl+E".to_string()]
+}
+
+fn default_export_pdf() -> Vec<String> {
+    vec!["Ctrl+Shift+E".to_string()]
+}
+
+fn default_save_document() -> Vec<String> {
+    vec!["Ctrl+Alt+S".to_string()]
+}
+
+fn default_save_document_as() -> Vec<String> {
+    vec!["Ctrl+Alt+Shift+S".to_string()]
+}
+
+fn default_next_page() -> Vec<String> {
+    vec!["PageDown".to_string()]
+}
+
+fn default_previous_page() -> Vec<String> {
+    vec!["PageUp".to_string()]
+}
+
+fn default_new_page() -> Vec<String> {
+    vec!["Ctrl+Alt+N".to_string()]
+}
+
+fn default_delete_page() -> Vec<String> {
+    vec!["Ctrl+Alt+D".to_string()]
+}
+
+fn default_next_layer() -> Vec<String> {
+    vec!["Ctrl+Alt+L".to_string()]
+}
+
+fn default_toggle_layer_visibility() -> Vec<String> {
+    vec!["Ctrl+Alt+H".to_string()]
+}
+
+fn default_clear_layer() -> Vec<String> {
+    vec!["Shift+E".to_string()]
+}
+
+fn default_peek() -> Vec<String> {
+    vec!["Space".to_string()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -685,4 +968,58 @@ mod tests {
         let err_msg = result.unwrap_err();
         assert!(err_msg.contains("Duplicate keybinding"));
     }
+
+    #[test]
+    fn test_palette_keys_map_to_set_color() {
+        let config = KeybindingsConfig::default();
+        let palette = crate::config::PaletteConfig::default();
+        let map = config
+            .build_action_map_with_palette(&palette.colors)
+            .unwrap();
+
+        let r = KeyBinding::parse("R").unwrap();
+        assert_eq!(map.get(&r), Some(&Action::SetColor(0)));
+
+        let k = KeyBinding::parse("K").unwrap();
+        assert_eq!(map.get(&k), Some(&Action::SetColor(7)));
+    }
+
+    #[test]
+    fn test_palette_key_conflicting_with_action_is_rejected() {
+        let config = KeybindingsConfig::default();
+        let palette = vec![PaletteEntry {
+            name: "teal".to_string(),
+            color: crate::config::ColorSpec::Rgb([0, 128, 128]),
+            keys: vec!["E".to_string()],
+        }];
+
+        let err = config.build_action_map_with_palette(&palette).unwrap_err();
+        assert!(err.contains("Duplicate keybinding"));
+    }
+
+    #[test]
+    fn test_custom_board_keys_map_to_toggle_custom_board() {
+        let config = KeybindingsConfig::default();
+        let board = |name: &str, key: &str| CustomBoardConfig {
+            name: name.to_string(),
+            background_color: [0.1, 0.3, 0.2],
+            background_alpha: 1.0,
+            pen_color: None,
+            keys: vec![key.to_string()],
+        };
+
+        let map = config
+            .build_action_map_with(
+                &[],
+                &[board("greenboard", "Alt+G"), board("blueprint", "Alt+U")],
+            )
+            .unwrap();
+        let binding = KeyBinding::parse("Alt+U").unwrap();
+        assert_eq!(map.get(&binding), Some(&Action::ToggleCustomBoard(1)));
+
+        let err = config
+            .build_action_map_with(&[], &[board("greenboard", "Ctrl+W")])
+            .unwrap_err();
+        assert!(err.contains("Duplicate keybinding"));
+    }
 }
@@ -1,6 +1,8 @@
+use super::types::PaletteConfig;
 use super::{legacy_config_dir, primary_config_dir};
 use anyhow::{Context, Result, anyhow};
 use chrono::Local;
+use log::{info, warn};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -101,6 +103,70 @@ pub fn migrate_config(dry_run: bool) -> Result<MigrationReport> {
     })
 }
 
+/// Prefix of the per-color keybindings replaced by `[palette]` entries.
+const SET_COLOR_PREFIX: &str = "set_color_";
+
+/// Moves the `set_color_*` keybindings of older config files onto the
+/// matching entries of the default palette, in place.
+///
+/// When the file defines its own `[[palette.colors]]`, those win and the old
+/// keybindings are dropped with a warning. Returns `true` if anything changed.
+pub fn migrate_color_keybindings(config: &mut toml::Table) -> bool {
+    let Some(toml::Value::Table(keybindings)) = config.get_mut("keybindings") else {
+        return false;
+    };
+    let old_keys: Vec<String> = keybindings
+        .keys()
+        .filter(|key| key.starts_with(SET_COLOR_PREFIX))
+        .cloned()
+        .collect();
+    if old_keys.is_empty() {
+        return false;
+    }
+    let color_keys: Vec<(String, toml::Value)> = old_keys
+        .into_iter()
+        .filter_map(|key| {
+            let value = keybindings.remove(&key)?;
+            Some((key[SET_COLOR_PREFIX.len()..].to_string(), value))
+        })
+        .collect();
+
+    let palette = config
+        .entry("palette")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    let Some(palette) = palette.as_table_mut() else {
+        return true;
+    };
+    if palette.contains_key("colors") {
+        warn!(
+            "Ignoring set_color_* keybindings because [[palette.colors]] is defined; \
+             set the keys of palette entries instead"
+        );
+        return true;
+    }
+
+    let mut colors = PaletteConfig::default().colors;
+    for (name, keys) in color_keys {
+        match (
+            colors.iter_mut().find(|entry| entry.name == name),
+            keys.try_into::<Vec<String>>(),
+        ) {
+            (Some(entry), Ok(keys)) => {
+                info!("Moved set_color_{name} keybinding to the {name} palette color");
+                entry.keys = keys;
+            }
+            _ => warn!("Ignoring unsupported keybinding set_color_{name}"),
+        }
+    }
+    match toml::Value::try_from(colors) {
+        Ok(colors) => {
+            palette.insert("colors".to_string(), colors);
+        }
+        Err(e) => warn!("Failed to migrate set_color_* keybindings: {}", e),
+    }
+    true
+}
+
 fn copy_directory(src: &Path, dest: &Path, dry_run: bool) -> Result<usize> {
     if !dry_run {
         fs::create_dir_all(dest)
@@ -229,6 +295,61 @@ mod tests {
         });
     }
 
+    #[test]
+    fn set_color_keybindings_move_to_the_palette() {
+        let mut table: toml::Table = toml::from_str(
+            r#"
+            [keybindings]
+            set_color_red = ["Alt+R"]
+            set_color_black = ["Alt+K", "Alt+0"]
+            undo = ["Ctrl+Z"]
+            "#,
+        )
+        .unwrap();
+        assert!(migrate_color_keybindings(&mut table));
+        assert!(
+            !table["keybindings"]
+                .as_table()
+                .unwrap()
+                .contains_key("set_color_red")
+        );
+
+        let config: crate::config::Config = table.try_into().unwrap();
+        let keys_of = |name: &str| {
+            config
+                .palette
+                .colors
+                .iter()
+                .find(|entry| entry.name == name)
+                .map(|entry| entry.keys.clone())
+                .unwrap()
+        };
+        assert_eq!(keys_of("red"), vec!["Alt+R"]);
+        assert_eq!(keys_of("black"), vec!["Alt+K", "Alt+0"]);
+        assert_eq!(
+            keys_of("green"),
+            vec!["G"],
+            "others keep their default keys"
+        );
+
+        // A palette of the user's own is left alone
+        let mut table: toml::Table = toml::from_str(
+            r#"
+            [keybindings]
+            set_color_red = ["Alt+R"]
+
+            [[palette.colors]]
+            name = "teal"
+            color = [0, 128, 128]
+            keys = ["T"]
+            "#,
+        )
+        .unwrap();
+        assert!(migrate_color_keybindings(&mut table));
+        let config: crate::config::Config = table.try_into().unwrap();
+        assert_eq!(config.palette.colors.len(), 1);
+    }
+
     #[test]
     fn migrate_supports_dry_run() {
         with_temp_config_home(|config_root| {
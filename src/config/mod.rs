@@ -12,17 +12,19 @@ pub mod migration;
 pub mod types;
 
 // Re-export commonly used types at module level
-pub use enums::StatusPosition;
+pub use enums::{ImageFit, StatusPosition, TextAlign, TextEffectStyle};
 pub use keybindings::{Action, KeyBinding, KeybindingsConfig};
 pub use migration::{MigrationActions, MigrationReport, migrate_config};
 pub use types::{
-    ArrowConfig, BoardConfig, CaptureConfig, DrawingConfig, HelpOverlayStyle, PerformanceConfig,
-    StatusBarStyle, UiConfig,
+    ArrowConfig, BoardConfig, CaptureConfig, DrawingConfig, HelpOverlayStyle, PaletteConfig,
+    PerformanceConfig, SessionConfig, StatusBarStyle, UiConfig,
 };
 
 // Re-export for public API (unused internally but part of public interface)
 #[allow(unused_imports)]
 pub use enums::ColorSpec;
+#[allow(unused_imports)]
+pub use types::{BoardBackgroundConfig, CustomBoardConfig, PaletteEntry};
 
 use crate::legacy;
 use anyhow::{Context, Result};
@@ -31,6 +33,7 @@ use log::{debug, info, warn};
 use schemars::{JsonSchema, schema_for};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -138,9 +141,21 @@ mod tests {
         config.drawing.default_font_size = 3.0;
         config.drawing.font_weight = "not-a-real-weight".to_string();
         config.drawing.font_style = "diagonal".to_string();
+        config.drawing.text_wrap_width = Some(10.0);
+        config.drawing.text_line_spacing = 9.0;
+        config.drawing.undo_history = 5000;
+        config.drawing.layers = vec![
+            " Diagram ".to_string(),
+            String::new(),
+            "diagram".to_string(),
+            "Ink".to_string(),
+        ];
+        config.drawing.text_outline_width = 0.0;
+        config.drawing.text_effect_color = Some([2.0, 0.5, -1.0, 1.0]);
         config.arrow.length = 100.0;
         config.arrow.angle_degrees = 5.0;
         config.performance.buffer_count = 8;
+        config.session.autosave_interval_secs = 1;
         config.board.default_mode = "magenta-board".to_string();
         config.board.whiteboard_color = [1.5, -0.5, 0.5];
         config.board.blackboard_color = [-0.2, 2.0, 0.5];
@@ -153,9 +168,16 @@ mod tests {
         assert_eq!(config.drawing.default_font_size, 8.0);
         assert_eq!(config.drawing.font_weight, "bold");
         assert_eq!(config.drawing.font_style, "normal");
+        assert_eq!(config.drawing.text_wrap_width, Some(50.0));
+        assert_eq!(config.drawing.text_line_spacing, 3.0);
+        assert_eq!(config.drawing.undo_history, 1000);
+        assert_eq!(config.drawing.layers, vec!["Diagram", "Ink"]);
+        assert_eq!(config.drawing.text_outline_width, 0.5);
+        assert_eq!(config.drawing.text_effect_color, Some([1.0, 0.5, 0.0, 1.0]));
         assert_eq!(config.arrow.length, 50.0);
         assert_eq!(config.arrow.angle_degrees, 15.0);
         assert_eq!(config.performance.buffer_count, 4);
+        assert_eq!(config.session.autosave_interval_secs, 5);
         assert_eq!(config.board.default_mode, "transparent");
         assert!(
             config
@@ -187,6 +209,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_and_clamp_resets_palette_with_conflicting_keys() {
+        let mut config = Config::default();
+        config.palette.colors.push(PaletteEntry {
+            name: "teal".to_string(),
+            color: ColorSpec::Rgb([0, 128, 128]),
+            keys: vec!["Ctrl+Z".to_string()],
+        });
+        config.palette.recent_colors = 40;
+
+        config.validate_and_clamp();
+
+        assert_eq!(config.palette.colors.len(), 8);
+        assert_eq!(config.keybindings.undo, vec!["Ctrl+Z".to_string()]);
+        assert!(config.build_action_map().is_ok());
+    }
+
+    #[test]
+    fn palette_section_parses_from_toml() {
+        let config: Config = toml::from_str(
+            r#"
+            [palette]
+            recent_colors = 4
+
+            [[palette.colors]]
+            name = "teal"
+            color = [0, 128, 128]
+            keys = ["Alt+T"]
+            "#,
+        )
+        .expect("palette config parses");
+
+        assert_eq!(config.palette.recent_colors, 4);
+        assert_eq!(config.palette.colors.len(), 1);
+        let map = config.build_action_map().unwrap();
+        let binding = KeyBinding::parse("Alt+T").unwrap();
+        assert_eq!(map.get(&binding), Some(&Action::SetColor(0)));
+    }
+
+    #[test]
+    fn custom_boards_parse_and_validate() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [board]
+            default_mode = "Blueprint"
+
+            [[board.custom]]
+            name = " blueprint "
+            background_color = [0.1, 0.2, 1.5]
+            pen_color = [1.0, 1.0, 1.0]
+            keys = ["Ctrl+Z"]
+
+            [[board.custom]]
+            name = "Whiteboard"
+            background_color = [1.0, 1.0, 1.0]
+
+            [[board.custom]]
+            name = "tinted"
+            background_color = [0.0, 0.0, 0.0]
+            background_alpha = 0.3
+
+            [[board.backgrounds]]
+            board = "Tinted"
+            image = "~/Templates/grid.png"
+            fit = "center"
+
+            [[board.backgrounds]]
+            board = "nowhere"
+            image = "~/Templates/grid.png"
+            "#,
+        )
+        .expect("custom boards parse");
+
+        config.validate_and_clamp();
+
+        let names: Vec<&str> = config
+            .board
+            .custom
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["blueprint", "tinted"]);
+        assert_eq!(config.board.custom[0].background_color, [0.1, 0.2, 1.0]);
+        assert_eq!(config.board.custom[1].background_alpha, 0.3);
+        assert_eq!(config.board.default_mode, "Blueprint");
+        assert_eq!(config.board.backgrounds.len(), 1);
+        assert_eq!(config.board.backgrounds[0].fit, ImageFit::Center);
+        // Ctrl+Z is taken by undo, so the board loses its keys
+        assert!(config.board.custom[0].keys.is_empty());
+        assert_eq!(config.keybindings.undo, vec!["Ctrl+Z".to_string()]);
+    }
+
+    #[test]
+    fn legacy_text_background_flag_maps_to_background_effect() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [drawing]
+            text_background_enabled = true
+            "#,
+        )
+        .expect("legacy drawing config parses");
+
+        config.validate_and_clamp();
+
+        assert_eq!(config.drawing.text_effect, TextEffectStyle::Background);
+        assert_eq!(config.drawing.text_background_enabled, None);
+    }
+
     #[test]
     fn save_with_backup_creates_timestamped_file() {
         with_temp_config_home(|config_root| {
@@ -257,6 +387,14 @@ pub(super) fn legacy_config_dir() -> Result<PathBuf> {
 /// show_status_bar = true
 /// status_bar_position = "bottom-left"
 ///
+/// [palette]
+/// recent_colors = 8
+///
+/// [[palette.colors]]
+/// name = "red"
+/// color = "red"
+/// keys = ["R"]
+///
 /// [keybindings]
 /// exit = ["Escape", "Ctrl+Q"]
 /// undo = ["Ctrl+Z"]
@@ -283,6 +421,10 @@ pub struct Config {
     #[serde(default)]
     pub board: BoardConfig,
 
+    /// Named color palette and recent color history
+    #[serde(default)]
+    pub palette: PaletteConfig,
+
     /// Keybinding customization
     #[serde(default)]
     pub keybindings: KeybindingsConfig,
@@ -290,6 +432,10 @@ pub struct Config {
     /// Screenshot capture settings
     #[serde(default)]
     pub capture: CaptureConfig,
+
+    /// Session persistence across overlay restarts
+    #[serde(default)]
+    pub session: SessionConfig,
 }
 
 impl Config {
@@ -309,9 +455,19 @@ impl Config {
     /// Validated ranges:
     /// - `default_thickness`: 1.0 - 20.0
     /// - `default_font_size`: 8.0 - 72.0
+    /// - `text_wrap_width`: 50.0 - 4000.0 (when set)
+    /// - `text_line_spacing`: 0.5 - 3.0
+    /// - `undo_history`: 0 - 1000
+    /// - `layers`: at least one, with distinct non-empty names
+    /// - `text_outline_width`: 0.5 - 10.0
+    /// - `text_shadow_offset`: 0.5 - 20.0
+    /// - `text_background_padding`: 0.0 - 40.0
+    /// - `text_effect_color` components: 0.0 - 1.0
     /// - `arrow.length`: 5.0 - 50.0
     /// - `arrow.angle_degrees`: 15.0 - 60.0
     /// - `buffer_count`: 2 - 4
+    /// - `palette.recent_colors`: 0 - 16
+    /// - `session.autosave_interval_secs`: 5 - 3600
     pub fn validate_and_clamp(&mut self) {
         // Thickness: 1.0 - 20.0
         if !(1.0..=20.0).contains(&self.drawing.default_thickness) {
@@ -331,6 +487,96 @@ impl Config {
             self.drawing.default_font_size = self.drawing.default_font_size.clamp(8.0, 72.0);
         }
 
+        // Text wrap width: 50.0 - 4000.0
+        if let Some(width) = self.drawing.text_wrap_width
+            && !(50.0..=4000.0).contains(&width)
+        {
+            log::warn!(
+                "Invalid text_wrap_width {:.1}, clamping to 50.0-4000.0 range",
+                width
+            );
+            self.drawing.text_wrap_width = Some(width.clamp(50.0, 4000.0));
+        }
+
+        // Text line spacing: 0.5 - 3.0
+        if !(0.5..=3.0).contains(&self.drawing.text_line_spacing) {
+            log::warn!(
+                "Invalid text_line_spacing {:.2}, clamping to 0.5-3.0 range",
+                self.drawing.text_line_spacing
+            );
+            self.drawing.text_line_spacing = self.drawing.text_line_spacing.clamp(0.5, 3.0);
+        }
+
+        // Undo history: 0 - 1000 steps
+        if self.drawing.undo_history > 1000 {
+            log::warn!(
+                "Invalid undo_history {}, clamping to 0-1000 range",
+                self.drawing.undo_history
+            );
+            self.drawing.undo_history = 1000;
+        }
+
+        // Layers: non-empty, distinct names
+        let mut layers: Vec<String> = Vec::new();
+        for name in std::mem::take(&mut self.drawing.layers) {
+            let name = name.trim().to_string();
+            if name.is_empty() || layers.iter().any(|layer| layer.eq_ignore_ascii_case(&name)) {
+                log::warn!("Ignoring empty or duplicate layer name '{}'", name);
+                continue;
+            }
+            layers.push(name);
+        }
+        if layers.is_empty() {
+            log::warn!("No layers configured, using the default layers");
+            layers = DrawingConfig::default().layers;
+        }
+        self.drawing.layers = layers;
+
+        // Legacy text background flag maps onto the text effect
+        if let Some(enabled) = self.drawing.text_background_enabled.take() {
+            log::warn!(
+                "text_background_enabled is deprecated; use text_effect = \"background\" instead"
+            );
+            if enabled {
+                self.drawing.text_effect = TextEffectStyle::Background;
+            }
+        }
+
+        // Text effect sizes
+        if !(0.5..=10.0).contains(&self.drawing.text_outline_width) {
+            log::warn!(
+                "Invalid text_outline_width {:.1}, clamping to 0.5-10.0 range",
+                self.drawing.text_outline_width
+            );
+            self.drawing.text_outline_width = self.drawing.text_outline_width.clamp(0.5, 10.0);
+        }
+        if !(0.5..=20.0).contains(&self.drawing.text_shadow_offset) {
+            log::warn!(
+                "Invalid text_shadow_offset {:.1}, clamping to 0.5-20.0 range",
+                self.drawing.text_shadow_offset
+            );
+            self.drawing.text_shadow_offset = self.drawing.text_shadow_offset.clamp(0.5, 20.0);
+        }
+        if !(0.0..=40.0).contains(&self.drawing.text_background_padding) {
+            log::warn!(
+                "Invalid text_background_padding {:.1}, clamping to 0.0-40.0 range",
+                self.drawing.text_background_padding
+            );
+            self.drawing.text_background_padding =
+                self.drawing.text_background_padding.clamp(0.0, 40.0);
+        }
+        if let Some(color) = self.drawing.text_effect_color.as_mut() {
+            for component in color.iter_mut() {
+                if !(0.0..=1.0).contains(component) {
+                    log::warn!(
+                        "Invalid text_effect_color component {:.2}, clamping to 0.0-1.0",
+                        component
+                    );
+                    *component = component.clamp(0.0, 1.0);
+                }
+            }
+        }
+
         // Arrow length: 5.0 - 50.0
         if !(5.0..=50.0).contains(&self.arrow.length) {
             log::warn!(
@@ -358,6 +604,16 @@ impl Config {
             self.performance.buffer_count = self.performance.buffer_count.clamp(2, 4);
         }
 
+        // Autosave interval: 5 - 3600 seconds
+        if !(5..=3600).contains(&self.session.autosave_interval_secs) {
+            log::warn!(
+                "Invalid autosave_interval_secs {}, clamping to 5-3600 range",
+                self.session.autosave_interval_secs
+            );
+            self.session.autosave_interval_secs =
+                self.session.autosave_interval_secs.clamp(5, 3600);
+        }
+
         // Validate font weight is reasonable
         let valid_weight = matches!(
             self.drawing.font_weight.to_lowercase().as_str(),
@@ -388,11 +644,55 @@ impl Config {
             self.drawing.font_style = "normal".to_string();
         }
 
+        // Custom boards need a unique name that doesn't shadow a built-in board
+        let mut names: Vec<String> = Vec::new();
+        self.board.custom.retain_mut(|board| {
+            board.name = board.name.trim().to_string();
+            let name = board.name.to_lowercase();
+            let valid = !name.is_empty()
+                && name.parse::<crate::input::BoardMode>().is_err()
+                && !names.contains(&name);
+            if valid {
+                names.push(name);
+            } else {
+                log::warn!(
+                    "Ignoring custom board with invalid or duplicate name '{}'",
+                    board.name
+                );
+            }
+            valid
+        });
+        for board in &mut self.board.custom {
+            for value in board
+                .background_color
+                .iter_mut()
+                .chain(board.pen_color.iter_mut().flatten())
+            {
+                *value = value.clamp(0.0, 1.0);
+            }
+            board.background_alpha = board.background_alpha.clamp(0.0, 1.0);
+        }
+
+        // Background images need a board to go on
+        let backgrounds = std::mem::take(&mut self.board.backgrounds);
+        let backgrounds = backgrounds
+            .into_iter()
+            .filter(|background| {
+                let known =
+                    crate::input::BoardMode::from_name(&background.board, &self.board).is_some();
+                if !known {
+                    log::warn!(
+                        "Ignoring background image for unknown board '{}'",
+                        background.board
+                    );
+                }
+                known
+            })
+            .collect();
+        self.board.backgrounds = backgrounds;
+
         // Validate board mode default
-        if !matches!(
-            self.board.default_mode.to_lowercase().as_str(),
-            "transparent" | "whiteboard" | "blackboard"
-        ) {
+        if crate::input::BoardMode::from_name(&self.board.default_mode, &self.board).is_none() {
             log::warn!(
                 "Invalid board default_mode '{}', falling back to 'transparent'",
                 self.board.default_mode
@@ -438,11 +738,50 @@ impl Config {
             }
         }
 
+        // Recent colors: 0 - 16
+        if self.palette.recent_colors > 16 {
+            log::warn!(
+                "Invalid palette recent_colors {}, clamping to 0-16 range",
+                self.palette.recent_colors
+            );
+            self.palette.recent_colors = 16;
+        }
+
         // Validate keybindings (try to build action map to catch parse errors)
         if let Err(e) = self.keybindings.build_action_map() {
             log::warn!("Invalid keybinding configuration: {}. Using defaults.", e);
             self.keybindings = KeybindingsConfig::default();
         }
+
+        // Validate palette keys against the remaining keybindings
+        if let Err(e) = self.build_action_map() {
+            log::warn!("Invalid palette configuration: {}. Using defaults.", e);
+            self.palette = PaletteConfig::default();
+
+            // The default palette keys may still clash with custom keybindings
+            if self.build_action_map().is_err() {
+                log::warn!("Default palette keys conflict with keybindings. Using defaults.");
+                self.keybindings = KeybindingsConfig::default();
+            }
+        }
+
+        // Custom board keys are dropped rather than the boards themselves
+        if let Err(e) = self.build_action_map() {
+            log::warn!("Invalid custom board keys: {}. Ignoring them.", e);
+            for board in &mut self.board.custom {
+                board.keys.clear();
+            }
+        }
+    }
+
+    /// Builds the keybinding action map, including the keys bound to palette colors
+    /// and custom boards.
+    ///
+    /// # Errors
+    /// Returns an error if any keybinding is invalid or bound to more than one action.
+    pub fn build_action_map(&self) -> Result<HashMap<KeyBinding, Action>, String> {
+        self.keybindings
+            .build_action_map_with(&self.palette.colors, &self.board.custom)
     }
 
     /// Returns the path to the configuration file.
@@ -491,7 +830,11 @@ impl Config {
         let config_str = fs::read_to_string(&config_path)
             .with_context(|| format!("Failed to read config from {}", config_path.display()))?;
 
-        let mut config: Config = toml::from_str(&config_str)
+        let mut table: toml::Table = toml::from_str(&config_str)
+            .with_context(|| format!("Failed to parse config from {}", config_path.display()))?;
+        migration::migrate_color_keybindings(&mut table);
+        let mut config: Config = table
+            .try_into()
             .with_context(|| format!("Failed to parse config from {}", config_path.display()))?;
 
         // Validate and clamp values to acceptable ranges
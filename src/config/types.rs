@@ -1,6 +1,7 @@
 //! Configuration type definitions.
 
-use super::enums::{ColorSpec, StatusPosition};
+use super::enums::{ColorSpec, ImageFit, StatusPosition, TextAlign, TextEffectStyle};
+use crate::draw::{Color, TextEffect};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -38,9 +39,76 @@ pub struct DrawingConfig {
     #[serde(default = "default_font_style")]
     pub font_style: String,
 
-    /// Enable semi-transparent background box behind text for better contrast
-    #[serde(default = "default_text_background")]
-    pub text_background_enabled: bool,
+    /// Effect drawn around text for readability: "none", "background", "outline" or "shadow"
+    #[serde(default)]
+    pub text_effect: TextEffectStyle,
+
+    /// Effect color as RGBA (0.0-1.0); leave unset for black or white, whichever
+    /// contrasts with the text color
+    #[serde(default)]
+    pub text_effect_color: Option<[f64; 4]>,
+
+    /// Outline width in pixels for the "outline" effect (valid range: 0.5 - 10.0)
+    #[serde(default = "default_text_outline_width")]
+    pub text_outline_width: f64,
+
+    /// Shadow offset in pixels for the "shadow" effect (valid range: 0.5 - 20.0)
+    #[serde(default = "default_text_shadow_offset")]
+    pub text_shadow_offset: f64,
+
+    /// Padding in pixels around the text for the "background" effect (valid range: 0.0 - 40.0)
+    #[serde(default = "default_text_background_padding")]
+    pub text_background_padding: f64,
+
+    /// Deprecated: use `text_effect = "background"` instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_background_enabled: Option<bool>,
+
+    /// Alignment of multi-line text: "left", "center" or "right"
+    #[serde(default)]
+    pub text_align: TextAlign,
+
+    /// Maximum text width in pixels before lines wrap (valid range: 50.0 - 4000.0)
+    /// Leave unset to only break lines at Shift+Enter
+    #[serde(default)]
+    pub text_wrap_width: Option<f64>,
+
+    /// Line spacing multiplier for multi-line text (valid range: 0.5 - 3.0)
+    #[serde(default = "default_text_line_spacing")]
+    pub text_line_spacing: f64,
+
+    /// Number of undo steps kept per board (valid range: 0 - 1000)
+    #[serde(default = "default_undo_history")]
+    pub undo_history: usize,
+
+    /// Names of the layers every page starts with, bottom first
+    /// New shapes go to the bottom layer until another one is made active
+    #[serde(default = "default_layers")]
+    pub layers: Vec<String>,
+}
+
+impl DrawingConfig {
+    /// Builds the text effect described by the `text_effect*` settings.
+    pub fn text_effect(&self) -> TextEffect {
+        let color = self
+            .text_effect_color
+            .map(|[r, g, b, a]| Color { r, g, b, a });
+        match self.text_effect {
+            TextEffectStyle::None => TextEffect::None,
+            TextEffectStyle::Background => TextEffect::Background {
+                color,
+                padding: self.text_background_padding,
+            },
+            TextEffectStyle::Outline => TextEffect::Outline {
+                color,
+                width: self.text_outline_width,
+            },
+            TextEffectStyle::Shadow => TextEffect::Shadow {
+                color,
+                offset: self.text_shadow_offset,
+            },
+        }
+    }
 }
 
 impl Default for DrawingConfig {
@@ -52,7 +120,17 @@ impl Default for DrawingConfig {
             font_family: default_font_family(),
             font_weight: default_font_weight(),
             font_style: default_font_style(),
-            text_background_enabled: default_text_background(),
+            text_effect: TextEffectStyle::default(),
+            text_effect_color: None,
+            text_outline_width: default_text_outline_width(),
+            text_shadow_offset: default_text_shadow_offset(),
+            text_background_padding: default_text_background_padding(),
+            text_background_enabled: None,
+            text_align: TextAlign::default(),
+            text_wrap_width: None,
+            text_line_spacing: default_text_line_spacing(),
+            undo_history: default_undo_history(),
+            layers: default_layers(),
         }
     }
 }
@@ -252,8 +330,30 @@ fn default_font_style() -> String {
     "normal".to_string()
 }
 
-fn default_text_background() -> bool {
-    false
+fn default_text_outline_width() -> f64 {
+    1.0
+}
+
+fn default_text_shadow_offset() -> f64 {
+    2.0
+}
+
+fn default_text_background_padding() -> f64 {
+    5.0
+}
+
+fn default_text_line_spacing() -> f64 {
+    1.0
+}
+
+fn default_undo_history() -> usize {
+    crate::draw::history::DEFAULT_HISTORY_LIMIT
+}
+
+fn default_layers() -> Vec<String> {
+    crate::draw::frame::DEFAULT_LAYER_NAMES
+        .map(String::from)
+        .to_vec()
 }
 
 fn default_arrow_length() -> f64 {
@@ -340,7 +440,7 @@ pub struct BoardConfig {
     #[serde(default = "default_board_enabled")]
     pub enabled: bool,
 
-    /// Default mode on startup (transparent, whiteboard, or blackboard)
+    /// Default mode on startup (transparent, whiteboard, blackboard, or a custom board name)
     #[serde(default = "default_board_mode")]
     pub default_mode: String,
 
@@ -363,6 +463,75 @@ pub struct BoardConfig {
     /// Automatically adjust pen color when entering board modes
     #[serde(default = "default_board_auto_adjust")]
     pub auto_adjust_pen: bool,
+
+    /// Additional boards defined by the user
+    #[serde(default)]
+    pub custom: Vec<CustomBoardConfig>,
+
+    /// Background images of boards
+    #[serde(default)]
+    pub backgrounds: Vec<BoardBackgroundConfig>,
+}
+
+/// A background image for one board, configured as a `[[board.backgrounds]]` entry.
+///
+/// The image is drawn over the board's background color, below the shapes.
+///
+/// # Example
+///
+/// ```toml
+/// [[board.backgrounds]]
+/// board = "whiteboard"
+/// image = "~/Templates/retro-grid.png"
+/// fit = "fill"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BoardBackgroundConfig {
+    /// Name of the board (built-in or custom)
+    pub board: String,
+
+    /// Path to a PNG or JPEG image (supports ~ expansion)
+    pub image: String,
+
+    /// How the image is scaled: fit, fill, or center
+    #[serde(default)]
+    pub fit: ImageFit,
+}
+
+/// A user-defined board, configured as a `[[board.custom]]` entry.
+///
+/// Each custom board keeps its own pages like the built-in boards. A background
+/// alpha below 1.0 tints the screen instead of hiding it.
+///
+/// # Example
+///
+/// ```toml
+/// [[board.custom]]
+/// name = "greenboard"
+/// background_color = [0.1, 0.3, 0.2]
+/// pen_color = [1.0, 1.0, 1.0]
+/// keys = ["Ctrl+Shift+G"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CustomBoardConfig {
+    /// Board name, shown in the status bar and accepted by `--mode`
+    pub name: String,
+
+    /// Background color [R, G, B] (0.0-1.0 range)
+    pub background_color: [f64; 3],
+
+    /// Background opacity (0.0-1.0); below 1.0 the screen shows through
+    #[serde(default = "default_custom_board_alpha")]
+    pub background_alpha: f64,
+
+    /// Pen color to switch to when entering the board [R, G, B] (0.0-1.0 range);
+    /// the pen is left alone if unset
+    #[serde(default)]
+    pub pen_color: Option<[f64; 3]>,
+
+    /// Keybindings that toggle the board (e.g., `["Ctrl+Shift+G"]`)
+    #[serde(default)]
+    pub keys: Vec<String>,
 }
 
 impl Default for BoardConfig {
@@ -375,6 +544,8 @@ impl Default for BoardConfig {
             whiteboard_pen_color: default_whiteboard_pen_color(),
             blackboard_pen_color: default_blackboard_pen_color(),
             auto_adjust_pen: default_board_auto_adjust(),
+            custom: Vec::new(),
+            backgrounds: Vec::new(),
         }
     }
 }
@@ -408,6 +579,10 @@ fn default_board_auto_adjust() -> bool {
     true
 }
 
+fn default_custom_board_alpha() -> f64 {
+    1.0
+}
+
 /// Screenshot capture configuration.
 ///
 /// Controls the behavior of screenshot capture features including file saving,
@@ -467,3 +642,117 @@ fn default_capture_format() -> String {
 fn default_capture_clipboard() -> bool {
     true
 }
+
+/// Session persistence settings.
+///
+/// Each time the overlay opens (including every daemon toggle) it starts
+/// from a blank canvas unless persistence is enabled.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionConfig {
+    /// Save every board, the pen color, thickness and board mode when the
+    /// overlay closes, and restore them when it opens again
+    #[serde(default = "default_session_persist")]
+    pub persist: bool,
+
+    /// Periodically save the open session to a recovery journal, so drawings
+    /// survive a crash of the overlay or the compositor
+    #[serde(default = "default_session_autosave")]
+    pub autosave: bool,
+
+    /// Seconds between autosaves (valid range: 5 - 3600)
+    #[serde(default = "default_session_autosave_interval")]
+    pub autosave_interval_secs: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            persist: default_session_persist(),
+            autosave: default_session_autosave(),
+            autosave_interval_secs: default_session_autosave_interval(),
+        }
+    }
+}
+
+fn default_session_persist() -> bool {
+    false
+}
+
+fn default_session_autosave() -> bool {
+    true
+}
+
+fn default_session_autosave_interval() -> u64 {
+    30
+}
+
+/// Color palette configuration.
+///
+/// Defines the named colors that can be selected with keybindings, plus how many
+/// recently used colors are remembered and shown as swatches in the status bar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PaletteConfig {
+    /// Palette entries, each with a name, a color and the keys that select it
+    #[serde(default = "default_palette_colors")]
+    pub colors: Vec<PaletteEntry>,
+
+    /// Number of recently used colors to remember (valid range: 0 - 16, 0 disables the history)
+    #[serde(default = "default_recent_colors")]
+    pub recent_colors: usize,
+}
+
+impl Default for PaletteConfig {
+    fn default() -> Self {
+        Self {
+            colors: default_palette_colors(),
+            recent_colors: default_recent_colors(),
+        }
+    }
+}
+
+/// A single named palette color.
+///
+/// # Example TOML
+/// ```toml
+/// [[palette.colors]]
+/// name = "teal"
+/// color = [0, 128, 128]
+/// keys = ["Alt+T"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PaletteEntry {
+    /// Display name for the color
+    pub name: String,
+
+    /// Color value - a named color or an RGB array like `[255, 0, 0]`
+    pub color: ColorSpec,
+
+    /// Keybindings that select this color (e.g., `["R"]`, `["Alt+1"]`)
+    #[serde(default)]
+    pub keys: Vec<String>,
+}
+
+// Palette defaults
+fn default_palette_colors() -> Vec<PaletteEntry> {
+    [
+        ("red", "R"),
+        ("green", "G"),
+        ("blue", "B"),
+        ("yellow", "Y"),
+        ("orange", "O"),
+        ("pink", "P"),
+        ("white", "W"),
+        ("black", "K"), // K for blacK, since B is blue
+    ]
+    .into_iter()
+    .map(|(name, key)| PaletteEntry {
+        name: name.to_string(),
+        color: ColorSpec::Name(name.to_string()),
+        keys: vec![key.to_string()],
+    })
+    .collect()
+}
+
+fn default_recent_colors() -> usize {
+    8
+}
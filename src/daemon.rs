@@ -1,8 +1,8 @@
 /// Daemon mode implementation: background service with toggle activation
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result, anyhow};
 use ksni::TrayMethods;
 use log::{debug, error, info, warn};
-use signal_hook::consts::signal::{SIGINT, SIGTERM, SIGUSR1};
+use signal_hook::consts::signal::{SIGINT, SIGTERM, SIGUSR1, SIGUSR2};
 use signal_hook::iterator::Signals;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
@@ -12,7 +12,7 @@ use std::thread;
 use std::thread::JoinHandle;
 use std::time::Duration;
 
-use crate::backend;
+use crate::backend::{self, OverlayControl};
 use crate::legacy;
 
 /// Overlay state for daemon mode
@@ -22,8 +22,8 @@ pub enum OverlayState {
     Visible, // Overlay active, capturing input
 }
 
-/// Daemon state manager
-type BackendRunner = dyn Fn(Option<String>) -> Result<()> + Send + Sync;
+/// Runs the overlay with a board mode and the control other threads use to reach it
+type BackendRunner = dyn Fn(Option<String>, Arc<OverlayControl>) -> Result<()> + Send + Sync;
 
 const TRAY_START_TIMEOUT: Duration = Duration::from_secs(5);
 
@@ -31,6 +31,7 @@ pub struct Daemon {
     overlay_state: OverlayState,
     should_quit: Arc<AtomicBool>,
     toggle_requested: Arc<AtomicBool>,
+    overlay_control: Arc<OverlayControl>,
     initial_mode: Option<String>,
     backend_runner: Arc<BackendRunner>,
     tray_thread: Option<JoinHandle<()>>,
@@ -38,6 +39,7 @@ pub struct Daemon {
 
 pub(crate) struct WayscriberTray {
     toggle_flag: Arc<AtomicBool>,
+    overlay_control: Arc<OverlayControl>,
     quit_flag: Arc<AtomicBool>,
     configurator_binary: String,
 }
@@ -45,19 +47,25 @@ pub(crate) struct WayscriberTray {
 impl WayscriberTray {
     fn new(
         toggle_flag: Arc<AtomicBool>,
+        overlay_control: Arc<OverlayControl>,
         quit_flag: Arc<AtomicBool>,
         configurator_binary: String,
     ) -> Self {
         Self {
             toggle_flag,
+            overlay_control,
             quit_flag,
             configurator_binary,
         }
     }
 
     #[cfg(test)]
-    fn new_for_tests(toggle_flag: Arc<AtomicBool>, quit_flag: Arc<AtomicBool>) -> Self {
-        Self::new(toggle_flag, quit_flag, "true".into())
+    fn new_for_tests(
+        toggle_flag: Arc<AtomicBool>,
+        overlay_control: Arc<OverlayControl>,
+        quit_flag: Arc<AtomicBool>,
+    ) -> Self {
+        Self::new(toggle_flag, overlay_control, quit_flag, "true".into())
     }
 }
 
@@ -167,6 +175,15 @@ impl ksni::Tray for WayscriberTray {
                 ..Default::default()
             }
             .into(),
+            StandardItem {
+                label: "Toggle Click-Through".to_string(),
+                icon_name: "input-mouse".into(),
+                activate: Box::new(|this: &mut Self| {
+                    this.overlay_control.toggle_click_through();
+                }),
+                ..Default::default()
+            }
+            .into(),
             StandardItem {
                 label: "Open Configurator".to_string(),
                 icon_name: "preferences-desktop".into(),
@@ -191,32 +208,32 @@ impl ksni::Tray for WayscriberTray {
 }
 
 impl Daemon {
-    pub fn new(initial_mode: Option<String>) -> Self {
-        Self::with_backend_runner_internal(
-            initial_mode,
-            Arc::new(|mode| backend::run_wayland(mode)),
-        )
+    pub fn new(initial_mode: Option<String>) -> Result<Self> {
+        Self::with_backend_runner_internal(initial_mode, Arc::new(backend::run_wayland))
     }
 
     fn with_backend_runner_internal(
         initial_mode: Option<String>,
         backend_runner: Arc<BackendRunner>,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        let overlay_control =
+            OverlayControl::new().context("Failed to create overlay control socket")?;
+        Ok(Self {
             overlay_state: OverlayState::Hidden,
             should_quit: Arc::new(AtomicBool::new(false)),
             toggle_requested: Arc::new(AtomicBool::new(false)),
+            overlay_control: Arc::new(overlay_control),
             initial_mode,
             backend_runner,
             tray_thread: None,
-        }
+        })
     }
 
     #[cfg(test)]
     pub fn with_backend_runner(
         initial_mode: Option<String>,
         backend_runner: Arc<BackendRunner>,
-    ) -> Self {
+    ) -> Result<Self> {
         Self::with_backend_runner_internal(initial_mode, backend_runner)
     }
 
@@ -225,12 +242,14 @@ impl Daemon {
         info!("Starting wayscriber daemon");
         info!("Send SIGUSR1 to toggle overlay (e.g., pkill -SIGUSR1 wayscriber)");
         info!("Configure Hyprland: bind = SUPER, D, exec, pkill -SIGUSR1 wayscriber");
+        info!("Send SIGUSR2 to toggle click-through while the overlay is visible");
 
         // Set up signal handling
-        let mut signals = Signals::new([SIGUSR1, SIGTERM, SIGINT])
+        let mut signals = Signals::new([SIGUSR1, SIGUSR2, SIGTERM, SIGINT])
             .context("Failed to register signal handler")?;
 
         let toggle_flag = self.toggle_requested.clone();
+        let overlay_control = self.overlay_control.clone();
         let quit_flag = self.should_quit.clone();
 
         // Spawn signal handler thread
@@ -247,6 +266,10 @@ impl Daemon {
                         // are visible to the thread that reads this flag
                         toggle_flag.store(true, Ordering::Release);
                     }
+                    SIGUSR2 => {
+                        info!("Received SIGUSR2 - toggling click-through");
+                        overlay_control.toggle_click_through();
+                    }
                     SIGTERM | SIGINT => {
                         info!(
                             "Received {} - initiating graceful shutdown",
@@ -265,8 +288,9 @@ impl Daemon {
 
         // Start system tray
         let tray_toggle = self.toggle_requested.clone();
+        let tray_control = self.overlay_control.clone();
         let tray_quit = self.should_quit.clone();
-        let tray_handle = start_system_tray(tray_toggle, tray_quit)
+        let tray_handle = start_system_tray(tray_toggle, tray_control, tray_quit)
             .context("Failed to start system tray")?;
         self.tray_thread = Some(tray_handle);
 
@@ -331,7 +355,7 @@ impl Daemon {
         info!("Overlay state set to Visible");
 
         // Run the Wayland backend (this will block until overlay is closed)
-        let result = (self.backend_runner)(self.initial_mode.clone());
+        let result = (self.backend_runner)(self.initial_mode.clone(), self.overlay_control.clone());
 
         // When run_wayland returns, the overlay was closed
         self.overlay_state = OverlayState::Hidden;
@@ -364,13 +388,19 @@ impl Daemon {
 /// System tray implementation
 fn start_system_tray(
     toggle_flag: Arc<AtomicBool>,
+    overlay_control: Arc<OverlayControl>,
     quit_flag: Arc<AtomicBool>,
 ) -> Result<JoinHandle<()>> {
     let configurator_binary =
         legacy::configurator_override().unwrap_or_else(|| "wayscriber-configurator".to_string());
 
     let tray_quit_flag = quit_flag.clone();
-    let tray = WayscriberTray::new(toggle_flag, tray_quit_flag.clone(), configurator_binary);
+    let tray = WayscriberTray::new(
+        toggle_flag,
+        overlay_control,
+        tray_quit_flag.clone(),
+        configurator_binary,
+    );
     let (ready_tx, ready_rx) = mpsc::channel::<Result<()>>();
 
     info!("Creating tray service...");
@@ -382,9 +412,12 @@ fn start_system_tray(
             Ok(runtime) => runtime,
             Err(e) => {
                 warn!("Failed to create Tokio runtime for system tray: {}", e);
-                report_tray_readiness(&ready_thread_tx, Err(anyhow!(
-                    "Failed to create Tokio runtime for system tray: {e}"
-                )));
+                report_tray_readiness(
+                    &ready_thread_tx,
+                    Err(anyhow!(
+                        "Failed to create Tokio runtime for system tray: {e}"
+                    )),
+                );
                 return;
             }
         };
@@ -452,8 +485,12 @@ mod tests {
     use ksni::{Tray, menu::MenuItem};
     use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 
+    fn control() -> Arc<OverlayControl> {
+        Arc::new(OverlayControl::new().unwrap())
+    }
+
     fn runner_counter(count: Arc<AtomicUsize>) -> Arc<BackendRunner> {
-        Arc::new(move |mode: Option<String>| -> Result<()> {
+        Arc::new(move |mode: Option<String>, _| -> Result<()> {
             assert_eq!(mode.as_deref(), Some("whiteboard"));
             count.fetch_add(1, AtomicOrdering::SeqCst);
             Ok(())
@@ -464,7 +501,7 @@ mod tests {
     fn toggle_overlay_invokes_backend_when_hidden() {
         let counter = Arc::new(AtomicUsize::new(0));
         let runner = runner_counter(counter.clone());
-        let mut daemon = Daemon::with_backend_runner(Some("whiteboard".into()), runner);
+        let mut daemon = Daemon::with_backend_runner(Some("whiteboard".into()), runner).unwrap();
 
         daemon.toggle_overlay().unwrap();
         assert_eq!(counter.load(AtomicOrdering::SeqCst), 1);
@@ -473,8 +510,8 @@ mod tests {
 
     #[test]
     fn hide_overlay_is_idempotent() {
-        let runner = Arc::new(|_: Option<String>| Ok(())) as Arc<BackendRunner>;
-        let mut daemon = Daemon::with_backend_runner(None, runner);
+        let runner = Arc::new(|_: Option<String>, _| Ok(())) as Arc<BackendRunner>;
+        let mut daemon = Daemon::with_backend_runner(None, runner).unwrap();
         daemon.hide_overlay().unwrap();
         assert_eq!(daemon.test_state(), OverlayState::Hidden);
 
@@ -485,12 +522,12 @@ mod tests {
 
     fn activate_menu_item(tray: &mut WayscriberTray, label: &str) {
         for item in tray.menu() {
-            if let MenuItem::Standard(standard) = item {
-                if standard.label.contains(label) {
-                    let activate = standard.activate;
-                    activate(tray);
-                    return;
-                }
+            if let MenuItem::Standard(standard) = item
+                && standard.label.contains(label)
+            {
+                let activate = standard.activate;
+                activate(tray);
+                return;
             }
         }
         panic!("Menu item '{label}' not found");
@@ -500,17 +537,39 @@ mod tests {
     fn tray_toggle_action_sets_flag() {
         let toggle = Arc::new(AtomicBool::new(false));
         let quit = Arc::new(AtomicBool::new(false));
-        let mut tray = WayscriberTray::new_for_tests(toggle.clone(), quit);
+        let mut tray = WayscriberTray::new_for_tests(toggle.clone(), control(), quit);
 
         activate_menu_item(&mut tray, "Toggle Overlay");
         assert!(toggle.load(Ordering::SeqCst));
     }
 
+    #[test]
+    fn tray_click_through_action_requests_toggle() {
+        let control = control();
+        let mut tray =
+            WayscriberTray::new_for_tests(Default::default(), control.clone(), Default::default());
+
+        activate_menu_item(&mut tray, "Toggle Click-Through");
+        assert!(control.take_click_through_toggle());
+    }
+
+    #[test]
+    fn overlay_control_is_passed_to_backend() {
+        let runner = Arc::new(|_: Option<String>, control: Arc<OverlayControl>| {
+            assert!(control.take_click_through_toggle());
+            Ok(())
+        }) as Arc<BackendRunner>;
+        let mut daemon = Daemon::with_backend_runner(None, runner).unwrap();
+        daemon.overlay_control.toggle_click_through();
+        daemon.toggle_overlay().unwrap();
+        assert!(!daemon.overlay_control.take_click_through_toggle());
+    }
+
     #[test]
     fn tray_quit_action_sets_quit_flag() {
         let toggle = Arc::new(AtomicBool::new(false));
         let quit = Arc::new(AtomicBool::new(false));
-        let mut tray = WayscriberTray::new_for_tests(toggle, quit.clone());
+        let mut tray = WayscriberTray::new_for_tests(toggle, control(), quit.clone());
 
         activate_menu_item(&mut tray, "Quit");
         assert!(quit.load(Ordering::SeqCst));
@@ -0,0 +1,362 @@
+//! Native annotation documents.
+//!
+//! A [`Document`] holds the shapes of every board as versioned JSON, so
+//! annotation sets can be saved, reopened and shared. The saved session (see
+//! [`crate::session`]) uses the same format.
+//!
+//! Every file carries a `version`. Files written by older releases are
+//! upgraded step by step in [`migrate`] before being parsed, so only the
+//! current layout needs a Rust type.
+
+use anyhow::{Context, Result, bail};
+use schemars::{JsonSchema, schema_for};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::draw::{Color, FrameData};
+use crate::input::{BoardMode, InputState};
+
+/// Version of the document layout written by this build.
+///
+/// - 1: the original session file (`mode`, pen settings at the top level,
+///   boards without groups); it has no `version` field
+/// - 2: adds `version`, moves pen settings into `pen`, saves shape groups
+/// - 3: boards hold a list of `pages` instead of a single set of shapes
+/// - 4: pages save their `layers`, the layer of each shape and the active
+///   layer; older pages need no upgrade and load with every shape on the
+///   bottom layer
+pub const DOCUMENT_VERSION: u32 = 4;
+
+/// File extension of saved documents.
+pub const DOCUMENT_EXTENSION: &str = "wayscriber";
+
+/// Where a save request writes the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveMode {
+    /// Overwrite the open document, or create a new file if none is open
+    Current,
+    /// Always create a new file, which becomes the open document
+    NewFile,
+}
+
+/// Pen settings saved with a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PenSettings {
+    /// Pen color
+    pub color: Color,
+    /// Pen thickness in pixels
+    pub thickness: f64,
+    /// Color to return to when leaving the board (see `auto_adjust_pen`)
+    #[serde(default)]
+    pub previous_color: Option<Color>,
+}
+
+/// Pages of one board.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DocumentBoard {
+    /// Board the pages belong to
+    pub mode: BoardMode,
+    /// Shapes, groups and layers of each page, in page order
+    pub pages: Vec<FrameData>,
+    /// Index of the page that was showing
+    #[serde(default)]
+    pub current_page: usize,
+}
+
+/// A saved set of annotations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Document {
+    /// Layout version (see [`DOCUMENT_VERSION`])
+    pub version: u32,
+    /// Board that was active
+    pub active_board: BoardMode,
+    /// Pen settings, saved with sessions but not with shared documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pen: Option<PenSettings>,
+    /// Every board that was created
+    pub boards: Vec<DocumentBoard>,
+}
+
+impl Document {
+    /// Takes a snapshot of the boards, and of the pen settings if `include_pen`.
+    pub fn capture(state: &InputState, include_pen: bool) -> Self {
+        Self {
+            version: DOCUMENT_VERSION,
+            active_board: state.board_mode().clone(),
+            pen: include_pen.then_some(PenSettings {
+                color: state.current_color,
+                thickness: state.current_thickness,
+                previous_color: state.board_previous_color,
+            }),
+            boards: state
+                .canvas_set
+                .boards()
+                .map(|(mode, board)| DocumentBoard {
+                    mode: mode.clone(),
+                    pages: board.pages().iter().map(|page| page.to_data()).collect(),
+                    current_page: board.current_index(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Puts the saved boards (and pen settings, if any) back into `state`.
+    ///
+    /// Boards missing from the document are emptied. The saved board is made
+    /// active only if `restore_mode` is set, so an explicitly requested mode
+    /// (e.g. `--mode`) wins. Restored shapes start with an empty undo history.
+    pub fn restore(mut self, state: &mut InputState, restore_mode: bool) {
+        // Custom boards are matched to the config ignoring case
+        for board in &mut self.boards {
+            board.mode = board.mode.configured(&state.board_config);
+        }
+        self.active_board = self.active_board.configured(&state.board_config);
+
+        let active_mode = state.canvas_set.active_mode().clone();
+        let emptied: Vec<BoardMode> = state
+            .canvas_set
+            .boards()
+            .map(|(mode, _)| mode)
+            .filter(|mode| !self.boards.iter().any(|board| &board.mode == *mode))
+            .cloned()
+            .collect();
+        for mode in emptied {
+            state.canvas_set.load_board(mode, Vec::new(), 0);
+        }
+        for board in self.boards {
+            state
+                .canvas_set
+                .load_board(board.mode, board.pages, board.current_page);
+        }
+
+        if restore_mode {
+            state.canvas_set.switch_mode(self.active_board.clone());
+        }
+        if let Some(pen) = self.pen {
+            if restore_mode {
+                state.board_previous_color = pen.previous_color;
+                state.current_color = pen.color;
+            } else if self.active_board == active_mode {
+                state.current_color = pen.color;
+            }
+            state.current_thickness = pen.thickness;
+        }
+        state.needs_redraw = true;
+    }
+
+    /// Returns how many shapes the document holds across all boards.
+    pub fn shape_count(&self) -> usize {
+        self.boards
+            .iter()
+            .flat_map(|board| &board.pages)
+            .map(|page| page.shapes.len())
+            .sum()
+    }
+
+    /// Parses a document, upgrading older versions first.
+    pub fn from_json(data: &[u8]) -> Result<Self> {
+        let value: Value = serde_json::from_slice(data).context("Document is not valid JSON")?;
+        let document = serde_json::from_value(migrate(value)?)?;
+        Ok(document)
+    }
+
+    /// Serializes the document as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec_pretty(self).context("Failed to serialize document")
+    }
+
+    /// Generates a JSON Schema describing the current document layout.
+    #[allow(dead_code)]
+    pub fn json_schema() -> Value {
+        serde_json::to_value(schema_for!(Document))
+            .expect("serializing document schema should succeed")
+    }
+}
+
+/// Upgrades a parsed document of any known version to [`DOCUMENT_VERSION`].
+pub fn migrate(mut value: Value) -> Result<Value> {
+    let Some(object) = value.as_object_mut() else {
+        bail!("Document is not a JSON object");
+    };
+    let version = match object.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .context("Document version is not a number")?,
+    };
+    if version > u64::from(DOCUMENT_VERSION) {
+        bail!(
+            "Document version {} is newer than this wayscriber supports ({})",
+            version,
+            DOCUMENT_VERSION
+        );
+    }
+
+    if version < 2 {
+        if let Some(mode) = object.remove("mode") {
+            object.insert("active_board".to_string(), mode);
+        }
+        let color = object.remove("color");
+        let thickness = object.remove("thickness");
+        let previous_color = object.remove("previous_color");
+        if let (Some(color), Some(thickness)) = (color, thickness) {
+            let mut pen = serde_json::Map::new();
+            pen.insert("color".to_string(), color);
+            pen.insert("thickness".to_string(), thickness);
+            if let Some(previous_color) = previous_color {
+                pen.insert("previous_color".to_string(), previous_color);
+            }
+            object.insert("pen".to_string(), Value::Object(pen));
+        }
+    }
+
+    if version < 3
+        && let Some(boards) = object.get_mut("boards").and_then(Value::as_array_mut)
+    {
+        for board in boards.iter_mut().filter_map(Value::as_object_mut) {
+            let mut page = serde_json::Map::new();
+            for key in ["shapes", "groups"] {
+                if let Some(value) = board.remove(key) {
+                    page.insert(key.to_string(), value);
+                }
+            }
+            board.insert("pages".to_string(), Value::Array(vec![Value::Object(page)]));
+        }
+    }
+
+    object.insert("version".to_string(), Value::from(DOCUMENT_VERSION));
+    Ok(value)
+}
+
+/// Loads a document from a file.
+pub fn load(path: &Path) -> Result<Document> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Document::from_json(&data).with_context(|| format!("Failed to open {}", path.display()))
+}
+
+/// Saves a document, replacing any previous file at `path`.
+///
+/// The file is written next to its destination and renamed into place, so
+/// a crash mid-write never leaves a truncated document behind.
+pub fn save(path: &Path, document: &Document) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let data = document.to_json()?;
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    fs::write(&temp_path, data)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    fs::rename(&temp_path, path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::color::RED;
+    use crate::draw::{Layer, Shape};
+    use serde_json::json;
+
+    fn rect(x: i32) -> Shape {
+        Shape::Rect {
+            x,
+            y: 10,
+            w: 20,
+            h: 20,
+            color: RED,
+            thick: 2.0,
+            rotation: 0.0,
+        }
+    }
+
+    #[test]
+    fn version_one_session_files_are_migrated() {
+        let v1 = json!({
+            "mode": "whiteboard",
+            "color": {"r": 0.0, "g": 0.0, "b": 0.0, "a": 1.0},
+            "thickness": 4.0,
+            "previous_color": {"r": 1.0, "g": 0.0, "b": 0.0, "a": 1.0},
+            "boards": [{"mode": "transparent", "shapes": [serde_json::to_value(rect(0)).unwrap()]}]
+        });
+
+        let document = Document::from_json(v1.to_string().as_bytes()).unwrap();
+        assert_eq!(document.version, DOCUMENT_VERSION);
+        assert_eq!(document.active_board, BoardMode::Whiteboard);
+        let pen = document.pen.unwrap();
+        assert_eq!(pen.thickness, 4.0);
+        assert_eq!(pen.previous_color, Some(RED));
+        assert_eq!(document.boards[0].pages.len(), 1);
+        assert_eq!(document.boards[0].pages[0].shapes, vec![rect(0)]);
+        assert!(document.boards[0].pages[0].groups.is_empty());
+    }
+
+    #[test]
+    fn version_two_boards_become_single_pages() {
+        let v2 = json!({
+            "version": 2,
+            "active_board": "transparent",
+            "boards": [{"mode": "transparent", "shapes": [serde_json::to_value(rect(0)).unwrap()], "groups": []}]
+        });
+
+        let document = Document::from_json(v2.to_string().as_bytes()).unwrap();
+        let board = &document.boards[0];
+        assert_eq!(board.current_page, 0);
+        assert_eq!(board.pages.len(), 1);
+        assert_eq!(board.pages[0].shapes, vec![rect(0)]);
+        assert_eq!(document.shape_count(), 1);
+    }
+
+    #[test]
+    fn documents_round_trip_through_a_file() {
+        let document = Document {
+            version: DOCUMENT_VERSION,
+            active_board: BoardMode::Blackboard,
+            pen: None,
+            boards: vec![DocumentBoard {
+                mode: BoardMode::Blackboard,
+                pages: vec![
+                    FrameData {
+                        shapes: vec![rect(0), rect(40)],
+                        groups: vec![vec![0, 1]],
+                        layers: vec![Layer::new("Slide"), Layer::new("Notes")],
+                        shape_layers: vec![0, 1],
+                        active_layer: 1,
+                    },
+                    FrameData::default(),
+                ],
+                current_page: 1,
+            }],
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes").join("slides.wayscriber");
+        save(&path, &document).unwrap();
+        assert_eq!(load(&path).unwrap(), document);
+        assert!(load(&dir.path().join("missing.wayscriber")).is_err());
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let future =
+            json!({"version": DOCUMENT_VERSION + 1, "active_board": "transparent", "boards": []});
+        let err = Document::from_json(future.to_string().as_bytes()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("newer than this wayscriber supports")
+        );
+    }
+
+    #[test]
+    fn schema_describes_the_document() {
+        let schema = Document::json_schema();
+        assert!(schema["properties"]["version"].is_object());
+        assert!(schema["properties"]["boards"].is_object());
+    }
+}
@@ -0,0 +1,273 @@
+//! Board backgrounds: a fill color and an optional image.
+//!
+//! Images are decoded once into cairo surfaces and shared between the
+//! overlay and exports, so switching boards or exporting never reloads them.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+use anyhow::{Context, Result};
+
+use super::color::Color;
+use crate::config::{BoardConfig, ImageFit};
+use crate::input::BoardMode;
+use crate::util;
+
+/// A decoded background image.
+#[derive(Debug)]
+pub struct BackgroundImage {
+    surface: cairo::ImageSurface,
+}
+
+impl BackgroundImage {
+    /// Decodes a PNG or JPEG file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let image = image::ImageReader::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?
+            .with_guessed_format()
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .decode()
+            .with_context(|| format!("Failed to decode {}", path.display()))?
+            .into_rgba8();
+
+        let (width, height) = image.dimensions();
+        let mut surface =
+            cairo::ImageSurface::create(cairo::Format::ARgb32, width as i32, height as i32)
+                .context("Image is too large")?;
+        let stride = surface.stride() as usize;
+        {
+            let mut data = surface.data().context("Failed to access image surface")?;
+            for (y, row) in image.rows().enumerate() {
+                for (x, pixel) in row.enumerate() {
+                    // Cairo expects premultiplied, native-endian ARGB
+                    let [r, g, b, a] = pixel.0.map(u32::from);
+                    let premultiply = |c: u32| (c * a + 127) / 255;
+                    let argb =
+                        (a << 24) | (premultiply(r) << 16) | (premultiply(g) << 8) | premultiply(b);
+                    let offset = y * stride + x * 4;
+                    data[offset..offset + 4].copy_from_slice(&argb.to_ne_bytes());
+                }
+            }
+        }
+        surface.mark_dirty();
+        Ok(Self { surface })
+    }
+
+    /// Returns the image size in pixels.
+    pub fn size(&self) -> (f64, f64) {
+        (self.surface.width() as f64, self.surface.height() as f64)
+    }
+
+    /// Encodes the image as PNG, for embedding in exports.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.surface
+            .write_to_png(&mut data)
+            .context("Failed to encode background image")?;
+        Ok(data)
+    }
+
+    /// Returns where the image goes on a `width` x `height` area, as
+    /// `(x, y, scale)` of its top-left corner.
+    pub fn placement(&self, fit: ImageFit, width: f64, height: f64) -> (f64, f64, f64) {
+        let (image_width, image_height) = self.size();
+        let scale = match fit {
+            ImageFit::Fit => (width / image_width).min(height / image_height),
+            ImageFit::Fill => (width / image_width).max(height / image_height),
+            ImageFit::Center => 1.0,
+        };
+        (
+            (width - image_width * scale) / 2.0,
+            (height - image_height * scale) / 2.0,
+            scale,
+        )
+    }
+
+    /// Paints the image onto a `width` x `height` area.
+    pub fn paint(
+        &self,
+        ctx: &cairo::Context,
+        fit: ImageFit,
+        width: f64,
+        height: f64,
+    ) -> Result<(), cairo::Error> {
+        let (x, y, scale) = self.placement(fit, width, height);
+        ctx.save()?;
+        ctx.translate(x, y);
+        ctx.scale(scale, scale);
+        ctx.set_source_surface(&self.surface, 0.0, 0.0)?;
+        let result = ctx.paint();
+        ctx.restore()?;
+        result
+    }
+}
+
+/// Everything drawn behind a board's shapes.
+#[derive(Debug, Clone, Default)]
+pub struct BoardBackground {
+    /// Fill color (`None` leaves the board transparent)
+    pub color: Option<Color>,
+    /// Image drawn over the fill color, and how it is scaled
+    pub image: Option<(Rc<BackgroundImage>, ImageFit)>,
+}
+
+impl PartialEq for BoardBackground {
+    fn eq(&self, other: &Self) -> bool {
+        self.color == other.color
+            && match (&self.image, &other.image) {
+                (Some((a, a_fit)), Some((b, b_fit))) => Rc::ptr_eq(a, b) && a_fit == b_fit,
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+impl From<Option<Color>> for BoardBackground {
+    fn from(color: Option<Color>) -> Self {
+        Self { color, image: None }
+    }
+}
+
+/// Background images loaded so far, keyed by configured path.
+///
+/// The overlay preloads every configured image; other users load them on
+/// first use. A file that fails to load is logged once and the board falls
+/// back to its background color.
+#[derive(Debug, Default)]
+pub struct BackgroundImages {
+    loaded: HashMap<String, Option<Rc<BackgroundImage>>>,
+}
+
+impl BackgroundImages {
+    /// Creates an empty image store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a store with every image in `config` already decoded.
+    pub fn preload(config: &BoardConfig) -> Self {
+        let mut images = Self::new();
+        for background in &config.backgrounds {
+            images.image(&background.image);
+        }
+        images
+    }
+
+    /// Returns the image at a configured path, loading it if needed.
+    fn image(&mut self, path: &str) -> Option<Rc<BackgroundImage>> {
+        self.loaded
+            .entry(path.to_string())
+            .or_insert_with(|| {
+                BackgroundImage::load(&util::expand_tilde(path))
+                    .map(Rc::new)
+                    .inspect_err(|err| log::warn!("Background image unavailable: {:#}", err))
+                    .ok()
+            })
+            .clone()
+    }
+
+    /// Returns the background of `mode`, loading its image if needed.
+    pub fn background(&mut self, mode: &BoardMode, config: &BoardConfig) -> BoardBackground {
+        let color = mode.background_color(config);
+        let image = config
+            .backgrounds
+            .iter()
+            .find(|background| background.board.eq_ignore_ascii_case(mode.name()))
+            .and_then(|background| {
+                let image = self.image(&background.image)?;
+                Some((image, background.fit))
+            });
+        BoardBackground { color, image }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BoardBackgroundConfig;
+
+    fn write_png(path: &Path, width: i32, height: i32) {
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, width, height).unwrap();
+        let ctx = cairo::Context::new(&surface).unwrap();
+        ctx.set_source_rgb(1.0, 0.0, 0.0);
+        ctx.paint().unwrap();
+        drop(ctx);
+        let mut file = std::fs::File::create(path).unwrap();
+        surface.write_to_png(&mut file).unwrap();
+    }
+
+    #[test]
+    fn images_are_placed_by_fit_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wide.png");
+        write_png(&path, 400, 100);
+        let image = BackgroundImage::load(&path).unwrap();
+        assert_eq!(image.size(), (400.0, 100.0));
+
+        assert_eq!(
+            image.placement(ImageFit::Fit, 200.0, 200.0),
+            (0.0, 75.0, 0.5)
+        );
+        assert_eq!(
+            image.placement(ImageFit::Fill, 200.0, 200.0),
+            (-300.0, 0.0, 2.0)
+        );
+        assert_eq!(
+            image.placement(ImageFit::Center, 200.0, 200.0),
+            (-100.0, 50.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn jpeg_images_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photo.jpg");
+        image::RgbImage::from_pixel(16, 9, image::Rgb([200, 30, 30]))
+            .save(&path)
+            .unwrap();
+
+        let image = BackgroundImage::load(&path).unwrap();
+        assert_eq!(image.size(), (16.0, 9.0));
+    }
+
+    #[test]
+    fn images_are_loaded_once_per_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("grid.png");
+        write_png(&path, 8, 8);
+
+        let mut config = BoardConfig::default();
+        config.backgrounds.push(BoardBackgroundConfig {
+            board: "Whiteboard".to_string(),
+            image: path.display().to_string(),
+            fit: ImageFit::Fill,
+        });
+        config.backgrounds.push(BoardBackgroundConfig {
+            board: "blackboard".to_string(),
+            image: dir.path().join("missing.png").display().to_string(),
+            fit: ImageFit::Fit,
+        });
+
+        let mut images = BackgroundImages::preload(&config);
+        assert_eq!(images.loaded.len(), 2);
+        let first = images.background(&BoardMode::Whiteboard, &config);
+        let second = images.background(&BoardMode::Whiteboard, &config);
+        assert_eq!(first, second);
+        assert_eq!(images.loaded.len(), 2);
+        assert!(first.color.is_some());
+        assert_eq!(
+            first.image.as_ref().map(|(_, fit)| *fit),
+            Some(ImageFit::Fill)
+        );
+
+        // Missing files leave just the color
+        let blackboard = images.background(&BoardMode::Blackboard, &config);
+        assert!(blackboard.image.is_none());
+        assert!(blackboard.color.is_some());
+        assert_eq!(
+            images.background(&BoardMode::Transparent, &config),
+            BoardBackground::default()
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Offscreen raster cache of committed shapes.
+
+use super::background::BoardBackground;
+use super::damage::{Damage, DamageTracker};
+use super::frame::Frame;
+use super::render::{render_board_background, render_shape, render_shapes};
+
+/// Image of the board background and every committed shape of a frame.
+///
+/// Each render copies the cache to the screen and draws only transient
+/// content (the shape being drawn, selection, text input, UI) on top, so
+/// the cost of a frame no longer depends on how many shapes are committed.
+///
+/// [`ShapeCache::update`] asks the frame what changed since the cache was last
+/// updated and repaints just those regions, so adding, editing, deleting,
+/// undoing or clearing shapes each refresh the affected area. Switching to
+/// another board changes the background and repaints everything.
+#[derive(Debug)]
+pub struct ShapeCache {
+    surface: cairo::ImageSurface,
+    background: BoardBackground,
+    tracker: DamageTracker,
+    /// Shapes drawn by the last update
+    shapes_rendered: usize,
+}
+
+impl ShapeCache {
+    /// Creates an empty cache of the given size in pixels.
+    pub fn new(width: i32, height: i32) -> Result<Self, cairo::Error> {
+        Ok(Self {
+            surface: cairo::ImageSurface::create(cairo::Format::ARgb32, width, height)?,
+            background: BoardBackground::default(),
+            tracker: DamageTracker::new(),
+            shapes_rendered: 0,
+        })
+    }
+
+    /// Returns the cache width in pixels.
+    pub fn width(&self) -> i32 {
+        self.surface.width()
+    }
+
+    /// Returns the cache height in pixels.
+    pub fn height(&self) -> i32 {
+        self.surface.height()
+    }
+
+    /// Sets the color and image painted behind the shapes.
+    pub fn set_background(&mut self, background: BoardBackground) {
+        if self.background != background {
+            self.background = background;
+            self.invalidate();
+        }
+    }
+
+    /// Returns how many shapes the last update drew.
+    pub fn shapes_rendered(&self) -> usize {
+        self.shapes_rendered
+    }
+
+    /// Forces the next update to repaint the whole cache.
+    pub fn invalidate(&mut self) {
+        self.tracker.invalidate();
+    }
+
+    /// Brings the cache up to date with the frame.
+    ///
+    /// Returns the regions that changed, which also need updating on screen.
+    pub fn update(&mut self, frame: &Frame) -> Result<Damage, cairo::Error> {
+        let damage = self
+            .tracker
+            .update(frame, self.width() as u32, self.height() as u32);
+        self.shapes_rendered = 0;
+        if damage.is_empty() {
+            return Ok(damage);
+        }
+
+        let ctx = cairo::Context::new(&self.surface)?;
+        if let Damage::Regions(regions) = &damage {
+            for region in regions {
+                ctx.rectangle(region.x, region.y, region.width, region.height);
+            }
+            ctx.clip();
+        }
+        ctx.set_operator(cairo::Operator::Source);
+        ctx.set_source_rgba(0.0, 0.0, 0.0, 0.0);
+        ctx.paint()?;
+        ctx.set_operator(cairo::Operator::Over);
+        render_board_background(
+            &ctx,
+            &self.background,
+            self.width() as f64,
+            self.height() as f64,
+        );
+
+        if damage == Damage::Full {
+            render_shapes(&ctx, frame);
+            self.shapes_rendered = frame.visible_shapes().count();
+        } else {
+            let shapes = frame.visible_shapes().zip(self.tracker.rendered_bounds());
+            for ((_, shape), bounds) in shapes {
+                if bounds.is_some_and(|bounds| damage.intersects(&bounds)) {
+                    render_shape(&ctx, shape);
+                    self.shapes_rendered += 1;
+                }
+            }
+        }
+        drop(ctx);
+        self.surface.flush();
+        Ok(damage)
+    }
+
+    /// Replaces everything on the target with the cached image.
+    pub fn paint(&self, ctx: &cairo::Context) -> Result<(), cairo::Error> {
+        ctx.save()?;
+        ctx.set_source_surface(&self.surface, 0.0, 0.0)?;
+        ctx.set_operator(cairo::Operator::Source);
+        let result = ctx.paint();
+        ctx.restore()?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::Color;
+    use crate::draw::Shape;
+    use crate::draw::color::{BLUE, RED, WHITE};
+
+    fn rect(x: i32, y: i32, color: Color) -> Shape {
+        Shape::Rect {
+            x,
+            y,
+            w: 20,
+            h: 20,
+            color,
+            thick: 4.0,
+            rotation: 0.0,
+        }
+    }
+
+    /// Renders the frame from scratch, the way the cache should look.
+    fn reference(frame: &Frame, background: Option<Color>) -> Vec<u8> {
+        let mut surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 100, 100).unwrap();
+        let ctx = cairo::Context::new(&surface).unwrap();
+        if let Some(color) = background {
+            ctx.set_source_rgba(color.r, color.g, color.b, color.a);
+            ctx.paint().unwrap();
+        }
+        render_shapes(&ctx, frame);
+        drop(ctx);
+        surface.data().unwrap().to_vec()
+    }
+
+    fn pixels(cache: &ShapeCache) -> Vec<u8> {
+        let mut surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 100, 100).unwrap();
+        let ctx = cairo::Context::new(&surface).unwrap();
+        cache.paint(&ctx).unwrap();
+        drop(ctx);
+        surface.data().unwrap().to_vec()
+    }
+
+    fn check(frame: &Frame, cache: &mut ShapeCache, background: Option<Color>) {
+        cache.update(frame).unwrap();
+        assert!(pixels(cache) == reference(frame, background));
+    }
+
+    #[test]
+    fn cache_matches_a_full_render_after_every_edit() {
+        let mut frame = Frame::new();
+        let mut cache = ShapeCache::new(100, 100).unwrap();
+        assert_eq!(cache.update(&frame).unwrap(), Damage::Full);
+
+        frame.add_shape(rect(10, 10, RED));
+        frame.add_shape(rect(20, 20, BLUE));
+        check(&frame, &mut cache, None);
+
+        let original = frame.shapes[0].clone();
+        frame.shape_mut(0).unwrap().translate(30, 0);
+        frame.record_changes(vec![(0, original)]);
+        check(&frame, &mut cache, None);
+
+        frame.bring_to_front(&[0]);
+        check(&frame, &mut cache, None);
+
+        frame.undo();
+        check(&frame, &mut cache, None);
+
+        frame.clear();
+        check(&frame, &mut cache, None);
+
+        frame.undo();
+        check(&frame, &mut cache, None);
+
+        cache.set_background(Some(WHITE).into());
+        check(&frame, &mut cache, Some(WHITE));
+        assert!(cache.update(&frame).unwrap().is_empty());
+    }
+}
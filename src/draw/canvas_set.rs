@@ -1,80 +1,337 @@
 //! Multi-frame canvas management for board modes.
 
 use super::Frame;
+use super::frame::{DEFAULT_LAYER_NAMES, FrameData};
+use super::history::DEFAULT_HISTORY_LIMIT;
 use crate::input::BoardMode;
+use std::collections::BTreeMap;
 
-/// Manages multiple frames, one per board mode (with lazy initialization).
+/// Pages of one board, one of which is shown at a time.
 ///
-/// This structure maintains separate drawing frames for each board mode:
-/// - Transparent mode always has a frame (used for screen annotation)
-/// - Whiteboard and Blackboard frames are lazily created on first use
+/// Each page is a [`Frame`] with its own shapes and undo history. A board
+/// always has at least one page.
+pub struct Board {
+    /// Pages in order (never empty)
+    pages: Vec<Frame>,
+    /// Index of the page being shown
+    current: usize,
+    /// Deleted pages that undo can bring back, oldest first
+    deleted: Vec<DeletedPage>,
+    /// ID given to the next deleted page
+    next_deleted_id: u64,
+}
+
+/// A deleted page, kept so undo can put it back.
+struct DeletedPage {
+    /// ID recorded in the history of the page shown after the deletion
+    id: u64,
+    /// Position the page was deleted from
+    index: usize,
+    frame: Frame,
+}
+
+impl Board {
+    /// Creates a board with a single empty page.
+    fn new(settings: &PageSettings) -> Self {
+        Self::with_pages(vec![settings.new_page()], 0)
+    }
+
+    /// Creates a board showing `pages[current]`.
+    fn with_pages(pages: Vec<Frame>, current: usize) -> Self {
+        Self {
+            pages,
+            current,
+            deleted: Vec::new(),
+            next_deleted_id: 0,
+        }
+    }
+
+    /// Returns every page in order.
+    pub fn pages(&self) -> &[Frame] {
+        &self.pages
+    }
+
+    /// Returns the index of the page being shown.
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Returns the page being shown.
+    pub fn current_page(&self) -> &Frame {
+        &self.pages[self.current]
+    }
+
+    fn current_page_mut(&mut self) -> &mut Frame {
+        &mut self.pages[self.current]
+    }
+
+    /// Removes the current page and shows the one before it (or the new
+    /// first page).
+    ///
+    /// The page is kept under `id` for undo, unless undo is disabled. Only
+    /// the last `limit` deleted pages are kept, since older ones could never
+    /// be restored.
+    fn remove_current_page(&mut self, id: u64, limit: usize) {
+        let index = self.current;
+        let frame = self.pages.remove(index);
+        self.current = index.saturating_sub(1);
+        if limit > 0 {
+            self.deleted.push(DeletedPage { id, index, frame });
+            if self.deleted.len() > limit {
+                self.deleted.remove(0);
+            }
+        }
+    }
+}
+
+/// How new pages are set up.
+struct PageSettings {
+    /// Undo steps kept per page
+    history_limit: usize,
+    /// Layers each page starts with, bottom first
+    layer_names: Vec<String>,
+}
+
+impl PageSettings {
+    /// Creates an empty page with these settings.
+    fn new_page(&self) -> Frame {
+        let mut frame = Frame::with_layers(&self.layer_names);
+        frame.set_history_limit(self.history_limit);
+        frame
+    }
+}
+
+/// Manages multiple boards, one per board mode (with lazy initialization).
+///
+/// This structure maintains separate boards keyed by board mode:
+/// - Transparent mode always has a board (used for screen annotation)
+/// - Whiteboard, Blackboard and custom boards are lazily created on first use
 ///
-/// This design allows seamless mode switching while preserving work,
-/// and saves memory when board modes are never activated.
+/// Each board holds one or more pages; the active frame is the current page
+/// of the active board. This design allows seamless mode switching while
+/// preserving work, and saves memory when board modes are never activated.
 pub struct CanvasSet {
-    /// Frame for transparent overlay mode (always exists)
-    transparent: Frame,
-    /// Frame for whiteboard mode (lazy: created on first use)
-    whiteboard: Option<Frame>,
-    /// Frame for blackboard mode (lazy: created on first use)
-    blackboard: Option<Frame>,
+    /// Boards that exist, keyed by mode (transparent is always present)
+    boards: BTreeMap<BoardMode, Board>,
     /// Currently active mode
     active_mode: BoardMode,
+    /// How new pages are set up
+    settings: PageSettings,
+    /// Empty page standing in for boards that don't exist yet
+    blank: Frame,
 }
 
 impl CanvasSet {
-    /// Creates a new canvas set with only the transparent frame initialized.
+    /// Creates a new canvas set with only the transparent board initialized.
     pub fn new() -> Self {
+        let settings = PageSettings {
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            layer_names: DEFAULT_LAYER_NAMES.map(String::from).to_vec(),
+        };
         Self {
-            transparent: Frame::new(),
-            whiteboard: None,
-            blackboard: None,
+            boards: BTreeMap::from([(BoardMode::Transparent, Board::new(&settings))]),
             active_mode: BoardMode::Transparent,
+            blank: settings.new_page(),
+            settings,
         }
     }
 
-    /// Gets the currently active frame (mutable).
+    /// Sets how many edits can be undone on each page, including pages created later.
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.settings.history_limit = limit;
+        for board in self.boards.values_mut() {
+            for frame in &mut board.pages {
+                frame.set_history_limit(limit);
+            }
+        }
+    }
+
+    /// Sets the layers of each page, including pages created later.
     ///
-    /// Lazily creates whiteboard/blackboard frames if they don't exist yet.
-    pub fn active_frame_mut(&mut self) -> &mut Frame {
-        match self.active_mode {
-            BoardMode::Transparent => &mut self.transparent,
-            BoardMode::Whiteboard => self.whiteboard.get_or_insert_with(Frame::new),
-            BoardMode::Blackboard => self.blackboard.get_or_insert_with(Frame::new),
+    /// See [`Frame::set_layer_names`] for what happens to existing shapes.
+    pub fn set_layer_names(&mut self, names: &[String]) {
+        self.settings.layer_names = names.to_vec();
+        self.blank.set_layer_names(names);
+        for board in self.boards.values_mut() {
+            for frame in &mut board.pages {
+                frame.set_layer_names(names);
+            }
         }
     }
 
-    /// Gets the currently active frame (immutable).
+    /// Gets the current page of the active board (mutable).
+    ///
+    /// Lazily creates the active board if it doesn't exist yet.
+    pub fn active_frame_mut(&mut self) -> &mut Frame {
+        self.active_board_mut().current_page_mut()
+    }
+
+    /// Gets the current page of the active board (immutable).
     ///
-    /// For board modes that don't exist yet, returns a reference to a static empty frame
+    /// For board modes that don't exist yet, returns a reference to a blank frame
     /// instead of creating one (since we can't mutate in an immutable method).
     pub fn active_frame(&self) -> &Frame {
-        static EMPTY_FRAME: Frame = Frame { shapes: Vec::new() };
+        self.active_board()
+            .map_or(&self.blank, |board| board.current_page())
+    }
 
-        match self.active_mode {
-            BoardMode::Transparent => &self.transparent,
-            BoardMode::Whiteboard => self.whiteboard.as_ref().unwrap_or(&EMPTY_FRAME),
-            BoardMode::Blackboard => self.blackboard.as_ref().unwrap_or(&EMPTY_FRAME),
-        }
+    /// Returns every board that exists, in transparent, whiteboard,
+    /// blackboard order followed by custom boards sorted by name.
+    pub fn boards(&self) -> impl Iterator<Item = (&BoardMode, &Board)> {
+        self.boards.iter()
     }
 
     /// Returns the current active board mode.
-    pub fn active_mode(&self) -> BoardMode {
-        self.active_mode
+    pub fn active_mode(&self) -> &BoardMode {
+        &self.active_mode
     }
 
     /// Switches to a different board mode.
     ///
-    /// This does not create frames lazily - they are created when first accessed
+    /// This does not create boards lazily - they are created when first accessed
     /// via `active_frame_mut()`.
     pub fn switch_mode(&mut self, new_mode: BoardMode) {
         self.active_mode = new_mode;
     }
 
+    /// Returns the current page index and page count of the active board.
+    pub fn page_position(&self) -> (usize, usize) {
+        self.active_board()
+            .map_or((0, 1), |board| (board.current, board.pages.len()))
+    }
+
+    /// Shows the next page of the active board.
+    ///
+    /// Returns `false` if the last page is already showing.
+    pub fn next_page(&mut self) -> bool {
+        let board = self.active_board_mut();
+        if board.current + 1 >= board.pages.len() {
+            return false;
+        }
+        board.current += 1;
+        true
+    }
+
+    /// Shows the previous page of the active board.
+    ///
+    /// Returns `false` if the first page is already showing.
+    pub fn previous_page(&mut self) -> bool {
+        let board = self.active_board_mut();
+        if board.current == 0 {
+            return false;
+        }
+        board.current -= 1;
+        true
+    }
+
+    /// Inserts an empty page after the current one and shows it.
+    pub fn new_page(&mut self) {
+        let page = self.settings.new_page();
+        let board = self.active_board_mut();
+        board.current += 1;
+        board.pages.insert(board.current, page);
+    }
+
+    /// Deletes the current page of the active board, showing the page
+    /// before it (or the new first page).
+    ///
+    /// A board's only page is cleared instead. Either way, [`CanvasSet::undo`]
+    /// brings the page back. Returns `true` if a page was removed.
+    pub fn delete_page(&mut self) -> bool {
+        let limit = self.settings.history_limit;
+        let board = self.active_board_mut();
+        if board.pages.len() == 1 {
+            board.pages[0].clear();
+            return false;
+        }
+        let id = board.next_deleted_id;
+        board.next_deleted_id += 1;
+        board.remove_current_page(id, limit);
+        board.current_page_mut().record_page_deleted(id);
+        true
+    }
+
+    /// Undoes the last edit of the current page, or brings back the page
+    /// deleted last if that happened after it.
+    ///
+    /// Returns `true` if anything was undone.
+    pub fn undo(&mut self) -> bool {
+        let board = self.active_board_mut();
+        if let Some(id) = board.pages[board.current].take_deleted_page()
+            && let Some(position) = board.deleted.iter().position(|page| page.id == id)
+        {
+            let DeletedPage { index, frame, .. } = board.deleted.remove(position);
+            let index = index.min(board.pages.len());
+            board.pages.insert(index, frame);
+            board.current = index;
+            board.current_page_mut().record_page_restored(id);
+            return true;
+        }
+        board.current_page_mut().undo()
+    }
+
+    /// Redoes the last undone edit of the current page, or deletes the page
+    /// again if undo brought it back.
+    ///
+    /// Returns `true` if anything was redone.
+    pub fn redo(&mut self) -> bool {
+        let limit = self.settings.history_limit;
+        let board = self.active_board_mut();
+        if let Some(id) = board.current_page_mut().take_page_to_delete() {
+            // Other pages may have been deleted since; the last one stays
+            if board.pages.len() == 1 {
+                return false;
+            }
+            board.remove_current_page(id, limit);
+            board.current_page_mut().redo_page_deleted(id);
+            return true;
+        }
+        board.current_page_mut().redo()
+    }
+
+    /// Replaces all pages of a board with previously saved ones.
+    ///
+    /// No pages means a single empty page. `current` is clamped to the last
+    /// page. Loaded pages start with an empty undo history.
+    pub fn load_board(&mut self, mode: BoardMode, pages: Vec<FrameData>, current: usize) {
+        let mut frames: Vec<Frame> = pages
+            .into_iter()
+            .map(|data| {
+                let mut frame = self.settings.new_page();
+                frame.load_data(data);
+                frame
+            })
+            .collect();
+        if frames.is_empty() {
+            frames.push(self.settings.new_page());
+        }
+        let current = current.min(frames.len() - 1);
+        self.boards.insert(mode, Board::with_pages(frames, current));
+    }
+
     /// Clears only the active frame.
     pub fn clear_active(&mut self) {
         self.active_frame_mut().clear();
     }
+
+    /// Clears every page of every board. Each clear can be undone on its own page.
+    pub fn clear_all(&mut self) {
+        for board in self.boards.values_mut() {
+            for frame in &mut board.pages {
+                frame.clear();
+            }
+        }
+    }
+
+    fn active_board(&self) -> Option<&Board> {
+        self.boards.get(&self.active_mode)
+    }
+
+    fn active_board_mut(&mut self) -> &mut Board {
+        self.boards
+            .entry(self.active_mode.clone())
+            .or_insert_with(|| Board::new(&self.settings))
+    }
 }
 
 impl Default for CanvasSet {
@@ -91,7 +348,7 @@ mod tests {
     #[test]
     fn test_initial_mode_is_transparent() {
         let canvas_set = CanvasSet::new();
-        assert_eq!(canvas_set.active_mode(), BoardMode::Transparent);
+        assert_eq!(*canvas_set.active_mode(), BoardMode::Transparent);
     }
 
     #[test]
@@ -135,6 +392,7 @@ mod tests {
             h: 50,
             color: BLACK,
             thick: 2.0,
+            rotation: 0.0,
         });
         assert_eq!(canvas_set.active_frame().shapes.len(), 1);
 
@@ -172,6 +430,7 @@ mod tests {
             h: 50,
             color: BLACK,
             thick: 2.0,
+            rotation: 0.0,
         });
 
         // Undo should only affect whiteboard frame
@@ -206,6 +465,7 @@ mod tests {
             h: 50,
             color: BLACK,
             thick: 2.0,
+            rotation: 0.0,
         });
 
         // Clear whiteboard only
@@ -217,6 +477,32 @@ mod tests {
         assert_eq!(canvas_set.active_frame().shapes.len(), 1);
     }
 
+    #[test]
+    fn custom_boards_are_kept_apart_by_name() {
+        let mut canvas_set = CanvasSet::new();
+        let greenboard = BoardMode::Custom("greenboard".to_string());
+
+        canvas_set.switch_mode(greenboard.clone());
+        canvas_set.active_frame_mut().add_shape(Shape::Line {
+            x1: 0,
+            y1: 0,
+            x2: 100,
+            y2: 100,
+            color: RED,
+            thick: 3.0,
+        });
+        canvas_set.switch_mode(BoardMode::Custom("blueprint".to_string()));
+        assert!(canvas_set.active_frame().shapes.is_empty());
+        canvas_set.switch_mode(BoardMode::Blackboard);
+        canvas_set.active_frame_mut();
+
+        let modes: Vec<&BoardMode> = canvas_set.boards().map(|(mode, _)| mode).collect();
+        assert_eq!(
+            modes,
+            vec![&BoardMode::Transparent, &BoardMode::Blackboard, &greenboard]
+        );
+    }
+
     #[test]
     fn test_immutable_access_to_nonexistent_frame() {
         let canvas_set = CanvasSet::new();
@@ -226,4 +512,129 @@ mod tests {
         // This test demonstrates the static EMPTY_FRAME pattern
         assert_eq!(canvas_set.active_frame().shapes.len(), 0);
     }
+
+    #[test]
+    fn pages_are_added_navigated_and_deleted_per_board() {
+        let mut canvas_set = CanvasSet::new();
+        let line = |x| Shape::Line {
+            x1: x,
+            y1: 0,
+            x2: 100,
+            y2: 100,
+            color: RED,
+            thick: 3.0,
+        };
+
+        canvas_set.active_frame_mut().add_shape(line(1));
+        canvas_set.new_page();
+        canvas_set.active_frame_mut().add_shape(line(2));
+        assert_eq!(canvas_set.page_position(), (1, 2));
+        assert!(!canvas_set.next_page());
+        assert!(canvas_set.previous_page());
+        assert!(!canvas_set.previous_page());
+        assert_eq!(canvas_set.active_frame().shapes, vec![line(1)]);
+
+        // A page inserted after the first one goes in the middle
+        canvas_set.new_page();
+        assert_eq!(canvas_set.page_position(), (1, 3));
+        assert!(canvas_set.active_frame().shapes.is_empty());
+
+        // Other boards keep their own pages
+        canvas_set.switch_mode(BoardMode::Whiteboard);
+        assert_eq!(canvas_set.page_position(), (0, 1));
+        canvas_set.switch_mode(BoardMode::Transparent);
+
+        assert!(canvas_set.delete_page());
+        assert_eq!(canvas_set.page_position(), (0, 2));
+        assert!(canvas_set.next_page());
+        assert_eq!(canvas_set.active_frame().shapes, vec![line(2)]);
+        assert!(canvas_set.delete_page());
+        assert!(
+            !canvas_set.delete_page(),
+            "the only page is cleared instead"
+        );
+        assert!(canvas_set.active_frame().shapes.is_empty());
+        assert!(canvas_set.active_frame_mut().undo());
+        assert_eq!(canvas_set.active_frame().shapes, vec![line(1)]);
+    }
+
+    #[test]
+    fn undo_brings_back_deleted_pages() {
+        let mut canvas_set = CanvasSet::new();
+        let line = |x| Shape::Line {
+            x1: x,
+            y1: 0,
+            x2: 100,
+            y2: 100,
+            color: RED,
+            thick: 3.0,
+        };
+
+        canvas_set.active_frame_mut().add_shape(line(1));
+        canvas_set.new_page();
+        canvas_set.active_frame_mut().add_shape(line(2));
+        canvas_set.active_frame_mut().add_shape(line(3));
+        assert!(canvas_set.delete_page());
+        assert_eq!(canvas_set.page_position(), (0, 1));
+
+        // Edits made after the deletion are undone first
+        canvas_set.active_frame_mut().add_shape(line(4));
+        assert!(canvas_set.undo());
+        assert_eq!(canvas_set.active_frame().shapes, vec![line(1)]);
+
+        assert!(canvas_set.undo());
+        assert_eq!(canvas_set.page_position(), (1, 2));
+        assert_eq!(canvas_set.active_frame().shapes, vec![line(2), line(3)]);
+
+        // The restored page keeps its own history
+        assert!(canvas_set.undo());
+        assert_eq!(canvas_set.active_frame().shapes, vec![line(2)]);
+        assert!(canvas_set.previous_page());
+        assert!(canvas_set.undo());
+        assert!(canvas_set.active_frame().shapes.is_empty());
+    }
+
+    #[test]
+    fn redo_deletes_restored_pages_again() {
+        let mut canvas_set = CanvasSet::new();
+        let line = |x| Shape::Line {
+            x1: x,
+            y1: 0,
+            x2: 100,
+            y2: 100,
+            color: RED,
+            thick: 3.0,
+        };
+
+        canvas_set.active_frame_mut().add_shape(line(1));
+        canvas_set.new_page();
+        canvas_set.active_frame_mut().add_shape(line(2));
+        canvas_set.active_frame_mut().add_shape(line(3));
+        assert!(canvas_set.undo());
+        assert!(canvas_set.delete_page());
+        canvas_set.active_frame_mut().add_shape(line(4));
+        assert!(canvas_set.undo());
+        assert!(canvas_set.undo());
+        assert_eq!(canvas_set.page_position(), (1, 2));
+
+        // Redo deletes the page again rather than redoing line 3 on it
+        assert!(canvas_set.redo());
+        assert_eq!(canvas_set.page_position(), (0, 1));
+        assert_eq!(canvas_set.active_frame().shapes, vec![line(1)]);
+
+        // Edits undone on the page left shown can still be redone
+        assert!(canvas_set.redo());
+        assert_eq!(canvas_set.active_frame().shapes, vec![line(1), line(4)]);
+        assert!(!canvas_set.redo());
+
+        // And the page can be brought back once more
+        assert!(canvas_set.undo());
+        assert!(canvas_set.undo());
+        assert_eq!(canvas_set.active_frame().shapes, vec![line(2)]);
+
+        // A new edit on the restored page drops the pending deletion
+        canvas_set.active_frame_mut().add_shape(line(5));
+        assert!(!canvas_set.redo());
+        assert_eq!(canvas_set.page_position(), (1, 2));
+    }
 }
@@ -0,0 +1,385 @@
+//! Damage tracking for incremental redraws.
+//!
+//! [`DamageTracker`] works out which regions changed between two renders from
+//! the shapes the active frame reports as changed since the last one (see
+//! [`Frame::revision`]). Transient
+//! overlays drawn on top (provisional shape, selection handles, status bar...)
+//! are added with [`Damage::with_overlays`].
+
+use super::frame::{Frame, ShapeId};
+use super::shape::{BoundingBox, Shape};
+use super::text::TextEffect;
+
+/// Extra pixels around every damaged box to cover antialiasing.
+const ANTIALIAS_MARGIN: f64 = 2.0;
+
+/// Above this many separate rectangles they are merged into one.
+const MAX_DAMAGE_RECTS: usize = 8;
+
+/// Once the damaged area covers this share of the screen, repaint everything.
+const FULL_REPAINT_RATIO: f64 = 0.5;
+
+/// Returns the screen area a shape paints to, including text effects and
+/// antialiasing, or `None` if it paints nothing.
+pub fn shape_damage_bounds(shape: &Shape) -> Option<BoundingBox> {
+    let bounds = shape.bounding_box()?;
+    let effect = match shape {
+        Shape::Text { effect, .. } => match effect {
+            TextEffect::None => 0.0,
+            TextEffect::Background { padding, .. } => *padding,
+            TextEffect::Outline { width, .. } => *width,
+            TextEffect::Shadow { offset, .. } => *offset,
+        },
+        _ => 0.0,
+    };
+    Some(bounds.inflate(effect + ANTIALIAS_MARGIN))
+}
+
+/// Regions of the screen that need repainting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Damage {
+    /// Everything must be repainted
+    Full,
+    /// Only these pixel-aligned, non-overlapping rectangles changed
+    Regions(Vec<BoundingBox>),
+}
+
+impl Damage {
+    /// Returns true if nothing needs repainting.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Damage::Regions(regions) if regions.is_empty())
+    }
+
+    /// Adds the areas of overlays drawn over the shapes, merging and snapping
+    /// them like [`DamageTracker::update`] does.
+    pub fn with_overlays(self, overlays: &[BoundingBox], width: u32, height: u32) -> Damage {
+        match self {
+            Damage::Full => Damage::Full,
+            Damage::Regions(mut regions) => {
+                regions.extend(
+                    overlays
+                        .iter()
+                        .map(|bounds| bounds.inflate(ANTIALIAS_MARGIN)),
+                );
+                merge_regions(regions, width, height)
+            }
+        }
+    }
+
+    /// Returns true if a box overlaps any damaged region.
+    pub fn intersects(&self, bounds: &BoundingBox) -> bool {
+        match self {
+            Damage::Full => true,
+            Damage::Regions(regions) => regions.iter().any(|region| region.intersects(bounds)),
+        }
+    }
+}
+
+/// Where a shape painted when it was last rendered.
+#[derive(Debug)]
+struct RenderedShape {
+    id: ShapeId,
+    bounds: Option<BoundingBox>,
+}
+
+/// Works out which parts of the screen changed between two renders.
+///
+/// Call [`DamageTracker::update`] before each render and repaint the
+/// returned regions.
+#[derive(Debug)]
+pub struct DamageTracker {
+    rendered: Vec<RenderedShape>,
+    /// Key and revision of the frame last rendered
+    seen: Option<(u64, u64)>,
+    full: bool,
+}
+
+impl Default for DamageTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DamageTracker {
+    /// Creates a tracker whose first update repaints everything.
+    pub fn new() -> Self {
+        Self {
+            rendered: Vec::new(),
+            seen: None,
+            full: true,
+        }
+    }
+
+    /// Forces the next update to repaint everything.
+    ///
+    /// Needed whenever the previous image can't be trusted, e.g. after a
+    /// resize or a switch to another board.
+    pub fn invalidate(&mut self) {
+        self.full = true;
+    }
+
+    /// Compares the frame with the last render and returns what to repaint.
+    ///
+    /// The damage covers shapes that were added, removed, changed or moved
+    /// in draw order. Shapes on hidden layers count as removed, and every
+    /// shape of another frame counts as changed. Nothing is looked at unless
+    /// the frame changed since the last update.
+    pub fn update(&mut self, frame: &Frame, width: u32, height: u32) -> Damage {
+        let current = (frame.key(), frame.revision());
+        let seen = self.seen.replace(current);
+        if seen == Some(current) {
+            return if std::mem::take(&mut self.full) {
+                Damage::Full
+            } else {
+                Damage::Regions(Vec::new())
+            };
+        }
+        let since = seen
+            .filter(|(key, _)| *key == frame.key())
+            .map(|(_, revision)| revision);
+        let changed = |id: ShapeId| since.is_none_or(|revision| frame.changed_since(id, revision));
+
+        let mut regions = Vec::new();
+        let visible: Vec<(ShapeId, &Shape)> = frame.visible_shapes().collect();
+
+        // Usually the same shapes are still there in the same order
+        let same_ids = self.rendered.len() == visible.len()
+            && self
+                .rendered
+                .iter()
+                .zip(&visible)
+                .all(|(old, (id, _))| old.id == *id);
+        if same_ids {
+            for (old, (id, shape)) in self.rendered.iter_mut().zip(&visible) {
+                if changed(*id) {
+                    regions.extend(old.bounds);
+                    old.bounds = shape_damage_bounds(shape);
+                    regions.extend(old.bounds);
+                }
+            }
+            if std::mem::take(&mut self.full) {
+                return Damage::Full;
+            }
+            return merge_regions(regions, width, height);
+        }
+
+        let mut previous: Vec<Option<RenderedShape>> = std::mem::take(&mut self.rendered)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let positions: std::collections::HashMap<ShapeId, usize> = previous
+            .iter()
+            .enumerate()
+            .filter_map(|(position, entry)| Some((entry.as_ref()?.id, position)))
+            .collect();
+
+        // Shapes that kept their content but now come before a shape they used
+        // to follow have changed stacking order and are repainted too
+        let mut last_position = None;
+        for (id, shape) in visible {
+            let old = positions
+                .get(&id)
+                .and_then(|&position| Some((position, previous[position].take()?)));
+            match old {
+                Some((position, old)) if !changed(id) => {
+                    if last_position.is_some_and(|last| position < last) {
+                        regions.extend(old.bounds);
+                    } else {
+                        last_position = Some(position);
+                    }
+                    self.rendered.push(old);
+                }
+                old => {
+                    if let Some((_, old)) = old {
+                        regions.extend(old.bounds);
+                    }
+                    let bounds = shape_damage_bounds(shape);
+                    regions.extend(bounds);
+                    self.rendered.push(RenderedShape { id, bounds });
+                }
+            }
+        }
+        // Whatever is left was removed from the frame
+        regions.extend(previous.into_iter().flatten().filter_map(|old| old.bounds));
+
+        if std::mem::take(&mut self.full) {
+            return Damage::Full;
+        }
+        merge_regions(regions, width, height)
+    }
+
+    /// Returns where each visible shape painted as of the last update, in
+    /// draw order.
+    pub fn rendered_bounds(&self) -> impl Iterator<Item = Option<BoundingBox>> {
+        self.rendered.iter().map(|rendered| rendered.bounds)
+    }
+}
+
+/// Snaps boxes to whole pixels inside the screen and merges overlapping ones.
+fn merge_regions(boxes: Vec<BoundingBox>, width: u32, height: u32) -> Damage {
+    let (width, height) = (width as f64, height as f64);
+    let mut regions: Vec<BoundingBox> = Vec::new();
+    for bounds in boxes {
+        let x1 = bounds.x.floor().max(0.0);
+        let y1 = bounds.y.floor().max(0.0);
+        let x2 = bounds.right().ceil().min(width);
+        let y2 = bounds.bottom().ceil().min(height);
+        if x2 <= x1 || y2 <= y1 {
+            continue;
+        }
+        let mut merged = BoundingBox::from_corners(x1, y1, x2, y2);
+        // Absorb every region the new one touches, repeating as it grows
+        while let Some(index) = regions.iter().position(|region| region.intersects(&merged)) {
+            merged = merged.union(&regions.swap_remove(index));
+        }
+        regions.push(merged);
+    }
+
+    if regions.len() > MAX_DAMAGE_RECTS {
+        let union = regions
+            .iter()
+            .copied()
+            .reduce(|combined, region| combined.union(&region));
+        regions = union.into_iter().collect();
+    }
+    let area: f64 = regions
+        .iter()
+        .map(|region| region.width * region.height)
+        .sum();
+    if area >= width * height * FULL_REPAINT_RATIO {
+        return Damage::Full;
+    }
+    Damage::Regions(regions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::color::RED;
+
+    const WIDTH: u32 = 1000;
+    const HEIGHT: u32 = 1000;
+
+    fn rect(x: i32, y: i32) -> Shape {
+        Shape::Rect {
+            x,
+            y,
+            w: 20,
+            h: 20,
+            color: RED,
+            thick: 2.0,
+            rotation: 0.0,
+        }
+    }
+
+    fn regions(damage: Damage) -> Vec<BoundingBox> {
+        match damage {
+            Damage::Regions(regions) => regions,
+            Damage::Full => panic!("expected partial damage"),
+        }
+    }
+
+    fn settled(frame: &Frame) -> DamageTracker {
+        let mut tracker = DamageTracker::new();
+        assert_eq!(tracker.update(frame, WIDTH, HEIGHT), Damage::Full);
+        assert!(tracker.update(frame, WIDTH, HEIGHT).is_empty());
+        tracker
+    }
+
+    #[test]
+    fn only_changed_shapes_are_damaged() {
+        let mut frame = Frame::new();
+        frame.add_shape(rect(0, 0));
+        frame.add_shape(rect(500, 500));
+        let mut tracker = settled(&frame);
+
+        frame.add_shape(rect(100, 100));
+        let damage = regions(tracker.update(&frame, WIDTH, HEIGHT));
+        assert_eq!(damage.len(), 1);
+        assert!(damage[0].contains_box(&rect(100, 100).bounding_box().unwrap()));
+        assert!(!damage[0].contains(510.0, 510.0));
+
+        // Moving a shape damages where it was and where it is now
+        frame.shape_mut(0).unwrap().translate(200, 0);
+        let damage = regions(tracker.update(&frame, WIDTH, HEIGHT));
+        assert_eq!(damage.len(), 2);
+        assert!(damage.iter().any(|region| region.contains(10.0, 10.0)));
+        assert!(damage.iter().any(|region| region.contains(210.0, 10.0)));
+
+        frame.remove_shape(1);
+        let damage = regions(tracker.update(&frame, WIDTH, HEIGHT));
+        assert!(damage.iter().any(|region| region.contains(510.0, 510.0)));
+    }
+
+    #[test]
+    fn restacking_damages_the_moved_shape() {
+        let mut frame = Frame::new();
+        frame.add_shape(rect(0, 0));
+        frame.add_shape(rect(10, 10));
+        frame.add_shape(rect(500, 500));
+        let mut tracker = settled(&frame);
+
+        frame.bring_to_front(&[0]);
+        let damage = regions(tracker.update(&frame, WIDTH, HEIGHT));
+        assert_eq!(damage.len(), 1);
+        assert!(damage[0].contains(5.0, 5.0));
+        assert!(!damage[0].contains(510.0, 510.0));
+    }
+
+    #[test]
+    fn another_frame_damages_every_shape() {
+        let mut first = Frame::new();
+        first.add_shape(rect(0, 0));
+        let mut second = Frame::new();
+        second.add_shape(rect(500, 500));
+        let mut tracker = settled(&first);
+
+        // Both frames use the same shape ID, but the shapes differ
+        let damage = regions(tracker.update(&second, WIDTH, HEIGHT));
+        assert!(damage.iter().any(|region| region.contains(10.0, 10.0)));
+        assert!(damage.iter().any(|region| region.contains(510.0, 510.0)));
+        assert!(tracker.update(&second, WIDTH, HEIGHT).is_empty());
+    }
+
+    #[test]
+    fn overlays_are_added_with_an_antialiasing_margin() {
+        let frame = Frame::new();
+        let mut tracker = settled(&frame);
+        let damage = tracker.update(&frame, WIDTH, HEIGHT).with_overlays(
+            &[BoundingBox::from_corners(10.0, 10.0, 50.0, 50.0)],
+            WIDTH,
+            HEIGHT,
+        );
+        assert_eq!(
+            regions(damage),
+            vec![BoundingBox::from_corners(8.0, 8.0, 52.0, 52.0)]
+        );
+
+        // A screen-sized overlay, like the help overlay, damages everything
+        let screen = BoundingBox::from_corners(0.0, 0.0, 1000.0, 1000.0);
+        assert_eq!(
+            Damage::Regions(Vec::new()).with_overlays(&[screen], WIDTH, HEIGHT),
+            Damage::Full
+        );
+        tracker.invalidate();
+        assert_eq!(tracker.update(&frame, WIDTH, HEIGHT), Damage::Full);
+    }
+
+    #[test]
+    fn regions_are_merged_and_clamped() {
+        let boxes = vec![
+            BoundingBox::from_corners(-5.0, -5.0, 10.5, 10.5),
+            BoundingBox::from_corners(5.0, 5.0, 20.0, 20.0),
+            BoundingBox::from_corners(100.0, 100.0, 100.0, 120.0),
+        ];
+        assert_eq!(
+            regions(merge_regions(boxes, WIDTH, HEIGHT)),
+            vec![BoundingBox::from_corners(0.0, 0.0, 20.0, 20.0)]
+        );
+
+        let scattered = (0..20)
+            .map(|i| BoundingBox::from_corners(i as f64 * 40.0, 0.0, i as f64 * 40.0 + 5.0, 5.0))
+            .collect();
+        assert_eq!(regions(merge_regions(scattered, WIDTH, HEIGHT)).len(), 1);
+    }
+}
@@ -1,11 +1,14 @@
 //! Font descriptor for text rendering.
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
 /// Font configuration for text rendering.
 ///
 /// Describes which font to use, including family name, weight, and style.
 /// This descriptor is passed through the rendering pipeline to ensure
 /// consistent font usage across preview and finalized text.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct FontDescriptor {
     /// Font family name (e.g., "Sans", "Monospace", "JetBrains Mono")
     /// Reference installed system fonts by name
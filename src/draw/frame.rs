@@ -1,14 +1,125 @@
 //! Frame container for managing collections of shapes.
 
-use super::shape::Shape;
+use super::history::{Command, History, ShapeChange, ShapeEntry};
+use super::shape::{BoundingBox, Shape};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Layers a page starts with unless configured otherwise, bottom first.
+pub const DEFAULT_LAYER_NAMES: [&str; 2] = ["Base", "Scratch"];
+
+/// Source of [`Frame::key`] values.
+static NEXT_FRAME_KEY: AtomicU64 = AtomicU64::new(1);
+
+/// Stable identifier of a shape (or group) within a frame.
+///
+/// IDs survive reordering and never get reused within a frame, unlike
+/// indices into [`Frame::shapes`].
+pub type ShapeId = u64;
+
+/// Shapes that are selected, moved, reordered and deleted as one unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeGroup {
+    /// Group identifier, drawn from the same sequence as shape IDs
+    pub id: ShapeId,
+    /// IDs of the grouped shapes (at least two)
+    pub members: Vec<ShapeId>,
+}
+
+/// A named layer of a frame.
+///
+/// Every shape belongs to one layer. Layers are drawn bottom first, and the
+/// shapes of a hidden layer are neither drawn nor picked by the selection tool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Layer {
+    /// Name shown in the status bar
+    pub name: String,
+    /// Whether the layer's shapes are drawn
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+}
+
+impl Layer {
+    /// Creates a visible layer.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            visible: true,
+        }
+    }
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// Saved form of a [`Frame`]: its shapes, groups and layers, without IDs or history.
+///
+/// Groups refer to shapes by index into `shapes`, so the data stays
+/// meaningful outside the frame it was taken from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FrameData {
+    /// Shapes in draw order
+    pub shapes: Vec<Shape>,
+    /// Shape groups, each listing the indices of its members
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<Vec<usize>>,
+    /// Layers, bottom first (none keeps the frame's own layers)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<Layer>,
+    /// Layer index of each shape, parallel to `shapes` (missing means the bottom layer)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shape_layers: Vec<usize>,
+    /// Index of the layer new shapes go to
+    #[serde(default)]
+    pub active_layer: usize,
+}
 
 /// Container for all shapes in the current drawing session.
 ///
 /// Manages a collection of [`Shape`]s and provides operations like adding,
 /// clearing, and undoing shapes. Acts as the drawing canvas state.
+///
+/// Every edit made through the frame's methods is recorded in its
+/// [`History`], so it can be undone and redone.
+///
+/// Shapes are organized in named [`Layer`]s. New shapes go to the active
+/// layer, and `shapes` is kept sorted by layer, so each layer's shapes form
+/// one run of it (see [`Frame::layer_range`]).
 pub struct Frame {
-    /// Vector of all shapes in draw order (first = bottom layer, last = top layer)
+    /// Vector of all shapes in draw order (first = bottom, last = top)
+    ///
+    /// Shapes may be modified in place through [`Frame::shape_mut`] (see
+    /// [`Frame::record_changes`]), but must be added, removed and reordered
+    /// through the frame's methods so IDs, layers, groups and history stay in
+    /// step.
     pub shapes: Vec<Shape>,
+    /// Stable ID of each shape, parallel to `shapes`
+    ids: Vec<ShapeId>,
+    /// Layers, bottom first (never empty)
+    layers: Vec<Layer>,
+    /// Layer of each shape by ID
+    ///
+    /// Removed shapes drop out; the history keeps their layers, so undoing
+    /// a removal puts a shape back on its own layer.
+    shape_layers: HashMap<ShapeId, usize>,
+    /// Index of the layer new shapes go to
+    active_layer: usize,
+    /// Shape groups in creation order
+    groups: Vec<ShapeGroup>,
+    /// Next ID handed out to a shape or group
+    next_id: ShapeId,
+    /// Undo and redo stacks
+    history: History,
+    /// Tells this frame apart from every other frame
+    key: u64,
+    /// Bumped whenever what the frame draws changes
+    revision: u64,
+    /// Revision at which each shape was last added or changed, by ID
+    changed_at: HashMap<ShapeId, u64>,
 }
 
 impl Default for Frame {
@@ -18,30 +129,1030 @@ impl Default for Frame {
 }
 
 impl Frame {
-    /// Creates a new empty frame with no shapes.
+    /// Creates a new empty frame with the default layers.
     pub fn new() -> Self {
-        Self { shapes: Vec::new() }
+        Self::with_layers(&DEFAULT_LAYER_NAMES)
+    }
+
+    /// Creates a new empty frame with the named layers, bottom first.
+    ///
+    /// The bottom layer is active. No names means the default layers.
+    pub fn with_layers<S: AsRef<str>>(names: &[S]) -> Self {
+        Self {
+            shapes: Vec::new(),
+            ids: Vec::new(),
+            layers: layers_named(names),
+            shape_layers: HashMap::new(),
+            active_layer: 0,
+            groups: Vec::new(),
+            next_id: 1,
+            history: History::new(),
+            key: NEXT_FRAME_KEY.fetch_add(1, Ordering::Relaxed),
+            revision: 0,
+            changed_at: HashMap::new(),
+        }
+    }
+
+    /// Sets how many edits can be undone.
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history.set_limit(limit);
     }
 
     /// Removes all shapes from the frame, clearing the canvas.
+    ///
+    /// Clearing is recorded like any other edit, so it can be undone.
     pub fn clear(&mut self) {
-        self.shapes.clear();
+        let indices: Vec<usize> = (0..self.shapes.len()).collect();
+        self.remove_shapes(&indices);
+    }
+
+    /// Removes the shapes of the active layer, leaving other layers alone.
+    ///
+    /// Like [`Self::clear`], this can be undone.
+    pub fn clear_layer(&mut self) {
+        let indices: Vec<usize> = self.layer_range(self.active_layer).collect();
+        self.remove_shapes(&indices);
+    }
+
+    /// Returns the layers, bottom first.
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Returns the index of the layer new shapes go to.
+    pub fn active_layer(&self) -> usize {
+        self.active_layer
+    }
+
+    /// Makes the layer above the active one active, wrapping around to the bottom.
+    pub fn next_layer(&mut self) {
+        self.active_layer = (self.active_layer + 1) % self.layers.len();
+    }
+
+    /// Shows or hides the active layer.
+    ///
+    /// Returns whether the layer is now visible. Layer visibility is a view
+    /// setting, not an edit, so it is not part of the undo history.
+    pub fn toggle_layer_visibility(&mut self) -> bool {
+        self.touch();
+        let layer = &mut self.layers[self.active_layer];
+        layer.visible = !layer.visible;
+        layer.visible
+    }
+
+    /// Renames the layers, adding or dropping layers at the top as needed.
+    ///
+    /// Shapes on dropped layers move to the new top layer. Layers that remain
+    /// keep their visibility. No names means the default layers.
+    pub fn set_layer_names<S: AsRef<str>>(&mut self, names: &[S]) {
+        let mut layers = layers_named(names);
+        for (layer, old) in layers.iter_mut().zip(&self.layers) {
+            layer.visible = old.visible;
+        }
+        let top = layers.len() - 1;
+        for layer in self.shape_layers.values_mut() {
+            *layer = (*layer).min(top);
+        }
+        self.active_layer = self.active_layer.min(top);
+        self.layers = layers;
+        self.touch();
+    }
+
+    /// Returns the layer of the shape at `index`.
+    pub fn layer_of(&self, index: usize) -> Option<usize> {
+        self.id_of(index).map(|id| self.layer_of_id(id))
+    }
+
+    /// Returns the indices of a layer's shapes, which are always contiguous.
+    pub fn layer_range(&self, layer: usize) -> Range<usize> {
+        let start = self.ids.partition_point(|&id| self.layer_of_id(id) < layer);
+        let end = self
+            .ids
+            .partition_point(|&id| self.layer_of_id(id) <= layer);
+        start..end
+    }
+
+    /// Returns a layer's shapes in draw order.
+    pub fn layer_shapes(&self, layer: usize) -> &[Shape] {
+        &self.shapes[self.layer_range(layer)]
+    }
+
+    /// Returns a key that tells this frame apart from every other frame.
+    pub fn key(&self) -> u64 {
+        self.key
+    }
+
+    /// Returns a counter that grows whenever what the frame draws changes:
+    /// shapes being added, removed, changed or restacked, and layers being
+    /// shown or hidden.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Returns true if the shape with this ID was added or changed after the
+    /// frame was at `revision`.
+    pub fn changed_since(&self, id: ShapeId, revision: u64) -> bool {
+        self.changed_at.get(&id).is_none_or(|&at| at > revision)
+    }
+
+    /// Returns the shapes of visible layers with their IDs, in draw order.
+    pub fn visible_shapes(&self) -> impl Iterator<Item = (ShapeId, &Shape)> {
+        self.ids
+            .iter()
+            .zip(&self.shapes)
+            .filter(|(id, _)| self.layers[self.layer_of_id(**id)].visible)
+            .map(|(id, shape)| (*id, shape))
+    }
+
+    /// Takes a snapshot of the frame's shapes, groups and layers.
+    pub fn to_data(&self) -> FrameData {
+        FrameData {
+            shapes: self.shapes.clone(),
+            groups: self
+                .groups
+                .iter()
+                .map(|group| {
+                    let mut members: Vec<usize> = group
+                        .members
+                        .iter()
+                        .filter_map(|id| self.index_of(*id))
+                        .collect();
+                    members.sort_unstable();
+                    members
+                })
+                .collect(),
+            layers: self.layers.clone(),
+            shape_layers: self.ids.iter().map(|id| self.layer_of_id(*id)).collect(),
+            active_layer: self.active_layer,
+        }
+    }
+
+    /// Replaces everything in the frame with previously saved shapes.
+    ///
+    /// This is not an edit: the undo history starts out empty, so restored
+    /// shapes can't be undone away. Groups with fewer than two valid members,
+    /// or sharing a shape with an earlier group, are dropped. Data without
+    /// layers keeps the frame's layers and puts every shape on the bottom one.
+    pub fn load_data(&mut self, data: FrameData) {
+        if !data.layers.is_empty() {
+            self.layers = data.layers;
+        }
+        let top = self.layers.len() - 1;
+        self.active_layer = data.active_layer.min(top);
+        self.ids = data.shapes.iter().map(|_| self.allocate_id()).collect();
+        self.shape_layers = self
+            .ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| {
+                let layer = data.shape_layers.get(index).copied().unwrap_or(0);
+                (*id, layer.min(top))
+            })
+            .collect();
+        self.shapes = data.shapes;
+        self.groups.clear();
+        self.history.clear();
+        self.touch();
+        self.changed_at = self.ids.iter().map(|&id| (id, self.revision)).collect();
+
+        let mut grouped: Vec<ShapeId> = Vec::new();
+        for indices in data.groups {
+            let mut members: Vec<ShapeId> = indices
+                .into_iter()
+                .filter_map(|index| self.id_of(index))
+                .collect();
+            members.sort_unstable();
+            members.dedup();
+            if members.len() < 2 || members.iter().any(|id| grouped.contains(id)) {
+                continue;
+            }
+            grouped.extend(&members);
+            let id = self.allocate_id();
+            self.groups.push(ShapeGroup { id, members });
+        }
+
+        // Keep each layer's shapes together, in their saved order
+        let mut entries: Vec<(Shape, ShapeId)> =
+            self.shapes.drain(..).zip(self.ids.drain(..)).collect();
+        entries.sort_by_key(|(_, id)| self.shape_layers[id]);
+        (self.shapes, self.ids) = entries.into_iter().unzip();
+    }
+
+    /// Adds a new shape on top of the active layer.
+    ///
+    /// Returns the ID assigned to the shape.
+    pub fn add_shape(&mut self, shape: Shape) -> ShapeId {
+        self.insert_shape(self.shapes.len(), shape)
+    }
+
+    /// Adds several shapes on top of the active layer as a single undo step.
+    ///
+    /// Returns the indices the shapes ended up at.
+    pub fn add_shapes(&mut self, shapes: impl IntoIterator<Item = Shape>) -> Range<usize> {
+        let first = self.layer_range(self.active_layer).end;
+        let entries: Vec<ShapeEntry> = shapes
+            .into_iter()
+            .enumerate()
+            .map(|(offset, shape)| ShapeEntry {
+                index: first + offset,
+                id: self.allocate_shape_id(),
+                layer: self.active_layer,
+                shape,
+            })
+            .collect();
+        if entries.is_empty() {
+            return first..first;
+        }
+        self.insert_entries(&entries);
+        let added = first..first + entries.len();
+        self.history.record(Command::Add(entries));
+        added
+    }
+
+    /// Inserts a shape into the active layer at `index` in draw order,
+    /// clamped to the layer's shapes.
+    ///
+    /// Drawing on a hidden layer shows it again. Returns the ID assigned to
+    /// the shape.
+    pub fn insert_shape(&mut self, index: usize, shape: Shape) -> ShapeId {
+        let range = self.layer_range(self.active_layer);
+        let entry = ShapeEntry {
+            index: index.clamp(range.start, range.end),
+            id: self.allocate_shape_id(),
+            layer: self.active_layer,
+            shape,
+        };
+        let id = entry.id;
+        self.insert_entries(std::slice::from_ref(&entry));
+        self.history.record(Command::Add(vec![entry]));
+        id
+    }
+
+    /// Removes and returns the shape at `index`, if it exists.
+    ///
+    /// The shape leaves its group; a group left with a single shape is dissolved.
+    pub fn remove_shape(&mut self, index: usize) -> Option<Shape> {
+        self.remove_shapes(&[index]).pop()
+    }
+
+    /// Removes the shapes at the given indices as a single undo step.
+    ///
+    /// Returns the removed shapes in draw order. Indices that don't exist are
+    /// ignored.
+    pub fn remove_shapes(&mut self, indices: &[usize]) -> Vec<Shape> {
+        let mut indices: Vec<usize> = indices
+            .iter()
+            .copied()
+            .filter(|&index| index < self.shapes.len())
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.is_empty() {
+            return Vec::new();
+        }
+        let entries: Vec<ShapeEntry> = indices
+            .into_iter()
+            .map(|index| ShapeEntry {
+                index,
+                id: self.ids[index],
+                layer: self.layer_of_id(self.ids[index]),
+                shape: self.shapes[index].clone(),
+            })
+            .collect();
+        let groups = self.groups.clone();
+        self.remove_entries(&entries);
+        let removed = entries.iter().map(|entry| entry.shape.clone()).collect();
+        self.history.record(Command::Remove { entries, groups });
+        removed
+    }
+
+    /// Replaces the shape at `index`, recording the change.
+    ///
+    /// Returns the previous shape, or `None` if `index` does not exist.
+    pub fn replace_shape(&mut self, index: usize, shape: Shape) -> Option<Shape> {
+        let before = std::mem::replace(self.shape_mut(index)?, shape);
+        self.record_changes(vec![(index, before.clone())]);
+        Some(before)
+    }
+
+    /// Returns the shape at `index` to change it in place.
+    ///
+    /// The change is not recorded for undo; see [`Self::record_changes`].
+    pub fn shape_mut(&mut self, index: usize) -> Option<&mut Shape> {
+        let id = self.id_of(index)?;
+        self.mark_changed(id);
+        self.shapes.get_mut(index)
+    }
+
+    /// Records changes already made in place to `shapes` as a single undo step.
+    ///
+    /// `originals` holds each changed shape's index and its state before the
+    /// change; shapes that ended up unchanged are skipped.
+    pub fn record_changes(&mut self, originals: Vec<(usize, Shape)>) {
+        let changes: Vec<ShapeChange> = originals
+            .into_iter()
+            .filter_map(|(index, before)| {
+                let after = self.shapes.get(index)?;
+                (*after != before).then(|| ShapeChange {
+                    id: self.ids[index],
+                    before,
+                    after: after.clone(),
+                })
+            })
+            .collect();
+        if !changes.is_empty() {
+            self.history.record(Command::Modify(changes));
+        }
+    }
+
+    /// Takes a shape out of the frame without recording an edit.
+    ///
+    /// Used while a shape is edited elsewhere; the shape keeps its ID and group
+    /// and must be given back with [`Self::restore_shape`].
+    pub fn take_shape(&mut self, index: usize) -> Option<(ShapeId, Shape)> {
+        if index >= self.shapes.len() {
+            return None;
+        }
+        let id = self.ids.remove(index);
+        self.mark_removed(id);
+        Some((id, self.shapes.remove(index)))
+    }
+
+    /// Puts back a shape taken with [`Self::take_shape`] without recording an edit.
+    pub fn restore_shape(&mut self, index: usize, id: ShapeId, shape: Shape) {
+        let range = self.layer_range(self.layer_of_id(id));
+        let index = index.clamp(range.start, range.end);
+        self.shapes.insert(index, shape);
+        self.ids.insert(index, id);
+        self.mark_changed(id);
+    }
+
+    /// Returns the ID of the shape at `index`.
+    pub fn id_of(&self, index: usize) -> Option<ShapeId> {
+        self.ids.get(index).copied()
+    }
+
+    /// Returns the current index of the shape with the given ID.
+    pub fn index_of(&self, id: ShapeId) -> Option<usize> {
+        self.ids.iter().position(|shape_id| *shape_id == id)
+    }
+
+    /// Returns the group containing the shape at `index`, if any.
+    pub fn group_of(&self, index: usize) -> Option<&ShapeGroup> {
+        let id = self.id_of(index)?;
+        self.groups.iter().find(|group| group.members.contains(&id))
+    }
+
+    /// Returns `indices` plus every other member of the groups they belong to,
+    /// sorted in draw order.
+    pub fn with_groups(&self, indices: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        let mut expanded = Vec::new();
+        for index in indices {
+            if index >= self.shapes.len() {
+                continue;
+            }
+            match self.group_of(index) {
+                Some(group) => {
+                    expanded.extend(group.members.iter().filter_map(|id| self.index_of(*id)))
+                }
+                None => expanded.push(index),
+            }
+        }
+        expanded.sort_unstable();
+        expanded.dedup();
+        expanded
+    }
+
+    /// Groups the given shapes, together with any groups they already belong to.
+    ///
+    /// Returns the new group's ID, or `None` if fewer than two shapes are involved.
+    pub fn group(&mut self, indices: &[usize]) -> Option<ShapeId> {
+        let members: Vec<ShapeId> = self
+            .with_groups(indices.iter().copied())
+            .into_iter()
+            .filter_map(|index| self.id_of(index))
+            .collect();
+        if members.len() < 2 {
+            return None;
+        }
+        let before = self.groups.clone();
+        // Existing groups are merged into the new one
+        self.groups
+            .retain(|group| !group.members.iter().any(|id| members.contains(id)));
+        let id = self.allocate_id();
+        self.groups.push(ShapeGroup { id, members });
+        self.history.record(Command::Regroup {
+            before,
+            after: self.groups.clone(),
+        });
+        Some(id)
+    }
+
+    /// Dissolves every group containing one of the given shapes.
+    ///
+    /// Returns `true` if any group was dissolved.
+    pub fn ungroup(&mut self, indices: &[usize]) -> bool {
+        let ids: Vec<ShapeId> = indices
+            .iter()
+            .filter_map(|&index| self.id_of(index))
+            .collect();
+        let before = self.groups.clone();
+        self.groups
+            .retain(|group| !group.members.iter().any(|id| ids.contains(id)));
+        if self.groups.len() == before.len() {
+            return false;
+        }
+        self.history.record(Command::Regroup {
+            before,
+            after: self.groups.clone(),
+        });
+        true
+    }
+
+    /// Moves the given shapes above all others, keeping their relative order.
+    ///
+    /// Returns the shapes' new indices.
+    pub fn bring_to_front(&mut self, indices: &[usize]) -> Vec<usize> {
+        self.restack(indices, false)
+    }
+
+    /// Moves the given shapes below all others, keeping their relative order.
+    ///
+    /// Returns the shapes' new indices.
+    pub fn send_to_back(&mut self, indices: &[usize]) -> Vec<usize> {
+        self.restack(indices, true)
+    }
+
+    /// Returns the index of the topmost visible text shape under the point, if any.
+    pub fn text_shape_at(&self, x: i32, y: i32) -> Option<usize> {
+        (0..self.shapes.len()).rev().find(|&index| {
+            let shape = &self.shapes[index];
+            self.is_visible(index)
+                && matches!(shape, Shape::Text { .. })
+                && shape.hit_test(x, y, 0.0)
+        })
+    }
+
+    /// Returns the index of the topmost visible shape touching the point, if any.
+    ///
+    /// See [`Shape::hit_test`] for how `tolerance` applies.
+    pub fn shape_at(&self, x: i32, y: i32, tolerance: f64) -> Option<usize> {
+        (0..self.shapes.len())
+            .rev()
+            .find(|&index| self.is_visible(index) && self.shapes[index].hit_test(x, y, tolerance))
     }
 
-    /// Adds a new shape to the frame (drawn on top of existing shapes).
-    pub fn add_shape(&mut self, shape: Shape) {
-        self.shapes.push(shape);
+    /// Returns the indices of all visible shapes lying entirely inside
+    /// `region`, in draw order.
+    ///
+    /// Grouped shapes are only included if their whole group is inside.
+    pub fn shapes_within(&self, region: &BoundingBox) -> Vec<usize> {
+        let inside = |index: usize| {
+            self.shapes[index]
+                .bounding_box()
+                .is_some_and(|bounds| region.contains_box(&bounds))
+        };
+        (0..self.shapes.len())
+            .filter(|&index| {
+                self.is_visible(index)
+                    && inside(index)
+                    && self.group_of(index).is_none_or(|group| {
+                        group
+                            .members
+                            .iter()
+                            .filter_map(|id| self.index_of(*id))
+                            .all(inside)
+                    })
+            })
+            .collect()
     }
 
-    /// Removes the most recently added shape.
+    /// Reverts the most recent edit.
     ///
-    /// Returns `true` if a shape was removed, `false` if the frame was already empty.
+    /// Returns `true` if an edit was undone, `false` if there was nothing to undo.
     pub fn undo(&mut self) -> bool {
-        if self.shapes.is_empty() {
-            false
-        } else {
-            self.shapes.pop();
-            true
+        let Some(command) = self.history.take_undo() else {
+            return false;
+        };
+        self.revert(&command);
+        self.history.push_redo(command);
+        true
+    }
+
+    /// Records that the page with ID `page` was deleted, leaving this frame shown.
+    pub fn record_page_deleted(&mut self, page: u64) {
+        self.history.record(Command::PageDeleted(page));
+    }
+
+    /// Takes the ID of the deleted page to bring back, if restoring it is
+    /// the next step to undo.
+    pub fn take_deleted_page(&mut self) -> Option<u64> {
+        let Some(&Command::PageDeleted(page)) = self.history.last_undo() else {
+            return None;
+        };
+        self.history.take_undo();
+        Some(page)
+    }
+
+    /// Records that undo brought this page back after it was deleted as
+    /// `page`, so redo deletes it again.
+    pub fn record_page_restored(&mut self, page: u64) {
+        self.history.push_redo(Command::PageDeleted(page));
+    }
+
+    /// Takes the ID to delete this page under, if deleting it again is the
+    /// next step to redo.
+    pub fn take_page_to_delete(&mut self) -> Option<u64> {
+        let Some(&Command::PageDeleted(page)) = self.history.last_redo() else {
+            return None;
+        };
+        self.history.take_redo();
+        Some(page)
+    }
+
+    /// Records that redo deleted the page with ID `page` again, leaving this
+    /// frame shown.
+    ///
+    /// Unlike [`Self::record_page_deleted`], steps left to redo are kept.
+    pub fn redo_page_deleted(&mut self, page: u64) {
+        self.history.push_undo(Command::PageDeleted(page));
+    }
+
+    /// Reapplies the most recently undone edit.
+    ///
+    /// Returns `true` if an edit was redone, `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(command) = self.history.take_redo() else {
+            return false;
+        };
+        self.apply(&command);
+        self.history.push_undo(command);
+        true
+    }
+
+    fn allocate_id(&mut self) -> ShapeId {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Allocates the ID of a new shape on the active layer, showing the layer
+    /// if it was hidden.
+    fn allocate_shape_id(&mut self) -> ShapeId {
+        let id = self.allocate_id();
+        self.layers[self.active_layer].visible = true;
+        id
+    }
+
+    /// Notes a change to what the frame draws that no single shape covers,
+    /// like a restacking or a layer being hidden.
+    fn touch(&mut self) {
+        self.revision += 1;
+    }
+
+    /// Notes that the shape with this ID was added or changed.
+    fn mark_changed(&mut self, id: ShapeId) {
+        self.touch();
+        self.changed_at.insert(id, self.revision);
+    }
+
+    /// Notes that the shape with this ID left the frame.
+    fn mark_removed(&mut self, id: ShapeId) {
+        self.touch();
+        self.changed_at.remove(&id);
+    }
+
+    fn layer_of_id(&self, id: ShapeId) -> usize {
+        self.shape_layers.get(&id).copied().unwrap_or(0)
+    }
+
+    /// Returns whether the shape at `index` is on a visible layer.
+    fn is_visible(&self, index: usize) -> bool {
+        self.layer_of(index)
+            .is_some_and(|layer| self.layers[layer].visible)
+    }
+
+    /// Moves the given shapes (and their groups) to the bottom or top of
+    /// their layers.
+    ///
+    /// Returns the shapes' new indices.
+    fn restack(&mut self, indices: &[usize], to_back: bool) -> Vec<usize> {
+        let moving = self.with_groups(indices.iter().copied());
+        if moving.is_empty() {
+            return moving;
+        }
+        // Old indices in their new order, layer by layer
+        let mut order = Vec::with_capacity(self.shapes.len());
+        for layer in 0..self.layers.len() {
+            let (picked, rest): (Vec<usize>, Vec<usize>) = self
+                .layer_range(layer)
+                .partition(|index| moving.binary_search(index).is_ok());
+            if to_back {
+                order.extend(picked);
+                order.extend(rest);
+            } else {
+                order.extend(rest);
+                order.extend(picked);
+            }
+        }
+
+        let before = self.ids.clone();
+        let mut entries: Vec<Option<(Shape, ShapeId)>> = self
+            .shapes
+            .drain(..)
+            .zip(self.ids.drain(..))
+            .map(Some)
+            .collect();
+        (self.shapes, self.ids) = order
+            .iter()
+            .filter_map(|&index| entries[index].take())
+            .unzip();
+        if self.ids != before {
+            self.touch();
+            self.history.record(Command::Reorder {
+                before,
+                after: self.ids.clone(),
+            });
+        }
+        order
+            .iter()
+            .enumerate()
+            .filter(|(_, index)| moving.binary_search(index).is_ok())
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Performs a recorded command again.
+    fn apply(&mut self, command: &Command) {
+        match command {
+            Command::Add(entries) => self.insert_entries(entries),
+            Command::Remove { entries, .. } => self.remove_entries(entries),
+            Command::Modify(changes) => {
+                for change in changes {
+                    self.set_shape(change.id, &change.after);
+                }
+            }
+            Command::Reorder { after, .. } => self.reorder(after),
+            Command::Regroup { after, .. } => self.groups = after.clone(),
+            Command::PageDeleted(_) => {}
         }
     }
+
+    /// Undoes a recorded command.
+    fn revert(&mut self, command: &Command) {
+        match command {
+            Command::Add(entries) => self.remove_entries(entries),
+            Command::Remove { entries, groups } => {
+                self.insert_entries(entries);
+                self.groups = groups.clone();
+            }
+            Command::Modify(changes) => {
+                for change in changes {
+                    self.set_shape(change.id, &change.before);
+                }
+            }
+            Command::Reorder { before, .. } => self.reorder(before),
+            Command::Regroup { before, .. } => self.groups = before.clone(),
+            Command::PageDeleted(_) => {}
+        }
+    }
+
+    /// Inserts shapes at their recorded indices (given in ascending order).
+    ///
+    /// Layers dropped since the shapes were recorded fall back to the top layer.
+    fn insert_entries(&mut self, entries: &[ShapeEntry]) {
+        let top = self.layers.len() - 1;
+        for entry in entries {
+            let index = entry.index.min(self.shapes.len());
+            self.shapes.insert(index, entry.shape.clone());
+            self.ids.insert(index, entry.id);
+            self.shape_layers.insert(entry.id, entry.layer.min(top));
+            self.mark_changed(entry.id);
+        }
+    }
+
+    /// Removes the given shapes, dissolving groups left with a single shape.
+    fn remove_entries(&mut self, entries: &[ShapeEntry]) {
+        // Remove from the top down so lower indices stay valid
+        for entry in entries.iter().rev() {
+            if let Some(index) = self.index_of(entry.id) {
+                self.shapes.remove(index);
+                self.ids.remove(index);
+                self.shape_layers.remove(&entry.id);
+                self.mark_removed(entry.id);
+            }
+        }
+        for group in &mut self.groups {
+            group
+                .members
+                .retain(|member| !entries.iter().any(|entry| entry.id == *member));
+        }
+        self.groups.retain(|group| group.members.len() > 1);
+    }
+
+    /// Overwrites the shape with the given ID, if it is in the frame.
+    fn set_shape(&mut self, id: ShapeId, shape: &Shape) {
+        if let Some(index) = self.index_of(id) {
+            self.shapes[index] = shape.clone();
+            self.mark_changed(id);
+        }
+    }
+
+    /// Rearranges the shapes into the given order of IDs.
+    fn reorder(&mut self, order: &[ShapeId]) {
+        let mut shapes: Vec<Option<Shape>> = self.shapes.drain(..).map(Some).collect();
+        let old_ids = std::mem::take(&mut self.ids);
+        for &id in order {
+            if let Some(index) = old_ids.iter().position(|old| *old == id)
+                && let Some(shape) = shapes[index].take()
+            {
+                self.shapes.push(shape);
+                self.ids.push(id);
+            }
+        }
+        self.touch();
+    }
+}
+
+/// Builds visible layers from their names, falling back to the default layers.
+fn layers_named<S: AsRef<str>>(names: &[S]) -> Vec<Layer> {
+    if names.is_empty() {
+        return DEFAULT_LAYER_NAMES
+            .iter()
+            .map(|name| Layer::new(*name))
+            .collect();
+    }
+    names.iter().map(|name| Layer::new(name.as_ref())).collect()
+}
+
+impl Serialize for Frame {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_data().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Frame {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut frame = Frame::new();
+        frame.load_data(FrameData::deserialize(deserializer)?);
+        Ok(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::RED;
+
+    fn rect(x: i32) -> Shape {
+        Shape::Rect {
+            x,
+            y: 0,
+            w: 10,
+            h: 10,
+            color: RED,
+            thick: 2.0,
+            rotation: 0.0,
+        }
+    }
+
+    fn xs(frame: &Frame) -> Vec<i32> {
+        frame
+            .shapes
+            .iter()
+            .map(|shape| match shape {
+                Shape::Rect { x, .. } => *x,
+                other => panic!("Expected a rectangle, got {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ids_stay_with_shapes_when_restacking() {
+        let mut frame = Frame::new();
+        let ids: Vec<ShapeId> = (0..4).map(|i| frame.add_shape(rect(i * 20))).collect();
+
+        assert_eq!(frame.bring_to_front(&[0, 2]), vec![2, 3]);
+        assert_eq!(xs(&frame), vec![20, 60, 0, 40]);
+        assert_eq!(frame.index_of(ids[0]), Some(2));
+
+        assert_eq!(frame.send_to_back(&[3]), vec![0]);
+        assert_eq!(xs(&frame), vec![40, 20, 60, 0]);
+        assert_eq!(frame.id_of(0), Some(ids[2]));
+    }
+
+    #[test]
+    fn groups_act_as_one_unit() {
+        let mut frame = Frame::new();
+        for i in 0..4 {
+            frame.add_shape(rect(i * 20));
+        }
+        assert_eq!(frame.group(&[0]), None, "a group needs two shapes");
+        frame.group(&[0, 3]).unwrap();
+        assert_eq!(frame.with_groups([3]), vec![0, 3]);
+
+        // Restacking moves the whole group
+        assert_eq!(frame.send_to_back(&[3]), vec![0, 1]);
+        assert_eq!(xs(&frame), vec![0, 60, 20, 40]);
+
+        // A region must enclose the whole group to pick it
+        let region = BoundingBox::from_corners(-5.0, -5.0, 35.0, 15.0);
+        assert_eq!(frame.shapes_within(&region), vec![2]);
+
+        // Deleting a group and undoing brings the whole group back
+        frame.bring_to_front(&[0]);
+        frame.remove_shapes(&frame.with_groups([2]));
+        assert_eq!(xs(&frame), vec![20, 40]);
+        assert!(frame.group_of(0).is_none() && frame.group_of(1).is_none());
+        assert!(frame.undo());
+        assert_eq!(xs(&frame), vec![20, 40, 0, 60]);
+        assert_eq!(frame.with_groups([3]), vec![2, 3]);
+    }
+
+    #[test]
+    fn grouping_merges_and_ungrouping_dissolves() {
+        let mut frame = Frame::new();
+        for i in 0..4 {
+            frame.add_shape(rect(i * 20));
+        }
+        frame.group(&[0, 1]).unwrap();
+        let merged = frame.group(&[1, 2]).unwrap();
+        assert_eq!(frame.group_of(0).map(|group| group.id), Some(merged));
+        assert_eq!(frame.with_groups([2]), vec![0, 1, 2]);
+
+        // Removing members dissolves a group once one shape is left
+        frame.remove_shape(0);
+        frame.remove_shape(0);
+        assert!(frame.group_of(0).is_none());
+
+        frame.group(&[0, 1]).unwrap();
+        assert!(frame.ungroup(&[1]));
+        assert!(!frame.ungroup(&[1]));
+        assert_eq!(frame.with_groups([1]), vec![1]);
+    }
+
+    #[test]
+    fn every_edit_can_be_undone_and_redone() {
+        let mut frame = Frame::new();
+        frame.add_shapes([rect(0), rect(20), rect(40)]);
+        frame.replace_shape(1, rect(25));
+        frame.bring_to_front(&[0]);
+        frame.clear();
+        assert!(frame.shapes.is_empty());
+
+        assert!(frame.undo());
+        assert_eq!(xs(&frame), vec![25, 40, 0], "clear is recoverable");
+        assert!(frame.undo());
+        assert_eq!(xs(&frame), vec![0, 25, 40]);
+        assert!(frame.undo());
+        assert_eq!(xs(&frame), vec![0, 20, 40]);
+        assert!(frame.undo());
+        assert!(frame.shapes.is_empty(), "adding several shapes is one step");
+        assert!(!frame.undo());
+
+        assert!(frame.redo());
+        assert!(frame.redo());
+        assert_eq!(xs(&frame), vec![0, 25, 40]);
+
+        // A new edit drops what could be redone
+        frame.remove_shape(0);
+        assert!(!frame.redo());
+        assert!(frame.undo());
+        assert_eq!(xs(&frame), vec![0, 25, 40]);
+    }
+
+    #[test]
+    fn history_limit_caps_undo_steps() {
+        let mut frame = Frame::new();
+        frame.set_history_limit(2);
+        for i in 0..4 {
+            frame.add_shape(rect(i * 20));
+        }
+        assert!(frame.undo());
+        assert!(frame.undo());
+        assert!(!frame.undo());
+        assert_eq!(xs(&frame), vec![0, 20]);
+    }
+
+    #[test]
+    fn frames_round_trip_through_json_with_their_groups() {
+        let mut frame = Frame::new();
+        frame.add_shapes([rect(0), rect(20), rect(40)]);
+        frame.group(&[0, 2]).unwrap();
+
+        let json = serde_json::to_string(&frame).unwrap();
+        let mut loaded: Frame = serde_json::from_str(&json).unwrap();
+        assert_eq!(xs(&loaded), vec![0, 20, 40]);
+        assert_eq!(loaded.with_groups([2]), vec![0, 2]);
+        assert!(!loaded.undo(), "loading is not an edit");
+
+        // Invalid or overlapping groups are dropped
+        loaded.load_data(FrameData {
+            shapes: vec![rect(0), rect(20)],
+            groups: vec![vec![0, 7], vec![0, 1], vec![1, 0]],
+            ..FrameData::default()
+        });
+        assert_eq!(loaded.to_data().groups, vec![vec![0, 1]]);
+    }
+
+    #[test]
+    fn new_shapes_go_to_the_active_layer() {
+        let mut frame = Frame::with_layers(&["Base", "Scratch"]);
+        frame.add_shape(rect(0));
+        frame.next_layer();
+        frame.add_shape(rect(20));
+        frame.next_layer();
+        assert_eq!(frame.active_layer(), 0, "next_layer wraps around");
+        frame.add_shape(rect(40));
+
+        // Each layer's shapes stay together, bottom layer first
+        assert_eq!(xs(&frame), vec![0, 40, 20]);
+        assert_eq!(frame.layer_range(0), 0..2);
+        assert_eq!(frame.layer_of(2), Some(1));
+
+        // Pasted shapes land on top of the active layer
+        assert_eq!(frame.add_shapes([rect(60), rect(80)]), 2..4);
+        assert_eq!(xs(&frame), vec![0, 40, 60, 80, 20]);
+
+        // Restacking stays within each layer
+        assert_eq!(frame.bring_to_front(&[0, 4]), vec![3, 4]);
+        assert_eq!(xs(&frame), vec![40, 60, 80, 0, 20]);
+        assert_eq!(frame.send_to_back(&[3]), vec![0]);
+    }
+
+    #[test]
+    fn layers_hide_and_clear_on_their_own() {
+        let mut frame = Frame::with_layers(&["Base", "Scratch"]);
+        frame.add_shape(rect(0));
+        frame.next_layer();
+        frame.add_shapes([rect(20), rect(40)]);
+
+        assert!(!frame.toggle_layer_visibility());
+        let visible: Vec<ShapeId> = frame.visible_shapes().map(|(id, _)| id).collect();
+        assert_eq!(visible, vec![frame.id_of(0).unwrap()]);
+        assert_eq!(
+            frame.shape_at(20, 5, 0.0),
+            None,
+            "hidden shapes can't be picked"
+        );
+        assert_eq!(frame.shape_at(0, 5, 0.0), Some(0));
+
+        // Drawing on a hidden layer shows it again
+        frame.add_shape(rect(60));
+        assert!(frame.layers()[1].visible);
+        assert_eq!(frame.shape_at(20, 5, 0.0), Some(1));
+
+        frame.clear_layer();
+        assert_eq!(xs(&frame), vec![0]);
+        assert_eq!(frame.shape_layers.len(), 1, "removed shapes are forgotten");
+        assert!(frame.undo());
+        assert_eq!(xs(&frame), vec![0, 20, 40, 60]);
+        assert_eq!(frame.shape_layers.len(), 4);
+        assert_eq!(
+            frame.layer_range(1),
+            1..4,
+            "undo puts shapes back on their layer"
+        );
+    }
+
+    #[test]
+    fn layers_round_trip_and_can_be_renamed() {
+        let mut frame = Frame::with_layers(&["Diagram", "Ink", "Notes"]);
+        frame.add_shape(rect(0));
+        frame.next_layer();
+        frame.next_layer();
+        frame.add_shape(rect(20));
+        frame.toggle_layer_visibility();
+
+        let mut loaded = Frame::new();
+        loaded.load_data(frame.to_data());
+        assert_eq!(loaded.layers(), frame.layers());
+        assert_eq!(loaded.active_layer(), 2);
+        assert_eq!(loaded.layer_of(1), Some(2));
+
+        // Shapes on dropped layers move to the new top layer
+        loaded.set_layer_names(&["Base", "Scratch"]);
+        assert_eq!(loaded.layers()[1].name, "Scratch");
+        assert_eq!(loaded.active_layer(), 1);
+        assert_eq!(loaded.layer_range(1), 1..2);
+
+        // Data saved without layers keeps the frame's own, with every shape at the bottom
+        loaded.load_data(FrameData {
+            shapes: vec![rect(0), rect(20)],
+            ..FrameData::default()
+        });
+        assert_eq!(loaded.layers().len(), 2);
+        assert_eq!(loaded.layer_range(0), 0..2);
+
+        // Shapes are regrouped by layer, keeping their saved order within each
+        loaded.load_data(FrameData {
+            shapes: vec![rect(0), rect(20), rect(40)],
+            groups: vec![vec![0, 2]],
+            layers: vec![Layer::new("Base"), Layer::new("Scratch")],
+            shape_layers: vec![1, 0, 1],
+            active_layer: 0,
+        });
+        assert_eq!(xs(&loaded), vec![20, 0, 40]);
+        assert_eq!(loaded.with_groups([1]), vec![1, 2]);
+    }
 }
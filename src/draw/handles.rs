@@ -0,0 +1,176 @@
+//! Transform handles drawn around selected shapes, and their geometry.
+
+use super::shape::BoundingBox;
+
+/// Gap in pixels between the selected shapes and the selection outline.
+pub const SELECTION_PADDING: f64 = 4.0;
+
+/// Side length of a handle square in pixels.
+pub const HANDLE_SIZE: f64 = 8.0;
+
+/// Distance in pixels of the rotate handle above the selection outline.
+pub const ROTATE_HANDLE_OFFSET: f64 = 24.0;
+
+/// Transform handles drawn around a selection.
+///
+/// The eight outline handles scale the selection away from the opposite
+/// handle; the rotate handle above the top edge turns it around its center.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionHandle {
+    TopLeft,
+    Top,
+    TopRight,
+    Right,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    Left,
+    Rotate,
+}
+
+impl SelectionHandle {
+    /// Every handle, in drawing order.
+    pub const ALL: [Self; 9] = [
+        Self::TopLeft,
+        Self::Top,
+        Self::TopRight,
+        Self::Right,
+        Self::BottomRight,
+        Self::Bottom,
+        Self::BottomLeft,
+        Self::Left,
+        Self::Rotate,
+    ];
+
+    /// Returns the handle's center for a selection enclosing `bounds`.
+    pub fn position(self, bounds: &BoundingBox) -> (f64, f64) {
+        let outline = bounds.inflate(SELECTION_PADDING);
+        let (mid_x, mid_y) = outline.center();
+        match self {
+            Self::TopLeft => (outline.x, outline.y),
+            Self::Top => (mid_x, outline.y),
+            Self::TopRight => (outline.right(), outline.y),
+            Self::Right => (outline.right(), mid_y),
+            Self::BottomRight => (outline.right(), outline.bottom()),
+            Self::Bottom => (mid_x, outline.bottom()),
+            Self::BottomLeft => (outline.x, outline.bottom()),
+            Self::Left => (outline.x, mid_y),
+            Self::Rotate => (mid_x, outline.y - ROTATE_HANDLE_OFFSET),
+        }
+    }
+
+    /// Returns the area covered by the outline and handles drawn around `bounds`.
+    pub fn extent(bounds: &BoundingBox) -> BoundingBox {
+        let outline = bounds.inflate(SELECTION_PADDING + HANDLE_SIZE / 2.0);
+        let (rotate_x, rotate_y) = Self::Rotate.position(bounds);
+        outline.union(&BoundingBox::from_corners(
+            rotate_x - HANDLE_SIZE / 2.0,
+            rotate_y - HANDLE_SIZE / 2.0,
+            rotate_x + HANDLE_SIZE / 2.0,
+            rotate_y + HANDLE_SIZE / 2.0,
+        ))
+    }
+
+    /// Returns the point that stays fixed while this handle is dragged.
+    ///
+    /// Scale handles keep the opposite handle in place; rotation turns
+    /// around the center of the selected shapes.
+    pub fn anchor(self, bounds: &BoundingBox) -> (f64, f64) {
+        let opposite = match self {
+            Self::TopLeft => Self::BottomRight,
+            Self::Top => Self::Bottom,
+            Self::TopRight => Self::BottomLeft,
+            Self::Right => Self::Left,
+            Self::BottomRight => Self::TopLeft,
+            Self::Bottom => Self::Top,
+            Self::BottomLeft => Self::TopRight,
+            Self::Left => Self::Right,
+            Self::Rotate => return bounds.center(),
+        };
+        opposite.position(bounds)
+    }
+
+    /// Returns the handle under the point for a selection enclosing `bounds`, if any.
+    pub fn at(bounds: &BoundingBox, x: f64, y: f64) -> Option<Self> {
+        let reach = HANDLE_SIZE / 2.0 + 2.0;
+        Self::ALL.into_iter().find(|handle| {
+            let (hx, hy) = handle.position(bounds);
+            (x - hx).abs() <= reach && (y - hy).abs() <= reach
+        })
+    }
+
+    /// Returns the scale factors for dragging this handle from `start` to `current`.
+    ///
+    /// Edge handles only scale along their own axis. Returns `None` for the
+    /// rotate handle.
+    pub fn scale_factors(
+        self,
+        bounds: &BoundingBox,
+        start: (f64, f64),
+        current: (f64, f64),
+    ) -> Option<(f64, f64)> {
+        if self == Self::Rotate {
+            return None;
+        }
+        let (anchor_x, anchor_y) = self.anchor(bounds);
+        let factor = |start: f64, current: f64, anchor: f64| {
+            let span = start - anchor;
+            if span.abs() < f64::EPSILON {
+                1.0
+            } else {
+                let factor = (current - anchor) / span;
+                // Keep a sliver of size so shapes can be dragged back out
+                if factor.abs() < MIN_SCALE {
+                    MIN_SCALE.copysign(factor)
+                } else {
+                    factor
+                }
+            }
+        };
+        let factor_x = match self {
+            Self::Top | Self::Bottom => 1.0,
+            _ => factor(start.0, current.0, anchor_x),
+        };
+        let factor_y = match self {
+            Self::Left | Self::Right => 1.0,
+            _ => factor(start.1, current.1, anchor_y),
+        };
+        Some((factor_x, factor_y))
+    }
+}
+
+/// Smallest scale factor a handle drag produces.
+const MIN_SCALE: f64 = 0.05;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_scale_away_from_the_opposite_side() {
+        let bounds = BoundingBox::from_corners(0.0, 0.0, 100.0, 50.0);
+        let (hx, hy) = SelectionHandle::BottomRight.position(&bounds);
+        assert_eq!(
+            SelectionHandle::at(&bounds, hx + 1.0, hy - 1.0),
+            Some(SelectionHandle::BottomRight)
+        );
+        assert_eq!(SelectionHandle::at(&bounds, 50.0, 25.0), None);
+
+        let (ax, ay) = SelectionHandle::BottomRight.anchor(&bounds);
+        let current = (ax + (hx - ax) * 2.0, ay + (hy - ay) * 0.5);
+        assert_eq!(
+            SelectionHandle::BottomRight.scale_factors(&bounds, (hx, hy), current),
+            Some((2.0, 0.5))
+        );
+
+        let (tx, ty) = SelectionHandle::Top.position(&bounds);
+        let (fx, _) = SelectionHandle::Top
+            .scale_factors(&bounds, (tx, ty), (tx + 40.0, ty - 10.0))
+            .unwrap();
+        assert_eq!(fx, 1.0, "edge handles keep the other axis");
+        assert_eq!(
+            SelectionHandle::Rotate.scale_factors(&bounds, (0.0, 0.0), (1.0, 1.0)),
+            None
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Undo/redo history of frame edits.
+
+use super::frame::{ShapeGroup, ShapeId};
+use super::shape::Shape;
+
+/// Number of undo steps kept per frame unless configured otherwise.
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// A shape together with its identity and position in draw order.
+#[derive(Debug, Clone)]
+pub struct ShapeEntry {
+    /// Index of the shape in draw order
+    pub index: usize,
+    /// Stable ID of the shape
+    pub id: ShapeId,
+    /// Layer the shape is on
+    pub layer: usize,
+    /// The shape itself
+    pub shape: Shape,
+}
+
+/// A reversible edit to a frame.
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Shapes were inserted at the recorded indices (ascending)
+    Add(Vec<ShapeEntry>),
+    /// Shapes were removed from the recorded indices (ascending), along with
+    /// the groups as they were before the removal
+    Remove {
+        entries: Vec<ShapeEntry>,
+        groups: Vec<ShapeGroup>,
+    },
+    /// Shapes were changed in place (moved, transformed or edited)
+    Modify(Vec<ShapeChange>),
+    /// The draw order changed
+    Reorder {
+        before: Vec<ShapeId>,
+        after: Vec<ShapeId>,
+    },
+    /// Shapes were grouped or ungrouped
+    Regroup {
+        before: Vec<ShapeGroup>,
+        after: Vec<ShapeGroup>,
+    },
+    /// The page with this ID was deleted, leaving this frame shown.
+    ///
+    /// Leaves the frame itself untouched; the board restores the page when
+    /// this is the next step to undo. Once restored, the page holds it as its
+    /// next step to redo, which deletes the page again.
+    PageDeleted(u64),
+}
+
+/// A shape's state before and after a [`Command::Modify`].
+#[derive(Debug, Clone)]
+pub struct ShapeChange {
+    /// Stable ID of the changed shape
+    pub id: ShapeId,
+    /// Shape before the change
+    pub before: Shape,
+    /// Shape after the change
+    pub after: Shape,
+}
+
+/// Undo and redo stacks for one frame.
+///
+/// Recording a new command discards everything that could be redone. Once
+/// more than `limit` commands are recorded, the oldest ones are forgotten.
+#[derive(Debug)]
+pub struct History {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+    limit: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl History {
+    /// Creates an empty history with the default limit.
+    pub const fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            limit: DEFAULT_HISTORY_LIMIT,
+        }
+    }
+
+    /// Changes the number of undo steps kept, dropping the oldest if needed.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    /// Forgets every recorded command, keeping the limit.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Records a command that has just been applied.
+    pub fn record(&mut self, command: Command) {
+        if self.limit == 0 {
+            return;
+        }
+        self.redo.clear();
+        self.undo.push(command);
+        self.trim();
+    }
+
+    /// Takes the most recent command to undo.
+    pub fn take_undo(&mut self) -> Option<Command> {
+        self.undo.pop()
+    }
+
+    /// Returns the most recent command to undo, without taking it.
+    pub fn last_undo(&self) -> Option<&Command> {
+        self.undo.last()
+    }
+
+    /// Returns the most recently undone command, without taking it.
+    pub fn last_redo(&self) -> Option<&Command> {
+        self.redo.last()
+    }
+
+    /// Takes the most recently undone command to redo.
+    pub fn take_redo(&mut self) -> Option<Command> {
+        self.redo.pop()
+    }
+
+    /// Stores a command that was just undone, so it can be redone.
+    pub fn push_redo(&mut self, command: Command) {
+        self.redo.push(command);
+    }
+
+    /// Stores a command that was just redone, keeping later redo steps.
+    pub fn push_undo(&mut self, command: Command) {
+        self.undo.push(command);
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        if self.undo.len() > self.limit {
+            let excess = self.undo.len() - self.limit;
+            self.undo.drain(..excess);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reorder(id: ShapeId) -> Command {
+        Command::Reorder {
+            before: vec![id],
+            after: vec![id],
+        }
+    }
+
+    #[test]
+    fn limit_drops_oldest_and_recording_clears_redo() {
+        let mut history = History::new();
+        history.set_limit(2);
+        for id in 1..=3 {
+            history.record(reorder(id));
+        }
+        let newest = history.take_undo().unwrap();
+        history.push_redo(newest);
+        let older = history.take_undo().unwrap();
+        assert!(matches!(older, Command::Reorder { before, .. } if before == vec![2]));
+        assert!(
+            history.take_undo().is_none(),
+            "the first command was dropped"
+        );
+
+        history.record(reorder(4));
+        assert!(history.take_redo().is_none());
+
+        history.set_limit(0);
+        history.record(reorder(5));
+        assert!(history.take_undo().is_none());
+    }
+}
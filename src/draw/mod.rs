@@ -6,22 +6,31 @@
 //! - [`Frame`]: Container for all shapes in the current drawing
 //! - Rendering functions for Cairo-based output
 
+pub mod background;
+pub mod cache;
 pub mod canvas_set;
 pub mod color;
+pub mod damage;
 pub mod font;
 pub mod frame;
+pub mod handles;
+pub mod history;
 pub mod render;
 pub mod shape;
+pub mod text;
 
 // Re-export commonly used types at module level
+pub use background::{BackgroundImages, BoardBackground};
 pub use canvas_set::CanvasSet;
 pub use color::Color;
 pub use font::FontDescriptor;
-pub use frame::Frame;
+pub use frame::{Frame, FrameData};
 pub use render::{
-    render_board_background, render_freehand_borrowed, render_shape, render_shapes, render_text,
+    render_board_background, render_freehand_borrowed, render_selection_handles,
+    render_selection_region, render_shape,
 };
-pub use shape::Shape;
+pub use shape::{BoundingBox, Shape};
+pub use text::{TextEffect, TextFormat, render_text_caret};
 
 // Re-export color constants for public API (unused internally but part of public interface)
 #[allow(unused_imports)]
@@ -29,4 +38,8 @@ pub use color::{BLACK, BLUE, GREEN, ORANGE, PINK, RED, TRANSPARENT, WHITE, YELLO
 
 // Re-export utility functions for public API (unused internally but part of public interface)
 #[allow(unused_imports)]
-pub use render::fill_transparent;
+pub use render::{fill_transparent, render_shapes, render_text};
+
+// Re-export shape identity and layer types for public API
+#[allow(unused_imports)]
+pub use frame::{Layer, ShapeGroup, ShapeId};
@@ -1,42 +1,60 @@
 //! Cairo-based rendering functions for shapes.
 
+use super::background::BoardBackground;
 use super::color::Color;
-use super::shape::Shape;
-use crate::config::BoardConfig;
-use crate::input::BoardMode;
+use super::frame::Frame;
+use super::handles::{HANDLE_SIZE, SELECTION_PADDING, SelectionHandle};
+use super::shape::{BoundingBox, Shape};
+use super::text::TextEffect;
 use crate::util;
 
-/// Renders board background for whiteboard/blackboard modes.
+/// Renders a board background: its fill color, then its image.
 ///
-/// This function fills the entire canvas with a solid color when in
-/// whiteboard or blackboard mode. For transparent mode, it does nothing
-/// (background remains transparent).
+/// This function fills the entire canvas with the board's color (if any)
+/// and draws the background image on top, scaled to the canvas size. For
+/// transparent mode without an image, it does nothing (background remains
+/// transparent).
 ///
 /// Should be called after clearing the canvas but before rendering shapes.
 ///
 /// # Arguments
 /// * `ctx` - Cairo drawing context to render to
-/// * `mode` - Current board mode
-/// * `config` - Board configuration with color settings
-pub fn render_board_background(ctx: &cairo::Context, mode: BoardMode, config: &BoardConfig) {
-    if let Some(bg_color) = mode.background_color(config) {
+/// * `background` - Background of the current board
+/// * `width` - Canvas width in pixels
+/// * `height` - Canvas height in pixels
+pub fn render_board_background(
+    ctx: &cairo::Context,
+    background: &BoardBackground,
+    width: f64,
+    height: f64,
+) {
+    if let Some(bg_color) = background.color {
         ctx.set_source_rgba(bg_color.r, bg_color.g, bg_color.b, bg_color.a);
         let _ = ctx.paint(); // Ignore errors - if paint fails, we'll just have transparent bg
     }
-    // If None (Transparent mode), do nothing - background stays transparent
+    if let Some((image, fit)) = &background.image
+        && let Err(err) = image.paint(ctx, *fit, width, height)
+    {
+        log::warn!("Failed to draw background image: {}", err);
+    }
 }
 
-/// Renders all shapes in a collection to a Cairo context.
+/// Renders all shapes of a frame to a Cairo context.
 ///
-/// Iterates through the shapes slice and renders each one in order.
-/// Shapes are drawn in the order they appear (first shape = bottom layer).
+/// Goes through the frame's layers bottom first, skipping hidden ones, and
+/// renders each layer's shapes in draw order (first shape = bottom).
 ///
 /// # Arguments
 /// * `ctx` - Cairo drawing context to render to
-/// * `shapes` - Slice of shapes to render
-pub fn render_shapes(ctx: &cairo::Context, shapes: &[Shape]) {
-    for shape in shapes {
-        render_shape(ctx, shape);
+/// * `frame` - Frame whose shapes to render
+pub fn render_shapes(ctx: &cairo::Context, frame: &Frame) {
+    for (index, layer) in frame.layers().iter().enumerate() {
+        if !layer.visible {
+            continue;
+        }
+        for shape in frame.layer_shapes(index) {
+            render_shape(ctx, shape);
+        }
     }
 }
 
@@ -49,6 +67,23 @@ pub fn render_shapes(ctx: &cairo::Context, shapes: &[Shape]) {
 /// * `ctx` - Cairo drawing context to render to
 /// * `shape` - The shape to render
 pub fn render_shape(ctx: &cairo::Context, shape: &Shape) {
+    // Rotated shapes are drawn in their own frame through the cairo matrix
+    if let Some((cx, cy, rotation)) = shape.rotation_center()
+        && rotation != 0.0
+    {
+        ctx.save().ok();
+        ctx.translate(cx, cy);
+        ctx.rotate(rotation);
+        ctx.translate(-cx, -cy);
+        render_shape_unrotated(ctx, shape);
+        ctx.restore().ok();
+    } else {
+        render_shape_unrotated(ctx, shape);
+    }
+}
+
+/// Renders a shape ignoring its rotation.
+fn render_shape_unrotated(ctx: &cairo::Context, shape: &Shape) {
     match shape {
         Shape::Freehand {
             points,
@@ -74,6 +109,7 @@ pub fn render_shape(ctx: &cairo::Context, shape: &Shape) {
             h,
             color,
             thick,
+            ..
         } => {
             render_rect(ctx, *x, *y, *w, *h, *color, *thick);
         }
@@ -84,6 +120,7 @@ pub fn render_shape(ctx: &cairo::Context, shape: &Shape) {
             ry,
             color,
             thick,
+            ..
         } => {
             render_ellipse(ctx, *cx, *cy, *rx, *ry, *color, *thick);
         }
@@ -116,7 +153,9 @@ pub fn render_shape(ctx: &cairo::Context, shape: &Shape) {
             color,
             size,
             font_descriptor,
-            background_enabled,
+            effect,
+            format,
+            ..
         } => {
             render_text(
                 ctx,
@@ -126,7 +165,8 @@ pub fn render_shape(ctx: &cairo::Context, shape: &Shape) {
                 *color,
                 *size,
                 font_descriptor,
-                *background_enabled,
+                effect,
+                format,
             );
         }
     }
@@ -261,12 +301,13 @@ fn render_arrow(
 /// Renders text at a specified position with multi-line support using Pango.
 ///
 /// Uses Pango for advanced font rendering with custom font support. The position (x, y)
-/// represents the text baseline starting point for the first line.
+/// represents the text baseline of the first line, at the left edge, center or right
+/// edge depending on the alignment in `format`.
 /// Text containing newline characters ('\n') will be rendered across multiple lines
-/// with proper line spacing determined by the font metrics.
+/// with line spacing determined by the font metrics and `format`.
 ///
-/// Text is rendered with a contrasting stroke outline for better visibility
-/// against any background color.
+/// A readability effect (background box, outline or drop shadow) is drawn
+/// with the text so it stays visible against any background color.
 ///
 /// # Arguments
 /// * `ctx` - Cairo drawing context to render to
@@ -276,7 +317,8 @@ fn render_arrow(
 /// * `color` - Text color
 /// * `size` - Font size in points
 /// * `font_descriptor` - Font configuration (family, weight, style)
-/// * `background_enabled` - Whether to draw background box behind text
+/// * `effect` - Readability effect drawn with the text
+/// * `format` - Paragraph format (alignment, wrapping, line spacing)
 #[allow(clippy::too_many_arguments)]
 pub fn render_text(
     ctx: &cairo::Context,
@@ -286,7 +328,8 @@ pub fn render_text(
     color: Color,
     size: f64,
     font_descriptor: &super::FontDescriptor,
-    background_enabled: bool,
+    effect: &TextEffect,
+    format: &super::text::TextFormat,
 ) {
     // Save context state to prevent settings from leaking to other drawing operations
     ctx.save().ok();
@@ -296,74 +339,174 @@ pub fn render_text(
     ctx.set_antialias(cairo::Antialias::Best);
 
     // Create Pango layout for text rendering
-    let layout = pangocairo::functions::create_layout(ctx);
+    let layout = super::text::create_text_layout(ctx, text, size, font_descriptor, format);
 
-    // Set font description from config
-    let font_desc_str = font_descriptor.to_pango_string(size);
-    let font_desc = pango::FontDescription::from_string(&font_desc_str);
-    layout.set_font_description(Some(&font_desc));
+    // Adjust position (Pango measures from top-left, we want baseline)
+    let (adjusted_x, adjusted_y) = super::text::layout_origin(&layout, x, y);
 
-    // Set the text (Pango handles newlines automatically)
-    layout.set_text(text);
+    match *effect {
+        TextEffect::None => {}
+        TextEffect::Background {
+            color: effect_color,
+            padding,
+        } => {
+            // Include ink rect offsets for italic glyphs with negative bearings
+            let (ink_rect, _logical_rect) = layout.extents();
+            let ink_x = ink_rect.x() as f64 / pango::SCALE as f64;
+            let ink_y = ink_rect.y() as f64 / pango::SCALE as f64;
+            let ink_width = ink_rect.width() as f64 / pango::SCALE as f64;
+            let ink_height = ink_rect.height() as f64 / pango::SCALE as f64;
+
+            if ink_width > 0.0 && ink_height > 0.0 {
+                let bg = effect_color.unwrap_or_else(|| contrast_color(color, 0.3));
+                ctx.rectangle(
+                    adjusted_x + ink_x - padding,
+                    adjusted_y + ink_y - padding,
+                    ink_width + padding * 2.0,
+                    ink_height + padding * 2.0,
+                );
+                ctx.set_source_rgba(bg.r, bg.g, bg.b, bg.a);
+                let _ = ctx.fill();
+            }
+        }
+        TextEffect::Shadow {
+            color: effect_color,
+            offset,
+        } => {
+            let shadow = effect_color.unwrap_or(DEFAULT_SHADOW_COLOR);
+            ctx.move_to(adjusted_x + offset, adjusted_y + offset);
+            ctx.set_source_rgba(shadow.r, shadow.g, shadow.b, shadow.a);
+            pangocairo::functions::show_layout(ctx, &layout);
+        }
+        TextEffect::Outline {
+            color: effect_color,
+            width,
+        } => {
+            // Stroke the glyph outlines; the fill below covers the inner half
+            let outline = effect_color.unwrap_or_else(|| contrast_color(color, 1.0));
+            ctx.move_to(adjusted_x, adjusted_y);
+            pangocairo::functions::layout_path(ctx, &layout);
+            ctx.set_source_rgba(outline.r, outline.g, outline.b, outline.a);
+            ctx.set_line_width(width * 2.0);
+            ctx.set_line_join(cairo::LineJoin::Round);
+            let _ = ctx.stroke();
+        }
+    }
 
-    // Get layout extents for background and effects
-    let (ink_rect, _logical_rect) = layout.extents();
+    // Fill with bright, full-intensity color
+    ctx.move_to(adjusted_x, adjusted_y);
+    ctx.set_source_rgba(color.r, color.g, color.b, color.a);
+    if ctx.target().type_() == cairo::SurfaceType::Pdf {
+        // Keep glyphs as real text in documents so it can be selected and searched
+        pangocairo::functions::show_layout(ctx, &layout);
+    } else {
+        pangocairo::functions::layout_path(ctx, &layout);
+        let _ = ctx.fill();
+    }
 
-    // Include ink rect offsets for italic/stroked glyphs with negative bearings
-    let ink_x = ink_rect.x() as f64 / pango::SCALE as f64;
-    let ink_y = ink_rect.y() as f64 / pango::SCALE as f64;
-    let ink_width = ink_rect.width() as f64 / pango::SCALE as f64;
-    let ink_height = ink_rect.height() as f64 / pango::SCALE as f64;
+    // Restore context state
+    ctx.restore().ok();
+}
 
-    // Calculate brightness to determine background/stroke color
-    let brightness = color.r * 0.299 + color.g * 0.587 + color.b * 0.114;
-    let (bg_r, bg_g, bg_b) = if brightness > 0.5 {
-        (0.0, 0.0, 0.0) // Dark background/stroke for light text colors
-    } else {
-        (1.0, 1.0, 1.0) // Light background/stroke for dark text colors
-    };
+/// Shadow color used when a text shadow has no color of its own.
+pub(crate) const DEFAULT_SHADOW_COLOR: Color = Color {
+    r: 0.0,
+    g: 0.0,
+    b: 0.0,
+    a: 0.5,
+};
 
-    // Adjust y position (Pango measures from top-left, we want baseline)
-    let baseline = layout.baseline() as f64 / pango::SCALE as f64;
-    let adjusted_y = y as f64 - baseline;
-
-    // First pass: draw semi-transparent background rectangle (if enabled)
-    if background_enabled && ink_width > 0.0 && ink_height > 0.0 {
-        let padding = size * 0.15;
-        // Use ink rect offsets to properly align background for italic/stroked glyphs
-        ctx.rectangle(
-            x as f64 + ink_x - padding,
-            adjusted_y + ink_y - padding,
-            ink_width + padding * 2.0,
-            ink_height + padding * 2.0,
-        );
-        ctx.set_source_rgba(bg_r, bg_g, bg_b, 0.3);
-        let _ = ctx.fill();
+/// Returns black or white, whichever contrasts with `color`, at the given alpha.
+pub(crate) fn contrast_color(color: Color, alpha: f64) -> Color {
+    let brightness = color.r * 0.299 + color.g * 0.587 + color.b * 0.114;
+    // Dark effect for light text colors, light effect for dark ones
+    let level = if brightness > 0.5 { 0.0 } else { 1.0 };
+    Color {
+        r: level,
+        g: level,
+        b: level,
+        a: alpha,
     }
+}
 
-    // Second pass: draw drop shadow for depth
-    let shadow_offset = size * 0.04;
-    ctx.move_to(x as f64 + shadow_offset, adjusted_y + shadow_offset);
-    ctx.set_source_rgba(0.0, 0.0, 0.0, 0.4);
-    pangocairo::functions::show_layout(ctx, &layout);
+/// Accent color for selection outlines and handles.
+const SELECTION_COLOR: Color = Color {
+    r: 0.26,
+    g: 0.56,
+    b: 0.96,
+    a: 1.0,
+};
 
-    // Third pass: render text with contrasting stroke outline
-    ctx.move_to(x as f64, adjusted_y);
+/// Renders the outline and transform handles around selected shapes.
+///
+/// Draws a dashed rectangle around `bounds` with square scale handles at its
+/// corners and edge midpoints, and a round rotate handle above the top edge.
+///
+/// # Arguments
+/// * `ctx` - Cairo drawing context to render to
+/// * `bounds` - Combined bounding box of the selected shapes
+pub fn render_selection_handles(ctx: &cairo::Context, bounds: &BoundingBox) {
+    let outline = bounds.inflate(SELECTION_PADDING);
+    let set_accent = |alpha: f64| {
+        ctx.set_source_rgba(
+            SELECTION_COLOR.r,
+            SELECTION_COLOR.g,
+            SELECTION_COLOR.b,
+            alpha,
+        );
+    };
+    ctx.save().ok();
 
-    // Create path from layout for stroking
-    pangocairo::functions::layout_path(ctx, &layout);
+    set_accent(1.0);
+    ctx.set_line_width(1.0);
+    ctx.set_dash(&[4.0, 4.0], 0.0);
+    ctx.rectangle(outline.x, outline.y, outline.width, outline.height);
+    let _ = ctx.stroke();
+    ctx.set_dash(&[], 0.0);
 
-    // Fully opaque stroke for maximum contrast and crispness
-    ctx.set_source_rgba(bg_r, bg_g, bg_b, 1.0);
-    ctx.set_line_width(size * 0.06);
-    ctx.set_line_join(cairo::LineJoin::Round);
-    let _ = ctx.stroke_preserve();
+    // Stem connecting the rotate handle to the outline
+    let (top_x, top_y) = SelectionHandle::Top.position(bounds);
+    let (rotate_x, rotate_y) = SelectionHandle::Rotate.position(bounds);
+    ctx.move_to(top_x, top_y);
+    ctx.line_to(rotate_x, rotate_y);
+    let _ = ctx.stroke();
 
-    // Fill with bright, full-intensity color
-    ctx.set_source_rgba(color.r, color.g, color.b, color.a);
-    let _ = ctx.fill();
+    let half = HANDLE_SIZE / 2.0;
+    for handle in SelectionHandle::ALL {
+        let (hx, hy) = handle.position(bounds);
+        if handle == SelectionHandle::Rotate {
+            ctx.new_sub_path();
+            ctx.arc(hx, hy, half, 0.0, 2.0 * std::f64::consts::PI);
+        } else {
+            ctx.rectangle(hx - half, hy - half, HANDLE_SIZE, HANDLE_SIZE);
+        }
+        ctx.set_source_rgba(1.0, 1.0, 1.0, 1.0);
+        let _ = ctx.fill_preserve();
+        set_accent(1.0);
+        let _ = ctx.stroke();
+    }
 
-    // Restore context state
+    ctx.restore().ok();
+}
+
+/// Renders the rubber-band rectangle of a selection drag in progress.
+///
+/// # Arguments
+/// * `ctx` - Cairo drawing context to render to
+/// * `region` - Rectangle spanned by the drag
+pub fn render_selection_region(ctx: &cairo::Context, region: &BoundingBox) {
+    ctx.save().ok();
+    ctx.rectangle(region.x, region.y, region.width, region.height);
+    ctx.set_source_rgba(
+        SELECTION_COLOR.r,
+        SELECTION_COLOR.g,
+        SELECTION_COLOR.b,
+        0.15,
+    );
+    let _ = ctx.fill_preserve();
+    ctx.set_source_rgba(SELECTION_COLOR.r, SELECTION_COLOR.g, SELECTION_COLOR.b, 0.8);
+    ctx.set_line_width(1.0);
+    let _ = ctx.stroke();
     ctx.restore().ok();
 }
 
@@ -2,12 +2,17 @@
 
 use super::color::Color;
 use super::font::FontDescriptor;
+use super::text::{TextEffect, TextFormat, text_bounds};
+use crate::util;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 /// Represents a drawable shape or annotation on screen.
 ///
 /// Each variant represents a different drawing tool/primitive with its specific parameters.
 /// All shapes store their own color and size information for independent rendering.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum Shape {
     /// Freehand drawing - polyline connecting mouse drag points
     Freehand {
@@ -47,6 +52,9 @@ pub enum Shape {
         color: Color,
         /// Border thickness in pixels
         thick: f64,
+        /// Clockwise rotation in radians about the rectangle's center
+        #[serde(default)]
+        rotation: f64,
     },
     /// Ellipse/circle outline (drawn with Tab modifier)
     Ellipse {
@@ -62,6 +70,9 @@ pub enum Shape {
         color: Color,
         /// Border thickness in pixels
         thick: f64,
+        /// Clockwise rotation in radians about the center
+        #[serde(default)]
+        rotation: f64,
     },
     /// Arrow with directional head (drawn with Ctrl+Shift modifiers)
     Arrow {
@@ -96,7 +107,720 @@ pub enum Shape {
         size: f64,
         /// Font descriptor (family, weight, style)
         font_descriptor: FontDescriptor,
-        /// Whether to draw background box behind text
-        background_enabled: bool,
+        /// Readability effect (background box, outline or shadow)
+        effect: TextEffect,
+        /// Paragraph format (alignment, wrap width, line spacing)
+        format: TextFormat,
+        /// Clockwise rotation in radians about the baseline anchor `(x, y)`
+        #[serde(default)]
+        rotation: f64,
     },
 }
+
+/// Axis-aligned screen rectangle enclosing one or more shapes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    /// Left edge
+    pub x: f64,
+    /// Top edge
+    pub y: f64,
+    /// Width in pixels
+    pub width: f64,
+    /// Height in pixels
+    pub height: f64,
+}
+
+impl BoundingBox {
+    /// Creates the box spanning two opposite corners, in any order.
+    pub fn from_corners(x1: f64, y1: f64, x2: f64, y2: f64) -> Self {
+        Self {
+            x: x1.min(x2),
+            y: y1.min(y2),
+            width: (x2 - x1).abs(),
+            height: (y2 - y1).abs(),
+        }
+    }
+
+    /// Returns the smallest box containing all points, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = (f64, f64)>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let (first_x, first_y) = points.next()?;
+        let (min_x, min_y, max_x, max_y) = points.fold(
+            (first_x, first_y, first_x, first_y),
+            |(min_x, min_y, max_x, max_y), (x, y)| {
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            },
+        );
+        Some(Self::from_corners(min_x, min_y, max_x, max_y))
+    }
+
+    /// Right edge
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    /// Bottom edge
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    /// Returns true if the point lies inside the box (edges included).
+    pub fn contains(&self, px: f64, py: f64) -> bool {
+        px >= self.x && px <= self.right() && py >= self.y && py <= self.bottom()
+    }
+
+    /// Returns true if `other` lies entirely inside this box.
+    pub fn contains_box(&self, other: &BoundingBox) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    /// Returns true if the boxes overlap (touching edges do not count).
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+
+    /// Returns the smallest box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> Self {
+        Self::from_corners(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.right().max(other.right()),
+            self.bottom().max(other.bottom()),
+        )
+    }
+
+    /// Center point
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Returns the box enclosing this box after rotating it around a center.
+    pub fn rotated(&self, center_x: f64, center_y: f64, angle: f64) -> Self {
+        if angle == 0.0 {
+            return *self;
+        }
+        let corners = [
+            (self.x, self.y),
+            (self.right(), self.y),
+            (self.right(), self.bottom()),
+            (self.x, self.bottom()),
+        ];
+        Self::from_points(
+            corners
+                .into_iter()
+                .map(|(x, y)| rotate_point(x, y, center_x, center_y, angle)),
+        )
+        .unwrap_or(*self)
+    }
+
+    /// Returns the box grown by `amount` pixels on every side.
+    pub fn inflate(&self, amount: f64) -> Self {
+        Self {
+            x: self.x - amount,
+            y: self.y - amount,
+            width: self.width + amount * 2.0,
+            height: self.height + amount * 2.0,
+        }
+    }
+}
+
+impl Shape {
+    /// Returns the screen rectangle covered by the shape, including its stroke.
+    ///
+    /// Returns `None` for shapes with nothing to draw (a freehand stroke without
+    /// points) or text that cannot be laid out.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        match self {
+            Shape::Freehand { points, thick, .. } => {
+                BoundingBox::from_points(points.iter().map(|&(x, y)| (x as f64, y as f64)))
+                    .map(|bounds| bounds.inflate(thick / 2.0))
+            }
+            Shape::Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                thick,
+                ..
+            } => Some(
+                BoundingBox::from_corners(*x1 as f64, *y1 as f64, *x2 as f64, *y2 as f64)
+                    .inflate(thick / 2.0),
+            ),
+            Shape::Rect {
+                x,
+                y,
+                w,
+                h,
+                thick,
+                rotation,
+                ..
+            } => {
+                let bounds = BoundingBox::from_corners(
+                    *x as f64,
+                    *y as f64,
+                    (*x + *w) as f64,
+                    (*y + *h) as f64,
+                )
+                .inflate(thick / 2.0);
+                let (cx, cy) = bounds.center();
+                Some(bounds.rotated(cx, cy, *rotation))
+            }
+            Shape::Ellipse {
+                cx,
+                cy,
+                rx,
+                ry,
+                thick,
+                rotation,
+                ..
+            } => {
+                // Half extents of a rotated ellipse
+                let (rx, ry) = (*rx as f64 + thick / 2.0, *ry as f64 + thick / 2.0);
+                let (sin, cos) = rotation.sin_cos();
+                let half_width = ((rx * cos).powi(2) + (ry * sin).powi(2)).sqrt();
+                let half_height = ((rx * sin).powi(2) + (ry * cos).powi(2)).sqrt();
+                Some(BoundingBox::from_corners(
+                    *cx as f64 - half_width,
+                    *cy as f64 - half_height,
+                    *cx as f64 + half_width,
+                    *cy as f64 + half_height,
+                ))
+            }
+            Shape::Arrow {
+                x1,
+                y1,
+                x2,
+                y2,
+                thick,
+                arrow_length,
+                arrow_angle,
+                ..
+            } => {
+                let head = util::calculate_arrowhead_custom(
+                    *x1,
+                    *y1,
+                    *x2,
+                    *y2,
+                    *arrow_length,
+                    *arrow_angle,
+                );
+                BoundingBox::from_points(
+                    [(*x1 as f64, *y1 as f64), (*x2 as f64, *y2 as f64)]
+                        .into_iter()
+                        .chain(head),
+                )
+                .map(|bounds| bounds.inflate(thick / 2.0))
+            }
+            Shape::Text {
+                x,
+                y,
+                text,
+                size,
+                font_descriptor,
+                format,
+                rotation,
+                ..
+            } => text_bounds(*x, *y, text, *size, font_descriptor, format).map(|bounds| {
+                BoundingBox {
+                    x: bounds.x,
+                    y: bounds.y,
+                    width: bounds.width,
+                    height: bounds.height,
+                }
+                .rotated(*x as f64, *y as f64, *rotation)
+            }),
+        }
+    }
+
+    /// Returns the point rotated shapes turn around, with their rotation.
+    ///
+    /// Shapes without a rotation field return `None`.
+    pub fn rotation_center(&self) -> Option<(f64, f64, f64)> {
+        match self {
+            Shape::Rect {
+                x,
+                y,
+                w,
+                h,
+                rotation,
+                ..
+            } => Some((
+                *x as f64 + *w as f64 / 2.0,
+                *y as f64 + *h as f64 / 2.0,
+                *rotation,
+            )),
+            Shape::Ellipse {
+                cx, cy, rotation, ..
+            } => Some((*cx as f64, *cy as f64, *rotation)),
+            Shape::Text { x, y, rotation, .. } => Some((*x as f64, *y as f64, *rotation)),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the point touches the shape.
+    ///
+    /// Outlines and strokes are hit within `tolerance` pixels of their drawn
+    /// edge; the inside of a rectangle or ellipse does not count. Text is hit
+    /// anywhere inside its bounds.
+    pub fn hit_test(&self, px: i32, py: i32, tolerance: f64) -> bool {
+        let (mut px, mut py) = (px as f64, py as f64);
+        // Test rotated shapes in their own unrotated frame
+        if let Some((cx, cy, rotation)) = self.rotation_center()
+            && rotation != 0.0
+        {
+            (px, py) = rotate_point(px, py, cx, cy, -rotation);
+        }
+        let near = |distance: f64, thick: f64| distance <= thick / 2.0 + tolerance;
+
+        match self {
+            Shape::Freehand { points, thick, .. } => match points.as_slice() {
+                [] => false,
+                [(x, y)] => near(distance(px, py, *x as f64, *y as f64), *thick),
+                points => points.windows(2).any(|pair| {
+                    let (x1, y1) = pair[0];
+                    let (x2, y2) = pair[1];
+                    near(
+                        distance_to_segment(px, py, x1 as f64, y1 as f64, x2 as f64, y2 as f64),
+                        *thick,
+                    )
+                }),
+            },
+            Shape::Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                thick,
+                ..
+            } => near(
+                distance_to_segment(px, py, *x1 as f64, *y1 as f64, *x2 as f64, *y2 as f64),
+                *thick,
+            ),
+            Shape::Rect {
+                x, y, w, h, thick, ..
+            } => {
+                let (left, top) = (*x as f64, *y as f64);
+                let (right, bottom) = ((*x + *w) as f64, (*y + *h) as f64);
+                [
+                    (left, top, right, top),
+                    (right, top, right, bottom),
+                    (right, bottom, left, bottom),
+                    (left, bottom, left, top),
+                ]
+                .into_iter()
+                .any(|(x1, y1, x2, y2)| near(distance_to_segment(px, py, x1, y1, x2, y2), *thick))
+            }
+            Shape::Ellipse {
+                cx,
+                cy,
+                rx,
+                ry,
+                thick,
+                ..
+            } => {
+                let (cx, cy, rx, ry) = (*cx as f64, *cy as f64, *rx as f64, *ry as f64);
+                if rx <= 0.0 || ry <= 0.0 {
+                    // Degenerate ellipse: drawn as a straight line (or a dot)
+                    return near(
+                        distance_to_segment(px, py, cx - rx, cy - ry, cx + rx, cy + ry),
+                        *thick,
+                    );
+                }
+                // Approximate the distance to the outline along the ray from the center
+                let (dx, dy) = (px - cx, py - cy);
+                let scale = ((dx / rx).powi(2) + (dy / ry).powi(2)).sqrt();
+                let distance = if scale == 0.0 {
+                    rx.min(ry)
+                } else {
+                    distance(dx, dy, dx / scale, dy / scale)
+                };
+                near(distance, *thick)
+            }
+            Shape::Arrow {
+                x1,
+                y1,
+                x2,
+                y2,
+                thick,
+                arrow_length,
+                arrow_angle,
+                ..
+            } => {
+                let (tip_x, tip_y) = (*x1 as f64, *y1 as f64);
+                let head = util::calculate_arrowhead_custom(
+                    *x1,
+                    *y1,
+                    *x2,
+                    *y2,
+                    *arrow_length,
+                    *arrow_angle,
+                );
+                near(
+                    distance_to_segment(px, py, tip_x, tip_y, *x2 as f64, *y2 as f64),
+                    *thick,
+                ) || head
+                    .into_iter()
+                    .any(|(hx, hy)| near(distance_to_segment(px, py, tip_x, tip_y, hx, hy), *thick))
+            }
+            Shape::Text {
+                x,
+                y,
+                text,
+                size,
+                font_descriptor,
+                format,
+                ..
+            } => text_bounds(*x, *y, text, *size, font_descriptor, format).is_some_and(|bounds| {
+                px >= bounds.x - tolerance
+                    && px <= bounds.x + bounds.width + tolerance
+                    && py >= bounds.y - tolerance
+                    && py <= bounds.y + bounds.height + tolerance
+            }),
+        }
+    }
+
+    /// Moves the shape by the given offset in pixels.
+    pub fn translate(&mut self, dx: i32, dy: i32) {
+        match self {
+            Shape::Freehand { points, .. } => {
+                for (x, y) in points {
+                    *x += dx;
+                    *y += dy;
+                }
+            }
+            Shape::Line { x1, y1, x2, y2, .. } | Shape::Arrow { x1, y1, x2, y2, .. } => {
+                *x1 += dx;
+                *y1 += dy;
+                *x2 += dx;
+                *y2 += dy;
+            }
+            Shape::Rect { x, y, .. } | Shape::Text { x, y, .. } => {
+                *x += dx;
+                *y += dy;
+            }
+            Shape::Ellipse { cx, cy, .. } => {
+                *cx += dx;
+                *cy += dy;
+            }
+        }
+    }
+
+    /// Scales the shape away from `(origin_x, origin_y)` by the given factors.
+    ///
+    /// Scaling is baked into the coordinates; stroke widths stay the same.
+    /// Text scales its font size (and wrap width) instead. Rotated rectangles,
+    /// ellipses and text can't be stretched along screen axes, so their size
+    /// scales uniformly by the mean of both factors.
+    pub fn scale(&mut self, origin_x: f64, origin_y: f64, factor_x: f64, factor_y: f64) {
+        let scale_point = |x: i32, y: i32| {
+            (
+                (origin_x + (x as f64 - origin_x) * factor_x).round() as i32,
+                (origin_y + (y as f64 - origin_y) * factor_y).round() as i32,
+            )
+        };
+        let uniform = (factor_x * factor_y).abs().sqrt();
+
+        match self {
+            Shape::Freehand { points, .. } => {
+                for point in points {
+                    *point = scale_point(point.0, point.1);
+                }
+            }
+            Shape::Line { x1, y1, x2, y2, .. } | Shape::Arrow { x1, y1, x2, y2, .. } => {
+                (*x1, *y1) = scale_point(*x1, *y1);
+                (*x2, *y2) = scale_point(*x2, *y2);
+            }
+            Shape::Rect {
+                x,
+                y,
+                w,
+                h,
+                rotation,
+                ..
+            } => {
+                if *rotation == 0.0 {
+                    let (x1, y1) = scale_point(*x, *y);
+                    let (x2, y2) = scale_point(*x + *w, *y + *h);
+                    (*x, *w) = (x1.min(x2), (x2 - x1).abs());
+                    (*y, *h) = (y1.min(y2), (y2 - y1).abs());
+                } else {
+                    let (cx, cy) = scale_point(*x + *w / 2, *y + *h / 2);
+                    *w = (*w as f64 * uniform).round() as i32;
+                    *h = (*h as f64 * uniform).round() as i32;
+                    (*x, *y) = (cx - *w / 2, cy - *h / 2);
+                }
+            }
+            Shape::Ellipse {
+                cx,
+                cy,
+                rx,
+                ry,
+                rotation,
+                ..
+            } => {
+                (*cx, *cy) = scale_point(*cx, *cy);
+                let (factor_x, factor_y) = if *rotation == 0.0 {
+                    (factor_x.abs(), factor_y.abs())
+                } else {
+                    (uniform, uniform)
+                };
+                *rx = (*rx as f64 * factor_x).round() as i32;
+                *ry = (*ry as f64 * factor_y).round() as i32;
+            }
+            Shape::Text {
+                x, y, size, format, ..
+            } => {
+                (*x, *y) = scale_point(*x, *y);
+                *size = (*size * uniform).max(1.0);
+                if let Some(wrap_width) = &mut format.wrap_width {
+                    *wrap_width = (*wrap_width * factor_x.abs()).max(1.0);
+                }
+            }
+        }
+    }
+
+    /// Rotates the shape clockwise by `angle` radians around `(center_x, center_y)`.
+    ///
+    /// Strokes bake the rotation into their points; rectangles, ellipses and
+    /// text move their anchor and add to their `rotation`.
+    pub fn rotate(&mut self, center_x: f64, center_y: f64, angle: f64) {
+        let turn = |x: i32, y: i32| {
+            let (x, y) = rotate_point(x as f64, y as f64, center_x, center_y, angle);
+            (x.round() as i32, y.round() as i32)
+        };
+
+        match self {
+            Shape::Freehand { points, .. } => {
+                for point in points {
+                    *point = turn(point.0, point.1);
+                }
+            }
+            Shape::Line { x1, y1, x2, y2, .. } | Shape::Arrow { x1, y1, x2, y2, .. } => {
+                (*x1, *y1) = turn(*x1, *y1);
+                (*x2, *y2) = turn(*x2, *y2);
+            }
+            Shape::Rect {
+                x,
+                y,
+                w,
+                h,
+                rotation,
+                ..
+            } => {
+                let (cx, cy) = turn(*x + *w / 2, *y + *h / 2);
+                (*x, *y) = (cx - *w / 2, cy - *h / 2);
+                *rotation = normalize_angle(*rotation + angle);
+            }
+            Shape::Ellipse {
+                cx, cy, rotation, ..
+            } => {
+                (*cx, *cy) = turn(*cx, *cy);
+                *rotation = normalize_angle(*rotation + angle);
+            }
+            Shape::Text { x, y, rotation, .. } => {
+                (*x, *y) = turn(*x, *y);
+                *rotation = normalize_angle(*rotation + angle);
+            }
+        }
+    }
+}
+
+/// Rotates a point clockwise (on screen) by `angle` radians around a center.
+pub fn rotate_point(x: f64, y: f64, center_x: f64, center_y: f64, angle: f64) -> (f64, f64) {
+    let (sin, cos) = angle.sin_cos();
+    let (dx, dy) = (x - center_x, y - center_y);
+    (
+        center_x + dx * cos - dy * sin,
+        center_y + dx * sin + dy * cos,
+    )
+}
+
+/// Wraps an angle into `(-PI, PI]`, snapping values within rounding noise of zero to zero.
+fn normalize_angle(angle: f64) -> f64 {
+    use std::f64::consts::{PI, TAU};
+    let wrapped = angle - TAU * ((angle + PI) / TAU).floor();
+    let wrapped = if wrapped <= -PI {
+        wrapped + TAU
+    } else {
+        wrapped
+    };
+    if wrapped.abs() < 1e-9 { 0.0 } else { wrapped }
+}
+
+/// Distance between two points.
+fn distance(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    (x2 - x1).hypot(y2 - y1)
+}
+
+/// Distance from a point to the segment between `(x1, y1)` and `(x2, y2)`.
+fn distance_to_segment(px: f64, py: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return distance(px, py, x1, y1);
+    }
+    let t = (((px - x1) * dx + (py - y1) * dy) / length_squared).clamp(0.0, 1.0);
+    distance(px, py, x1 + t * dx, y1 + t * dy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::RED;
+
+    fn rect(x: i32, y: i32, w: i32, h: i32) -> Shape {
+        Shape::Rect {
+            x,
+            y,
+            w,
+            h,
+            color: RED,
+            thick: 4.0,
+            rotation: 0.0,
+        }
+    }
+
+    #[test]
+    fn bounding_box_includes_stroke_width() {
+        let bounds = rect(10, 20, 100, 50).bounding_box().unwrap();
+        assert_eq!(bounds, BoundingBox::from_corners(8.0, 18.0, 112.0, 72.0));
+
+        let empty = Shape::Freehand {
+            points: Vec::new(),
+            color: RED,
+            thick: 2.0,
+        };
+        assert!(empty.bounding_box().is_none());
+    }
+
+    #[test]
+    fn outline_shapes_are_hit_on_their_edges_only() {
+        let shape = rect(0, 0, 100, 100);
+        assert!(shape.hit_test(0, 50, 0.0));
+        assert!(shape.hit_test(104, 50, 3.0));
+        assert!(
+            !shape.hit_test(50, 50, 3.0),
+            "inside of an outline is not a hit"
+        );
+
+        let ellipse = Shape::Ellipse {
+            cx: 100,
+            cy: 100,
+            rx: 50,
+            ry: 20,
+            color: RED,
+            thick: 2.0,
+            rotation: 0.0,
+        };
+        assert!(ellipse.hit_test(150, 100, 0.0));
+        assert!(ellipse.hit_test(100, 122, 2.0));
+        assert!(!ellipse.hit_test(100, 100, 2.0));
+    }
+
+    #[test]
+    fn strokes_are_hit_near_their_segments() {
+        let stroke = Shape::Freehand {
+            points: vec![(0, 0), (100, 0), (100, 100)],
+            color: RED,
+            thick: 2.0,
+        };
+        assert!(stroke.hit_test(50, 3, 2.0));
+        assert!(stroke.hit_test(103, 60, 2.0));
+        assert!(!stroke.hit_test(50, 50, 2.0));
+    }
+
+    #[test]
+    fn translate_moves_every_coordinate() {
+        let mut line = Shape::Line {
+            x1: 0,
+            y1: 0,
+            x2: 10,
+            y2: 10,
+            color: RED,
+            thick: 2.0,
+        };
+        let before = line.bounding_box().unwrap();
+        line.translate(5, -3);
+        let after = line.bounding_box().unwrap();
+        assert_eq!(after.x - before.x, 5.0);
+        assert_eq!(after.y - before.y, -3.0);
+        assert_eq!(after.width, before.width);
+    }
+
+    #[test]
+    fn scale_bakes_into_coordinates_and_text_size() {
+        let mut shape = rect(10, 10, 20, 10);
+        shape.scale(10.0, 10.0, 2.0, 3.0);
+        assert!(matches!(
+            shape,
+            Shape::Rect {
+                x: 10,
+                y: 10,
+                w: 40,
+                h: 30,
+                ..
+            }
+        ));
+
+        // Dragging past the anchor flips without negative sizes
+        shape.scale(10.0, 10.0, -1.0, 1.0);
+        assert!(matches!(shape, Shape::Rect { x: -30, w: 40, .. }));
+
+        let mut text = Shape::Text {
+            x: 0,
+            y: 0,
+            text: "hi".to_string(),
+            color: RED,
+            size: 20.0,
+            font_descriptor: FontDescriptor::default(),
+            effect: TextEffect::None,
+            format: TextFormat::default(),
+            rotation: 0.0,
+        };
+        text.scale(0.0, 0.0, 2.0, 2.0);
+        assert!(matches!(text, Shape::Text { size, .. } if size == 40.0));
+    }
+
+    #[test]
+    fn rotation_is_baked_into_strokes_and_stored_for_outlines() {
+        use std::f64::consts::FRAC_PI_2;
+
+        let mut line = Shape::Line {
+            x1: 10,
+            y1: 0,
+            x2: 20,
+            y2: 0,
+            color: RED,
+            thick: 2.0,
+        };
+        line.rotate(0.0, 0.0, FRAC_PI_2);
+        assert!(matches!(
+            line,
+            Shape::Line {
+                x1: 0,
+                y1: 10,
+                x2: 0,
+                y2: 20,
+                ..
+            }
+        ));
+
+        // A wide rectangle turned a quarter becomes tall, but keeps its size
+        let mut shape = rect(0, 0, 100, 20);
+        shape.rotate(50.0, 10.0, FRAC_PI_2);
+        assert!(matches!(shape, Shape::Rect { w: 100, h: 20, .. }));
+        let bounds = shape.bounding_box().unwrap();
+        assert!((bounds.width - 24.0).abs() < 1e-6);
+        assert!((bounds.height - 104.0).abs() < 1e-6);
+
+        // Hit testing follows the rotated outline
+        assert!(shape.hit_test(60, 60, 1.0));
+        assert!(!shape.hit_test(100, 10, 1.0));
+    }
+}
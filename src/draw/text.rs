@@ -0,0 +1,386 @@
+//! Pango text layout helpers shared by text rendering and text editing.
+
+use super::color::Color;
+use super::font::FontDescriptor;
+use crate::config::TextAlign;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Width of the text caret in pixels.
+const CARET_WIDTH: f64 = 2.0;
+
+/// Paragraph formatting for text annotations.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TextFormat {
+    /// Horizontal alignment (also decides which edge the anchor point is)
+    pub align: TextAlign,
+    /// Maximum line width in pixels before wrapping (`None` = no wrapping)
+    pub wrap_width: Option<f64>,
+    /// Line spacing as a multiple of the font height (1.0 = Pango default)
+    pub line_spacing: f64,
+}
+
+/// Readability effect drawn with a text annotation.
+///
+/// Effect colors left as `None` are picked automatically from the text color.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TextEffect {
+    /// Plain text
+    None,
+    /// Semi-transparent box behind the text
+    Background {
+        /// Box color (default: contrasting black or white at 30% opacity)
+        color: Option<Color>,
+        /// Padding around the text in pixels
+        padding: f64,
+    },
+    /// Outline stroked around each glyph
+    Outline {
+        /// Outline color (default: contrasting black or white)
+        color: Option<Color>,
+        /// Outline width in pixels
+        width: f64,
+    },
+    /// Drop shadow offset down and to the right
+    Shadow {
+        /// Shadow color (default: translucent black)
+        color: Option<Color>,
+        /// Shadow offset in pixels
+        offset: f64,
+    },
+}
+
+impl Default for TextEffect {
+    fn default() -> Self {
+        TextEffect::Outline {
+            color: None,
+            width: 1.0,
+        }
+    }
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        Self {
+            align: TextAlign::Left,
+            wrap_width: None,
+            line_spacing: 1.0,
+        }
+    }
+}
+
+/// Creates a Pango layout for `text` with the given font and paragraph format.
+///
+/// All text drawing and measuring goes through this so that caret positions
+/// line up exactly with the rendered glyphs.
+pub fn create_text_layout(
+    ctx: &cairo::Context,
+    text: &str,
+    size: f64,
+    font_descriptor: &FontDescriptor,
+    format: &TextFormat,
+) -> pango::Layout {
+    let layout = pangocairo::functions::create_layout(ctx);
+
+    // Set font description from config
+    let font_desc_str = font_descriptor.to_pango_string(size);
+    let font_desc = pango::FontDescription::from_string(&font_desc_str);
+    layout.set_font_description(Some(&font_desc));
+
+    layout.set_alignment(match format.align {
+        TextAlign::Left => pango::Alignment::Left,
+        TextAlign::Center => pango::Alignment::Center,
+        TextAlign::Right => pango::Alignment::Right,
+    });
+    if let Some(width) = format.wrap_width {
+        layout.set_width((width * pango::SCALE as f64) as i32);
+        layout.set_wrap(pango::WrapMode::WordChar);
+    }
+    // Only override spacing when asked, so default text keeps the font's own metrics
+    if (format.line_spacing - 1.0).abs() > f64::EPSILON {
+        layout.set_line_spacing(format.line_spacing as f32);
+    }
+
+    // Set the text (Pango handles newlines automatically)
+    layout.set_text(text);
+    layout
+}
+
+/// Returns the top-left origin of a layout anchored at `(x, y)`.
+///
+/// Pango measures from the top-left corner while text shapes are anchored
+/// at the baseline of their first line. Horizontally the anchor is the left
+/// edge, center or right edge of the paragraph depending on its alignment,
+/// where the paragraph is the wrap width if set, or the widest line.
+pub fn layout_origin(layout: &pango::Layout, x: i32, y: i32) -> (f64, f64) {
+    let scale = pango::SCALE as f64;
+    let baseline = layout.baseline() as f64 / scale;
+    let width = if layout.width() >= 0 {
+        layout.width() as f64 / scale
+    } else {
+        layout.extents().1.width() as f64 / scale
+    };
+    let offset = match layout.alignment() {
+        pango::Alignment::Center => width / 2.0,
+        pango::Alignment::Right => width,
+        _ => 0.0,
+    };
+    (x as f64 - offset, y as f64 - baseline)
+}
+
+/// Creates a layout for measuring text outside of rendering.
+///
+/// Hit-testing happens in input handling where no drawing context exists,
+/// so the layout is backed by a throwaway 1x1 surface. Font metrics do not
+/// depend on the surface size.
+pub(crate) fn measure_text_layout(
+    text: &str,
+    size: f64,
+    font_descriptor: &FontDescriptor,
+    format: &TextFormat,
+) -> Option<pango::Layout> {
+    let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 1, 1).ok()?;
+    let ctx = cairo::Context::new(&surface).ok()?;
+    Some(create_text_layout(
+        &ctx,
+        text,
+        size,
+        font_descriptor,
+        format,
+    ))
+}
+
+/// Axis-aligned screen rectangle covered by a text annotation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextBounds {
+    /// Left edge
+    pub x: f64,
+    /// Top edge
+    pub y: f64,
+    /// Width in pixels
+    pub width: f64,
+    /// Height in pixels
+    pub height: f64,
+}
+
+/// Computes the clickable bounds of text whose first baseline sits at `(x, y)`.
+///
+/// Uses the logical extents of the same layout `render_text` draws, padded
+/// like the text background so short strings are still easy to hit.
+pub fn text_bounds(
+    x: i32,
+    y: i32,
+    text: &str,
+    size: f64,
+    font_descriptor: &FontDescriptor,
+    format: &TextFormat,
+) -> Option<TextBounds> {
+    let layout = measure_text_layout(text, size, font_descriptor, format)?;
+    let (origin_x, origin_y) = layout_origin(&layout, x, y);
+    let (_ink_rect, logical_rect) = layout.extents();
+    let scale = pango::SCALE as f64;
+    let padding = size * 0.15;
+
+    Some(TextBounds {
+        x: origin_x + logical_rect.x() as f64 / scale - padding,
+        y: origin_y + logical_rect.y() as f64 / scale - padding,
+        width: logical_rect.width() as f64 / scale + padding * 2.0,
+        height: logical_rect.height() as f64 / scale + padding * 2.0,
+    })
+}
+
+/// Returns the caret position (byte offset) closest to a point.
+///
+/// Clicks past the end of a line land after its last character.
+#[allow(clippy::too_many_arguments)]
+pub fn text_index_at_point(
+    x: i32,
+    y: i32,
+    text: &str,
+    size: f64,
+    font_descriptor: &FontDescriptor,
+    format: &TextFormat,
+    px: i32,
+    py: i32,
+) -> usize {
+    let Some(layout) = measure_text_layout(text, size, font_descriptor, format) else {
+        return text.len();
+    };
+    let (origin_x, origin_y) = layout_origin(&layout, x, y);
+    let scale = pango::SCALE as f64;
+    let (_inside, index, trailing) = layout.xy_to_index(
+        ((px as f64 - origin_x) * scale) as i32,
+        ((py as f64 - origin_y) * scale) as i32,
+    );
+
+    // `trailing` counts characters past `index`, i.e. a click on the right
+    // half of a glyph puts the caret after it.
+    let index = (index.max(0) as usize).min(text.len());
+    text[index..]
+        .char_indices()
+        .nth(trailing.max(0) as usize)
+        .map_or(text.len(), |(offset, _)| index + offset)
+}
+
+/// Returns the screen rectangle of the caret at byte offset `caret`.
+///
+/// Used to tell input methods where to place their candidate window.
+pub fn text_caret_rect(
+    x: i32,
+    y: i32,
+    text: &str,
+    caret: usize,
+    size: f64,
+    font_descriptor: &FontDescriptor,
+    format: &TextFormat,
+) -> Option<TextBounds> {
+    let layout = measure_text_layout(text, size, font_descriptor, format)?;
+    let (origin_x, origin_y) = layout_origin(&layout, x, y);
+    let (strong, _weak) = layout.cursor_pos(caret as i32);
+    let scale = pango::SCALE as f64;
+
+    Some(TextBounds {
+        x: origin_x + strong.x() as f64 / scale,
+        y: origin_y + strong.y() as f64 / scale,
+        width: CARET_WIDTH,
+        height: strong.height() as f64 / scale,
+    })
+}
+
+/// Renders the caret and selection highlight for text being edited.
+///
+/// Uses Pango cursor positions so the caret lands between the right glyphs
+/// even for multi-line text, kerning and non-ASCII characters.
+///
+/// # Arguments
+/// * `ctx` - Cairo drawing context to render to
+/// * `x` - X coordinate of the text's left edge
+/// * `y` - Y coordinate of the first line's baseline
+/// * `text` - Text being edited
+/// * `caret` - Caret position as a byte offset into `text`
+/// * `selection` - Selected byte range, if any
+/// * `preedit` - Byte range of input method preedit text to underline, if any
+/// * `color` - Caret color (the selection uses a translucent version)
+/// * `size` - Font size in points
+/// * `font_descriptor` - Font configuration
+/// * `format` - Paragraph format (alignment, wrapping, line spacing)
+#[allow(clippy::too_many_arguments)]
+pub fn render_text_caret(
+    ctx: &cairo::Context,
+    x: i32,
+    y: i32,
+    text: &str,
+    caret: usize,
+    selection: Option<Range<usize>>,
+    preedit: Option<Range<usize>>,
+    color: Color,
+    size: f64,
+    font_descriptor: &FontDescriptor,
+    format: &TextFormat,
+) {
+    ctx.save().ok();
+
+    let layout = create_text_layout(ctx, text, size, font_descriptor, format);
+    let (origin_x, origin_y) = layout_origin(&layout, x, y);
+    let scale = pango::SCALE as f64;
+
+    if let Some(range) = selection {
+        ctx.set_source_rgba(color.r, color.g, color.b, 0.3);
+        for (index, _) in text[range.clone()].char_indices() {
+            let pos = layout.index_to_pos((range.start + index) as i32);
+            ctx.rectangle(
+                origin_x + pos.x() as f64 / scale,
+                origin_y + pos.y() as f64 / scale,
+                pos.width() as f64 / scale,
+                pos.height() as f64 / scale,
+            );
+        }
+        let _ = ctx.fill();
+    }
+
+    // Underline composed text like other text fields do
+    if let Some(range) = preedit {
+        ctx.set_source_rgba(color.r, color.g, color.b, color.a);
+        for (index, _) in text[range.clone()].char_indices() {
+            let pos = layout.index_to_pos((range.start + index) as i32);
+            ctx.rectangle(
+                origin_x + pos.x() as f64 / scale,
+                origin_y + (pos.y() + pos.height()) as f64 / scale - 1.0,
+                pos.width() as f64 / scale,
+                1.0,
+            );
+        }
+        let _ = ctx.fill();
+    }
+
+    let (strong, _weak) = layout.cursor_pos(caret as i32);
+    ctx.set_source_rgba(color.r, color.g, color.b, color.a);
+    ctx.rectangle(
+        origin_x + strong.x() as f64 / scale - CARET_WIDTH / 2.0,
+        origin_y + strong.y() as f64 / scale,
+        CARET_WIDTH,
+        strong.height() as f64 / scale,
+    );
+    let _ = ctx.fill();
+
+    ctx.restore().ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(text: &str, format: TextFormat) -> TextBounds {
+        text_bounds(200, 100, text, 20.0, &FontDescriptor::default(), &format).unwrap()
+    }
+
+    #[test]
+    fn alignment_moves_anchor_to_matching_edge() {
+        let left = bounds("Hello", TextFormat::default());
+        let center = bounds(
+            "Hello",
+            TextFormat {
+                align: TextAlign::Center,
+                ..TextFormat::default()
+            },
+        );
+        let right = bounds(
+            "Hello",
+            TextFormat {
+                align: TextAlign::Right,
+                ..TextFormat::default()
+            },
+        );
+
+        assert!(left.x < 200.0 && left.x + left.width > 200.0 + left.width / 2.0);
+        assert!((center.x + center.width / 2.0 - 200.0).abs() < 1.0);
+        assert!(right.x + right.width < 200.0 + right.width / 2.0);
+    }
+
+    #[test]
+    fn wrap_width_and_line_spacing_change_height() {
+        let text = "several words that will not fit on one short line";
+        let single = bounds(text, TextFormat::default());
+        let wrapped = bounds(
+            text,
+            TextFormat {
+                wrap_width: Some(80.0),
+                ..TextFormat::default()
+            },
+        );
+        let spaced = bounds(
+            text,
+            TextFormat {
+                wrap_width: Some(80.0),
+                line_spacing: 2.0,
+                ..TextFormat::default()
+            },
+        );
+
+        assert!(wrapped.height > single.height * 2.0);
+        assert!(wrapped.width < single.width);
+        assert!(spaced.height > wrapped.height);
+    }
+}
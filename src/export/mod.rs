@@ -0,0 +1,50 @@
+//! Vector exports of boards.
+//!
+//! Unlike screenshots, exports are built from the shapes themselves, so they
+//! stay sharp at any zoom level and text remains real, selectable text.
+
+pub mod pdf;
+pub mod svg;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// File formats a board can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Scalable Vector Graphics (active board only)
+    Svg,
+    /// Portable Document Format, one page per board
+    Pdf,
+}
+
+impl ExportFormat {
+    /// Returns the file extension for exported files.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Svg => "svg",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "svg" => Ok(ExportFormat::Svg),
+            "pdf" => Ok(ExportFormat::Pdf),
+            _ => Err(format!(
+                "unknown export format '{}' (expected: svg, pdf)",
+                s
+            )),
+        }
+    }
+}
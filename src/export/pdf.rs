@@ -0,0 +1,157 @@
+//! PDF export of a drawing session.
+//!
+//! Pages are drawn with the same cairo rendering as the overlay, onto a PDF
+//! surface, so shapes stay vectors and text is embedded as real text.
+
+use crate::config::BoardConfig;
+use crate::draw::{
+    BackgroundImages, BoardBackground, CanvasSet, Frame, render_board_background, render_shapes,
+};
+
+/// Renders frames as consecutive PDF pages of the given size in pixels.
+///
+/// Each page is filled with its background color and image (an empty
+/// background leaves it blank) before its shapes are drawn. One pixel maps
+/// to one PDF point.
+pub fn frames_to_pdf(
+    pages: &[(&Frame, BoardBackground)],
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, cairo::Error> {
+    let surface = cairo::PdfSurface::for_stream(width as f64, height as f64, Vec::<u8>::new())?;
+    let ctx = cairo::Context::new(&surface)?;
+    for (frame, background) in pages {
+        render_board_background(&ctx, background, width as f64, height as f64);
+        render_shapes(&ctx, frame);
+        ctx.show_page()?;
+    }
+    drop(ctx);
+
+    let stream = surface
+        .finish_output_stream()
+        .map_err(|_| cairo::Error::WriteError)?;
+    stream
+        .downcast::<Vec<u8>>()
+        .map(|data| *data)
+        .map_err(|_| cairo::Error::WriteError)
+}
+
+/// Renders every board page with visible shapes as one PDF page each.
+///
+/// Boards come in transparent, whiteboard, blackboard order, then custom
+/// boards by name, each page on its board's background. Shapes on hidden
+/// layers don't count, since they aren't drawn. When every page is empty,
+/// the current page is written as a single blank page so the export
+/// is never an empty document.
+pub fn canvas_to_pdf(
+    canvas_set: &CanvasSet,
+    board_config: &BoardConfig,
+    images: &mut BackgroundImages,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, cairo::Error> {
+    let has_visible_shapes = |page: &Frame| page.visible_shapes().next().is_some();
+    let mut pages: Vec<(&Frame, BoardBackground)> = canvas_set
+        .boards()
+        .filter(|(_, board)| board.pages().iter().any(has_visible_shapes))
+        .flat_map(|(mode, board)| {
+            let background = images.background(mode, board_config);
+            board
+                .pages()
+                .iter()
+                .map(move |page| (page, background.clone()))
+        })
+        .filter(|(page, _)| has_visible_shapes(page))
+        .collect();
+    if pages.is_empty() {
+        pages.push((
+            canvas_set.active_frame(),
+            images.background(canvas_set.active_mode(), board_config),
+        ));
+    }
+    frames_to_pdf(&pages, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::color::RED;
+    use crate::draw::{FontDescriptor, Shape, TextEffect, TextFormat};
+    use crate::input::BoardMode;
+
+    /// Counts page objects, which cairo writes uncompressed.
+    fn page_count(pdf: &[u8]) -> usize {
+        String::from_utf8_lossy(pdf)
+            .matches("/Type /Page %")
+            .count()
+    }
+
+    fn rect() -> Shape {
+        Shape::Rect {
+            x: 10,
+            y: 10,
+            w: 50,
+            h: 30,
+            color: RED,
+            thick: 2.0,
+            rotation: 0.0,
+        }
+    }
+
+    #[test]
+    fn every_board_with_shapes_becomes_a_page() {
+        let mut canvas_set = CanvasSet::new();
+        let config = BoardConfig::default();
+        let mut images = BackgroundImages::new();
+
+        let pdf = canvas_to_pdf(&canvas_set, &config, &mut images, 200, 100).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+        assert_eq!(page_count(&pdf), 1);
+
+        canvas_set.active_frame_mut().add_shape(rect());
+        canvas_set.switch_mode(BoardMode::Whiteboard);
+        canvas_set.active_frame_mut().add_shape(rect());
+        // Created but empty, so skipped
+        canvas_set.switch_mode(BoardMode::Blackboard);
+        canvas_set.active_frame_mut();
+
+        let pdf = canvas_to_pdf(&canvas_set, &config, &mut images, 200, 100).unwrap();
+        assert_eq!(page_count(&pdf), 2);
+
+        // Every page of a board gets its own PDF page
+        canvas_set.switch_mode(BoardMode::Whiteboard);
+        canvas_set.new_page();
+        canvas_set.active_frame_mut().add_shape(rect());
+        canvas_set.new_page();
+        let pdf = canvas_to_pdf(&canvas_set, &config, &mut images, 200, 100).unwrap();
+        assert_eq!(page_count(&pdf), 3);
+
+        // Pages whose shapes are all on hidden layers are skipped too
+        canvas_set.active_frame_mut().add_shape(rect());
+        canvas_set.active_frame_mut().toggle_layer_visibility();
+        let pdf = canvas_to_pdf(&canvas_set, &config, &mut images, 200, 100).unwrap();
+        assert_eq!(page_count(&pdf), 3);
+    }
+
+    #[test]
+    fn text_is_embedded_as_a_font() {
+        let mut frame = Frame::new();
+        frame.add_shape(rect());
+        let pdf = frames_to_pdf(&[(&frame, BoardBackground::default())], 200, 100).unwrap();
+        assert!(!String::from_utf8_lossy(&pdf).contains("/Type /Font"));
+
+        frame.add_shape(Shape::Text {
+            x: 20,
+            y: 50,
+            text: "Lecture notes".to_string(),
+            color: RED,
+            size: 24.0,
+            font_descriptor: FontDescriptor::default(),
+            effect: TextEffect::default(),
+            format: TextFormat::default(),
+            rotation: 0.0,
+        });
+        let pdf = frames_to_pdf(&[(&frame, BoardBackground::default())], 200, 100).unwrap();
+        assert!(String::from_utf8_lossy(&pdf).contains("/Type /Font"));
+    }
+}
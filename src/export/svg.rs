@@ -0,0 +1,535 @@
+//! SVG serialization of a frame.
+//!
+//! Each shape maps to the SVG element closest to how it is rendered on
+//! screen. Text becomes `<text>` elements laid out line by line with Pango,
+//! so wrapping and alignment match the overlay while the text stays
+//! selectable and searchable in SVG viewers.
+
+use std::fmt::Write;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+use crate::draw::render::{DEFAULT_SHADOW_COLOR, contrast_color};
+use crate::draw::text::{layout_origin, measure_text_layout};
+use crate::draw::{BoardBackground, Color, FontDescriptor, Frame, Shape, TextEffect, TextFormat};
+use crate::util;
+
+/// Pango's default resolution; font sizes are in points at this DPI.
+const PANGO_DPI: f64 = 96.0;
+
+/// Serializes a frame to an SVG document of the given size in pixels.
+///
+/// `background` fills the whole document first, as the board background
+/// does on screen (no color leaves it transparent). A background image is
+/// embedded as PNG data.
+pub fn frame_to_svg(
+    frame: &Frame,
+    background: &BoardBackground,
+    width: u32,
+    height: u32,
+) -> String {
+    let mut svg = String::new();
+    let _ = writeln!(svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    if let Some(color) = background.color {
+        let _ = writeln!(
+            svg,
+            r#"  <rect width="{width}" height="{height}"{}/>"#,
+            paint("fill", color)
+        );
+    }
+    if let Some((image, fit)) = &background.image {
+        match image.to_png() {
+            Ok(png) => {
+                let (x, y, scale) = image.placement(*fit, width as f64, height as f64);
+                let (image_width, image_height) = image.size();
+                let _ = writeln!(
+                    svg,
+                    r#"  <image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none" href="data:image/png;base64,{}"/>"#,
+                    num(x),
+                    num(y),
+                    num(image_width * scale),
+                    num(image_height * scale),
+                    BASE64.encode(png)
+                );
+            }
+            Err(err) => log::warn!("Leaving background image out of SVG: {:#}", err),
+        }
+    }
+    for (_, shape) in frame.visible_shapes() {
+        write_shape(&mut svg, shape);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Appends the elements for one shape.
+fn write_shape(svg: &mut String, shape: &Shape) {
+    let transform = match shape.rotation_center() {
+        Some((cx, cy, rotation)) if rotation != 0.0 => format!(
+            r#" transform="rotate({} {} {})""#,
+            num(rotation.to_degrees()),
+            num(cx),
+            num(cy)
+        ),
+        _ => String::new(),
+    };
+
+    match shape {
+        Shape::Freehand {
+            points,
+            color,
+            thick,
+        } => {
+            // Cairo draws nothing for a lone point, so neither do we
+            if points.len() < 2 {
+                return;
+            }
+            let points: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+            let _ = writeln!(
+                svg,
+                r#"  <polyline points="{}" fill="none"{} stroke-linecap="round" stroke-linejoin="round"/>"#,
+                points.join(" "),
+                stroke(*color, *thick)
+            );
+        }
+        Shape::Line {
+            x1,
+            y1,
+            x2,
+            y2,
+            color,
+            thick,
+        } => {
+            let _ = writeln!(
+                svg,
+                r#"  <line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}"{} stroke-linecap="round"/>"#,
+                stroke(*color, *thick)
+            );
+        }
+        Shape::Rect {
+            x,
+            y,
+            w,
+            h,
+            color,
+            thick,
+            ..
+        } => {
+            let _ = writeln!(
+                svg,
+                r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="none"{}{transform}/>"#,
+                (*x).min(x + w),
+                (*y).min(y + h),
+                w.abs(),
+                h.abs(),
+                stroke(*color, *thick)
+            );
+        }
+        Shape::Ellipse {
+            cx,
+            cy,
+            rx,
+            ry,
+            color,
+            thick,
+            ..
+        } => {
+            if *rx == 0 || *ry == 0 {
+                return;
+            }
+            let _ = writeln!(
+                svg,
+                r#"  <ellipse cx="{cx}" cy="{cy}" rx="{}" ry="{}" fill="none"{}{transform}/>"#,
+                rx.abs(),
+                ry.abs(),
+                stroke(*color, *thick)
+            );
+        }
+        Shape::Arrow {
+            x1,
+            y1,
+            x2,
+            y2,
+            color,
+            thick,
+            arrow_length,
+            arrow_angle,
+        } => {
+            let [left, right] =
+                util::calculate_arrowhead_custom(*x1, *y1, *x2, *y2, *arrow_length, *arrow_angle);
+            let _ = writeln!(
+                svg,
+                r#"  <path d="M {x2} {y2} L {x1} {y1} M {} {} L {x1} {y1} L {} {}" fill="none"{} stroke-linecap="round" stroke-linejoin="round"/>"#,
+                num(left.0),
+                num(left.1),
+                num(right.0),
+                num(right.1),
+                stroke(*color, *thick)
+            );
+        }
+        Shape::Text {
+            x,
+            y,
+            text,
+            color,
+            size,
+            font_descriptor,
+            effect,
+            format,
+            ..
+        } => {
+            write_text(
+                svg,
+                *x,
+                *y,
+                text,
+                *color,
+                *size,
+                font_descriptor,
+                effect,
+                format,
+                &transform,
+            );
+        }
+    }
+}
+
+/// Appends a text annotation with its readability effect.
+///
+/// Lines are positioned from the same Pango layout the overlay renders, one
+/// `<tspan>` per laid-out line.
+#[allow(clippy::too_many_arguments)]
+fn write_text(
+    svg: &mut String,
+    x: i32,
+    y: i32,
+    text: &str,
+    color: Color,
+    size: f64,
+    font_descriptor: &FontDescriptor,
+    effect: &TextEffect,
+    format: &TextFormat,
+    transform: &str,
+) {
+    let Some(layout) = measure_text_layout(text, size, font_descriptor, format) else {
+        return;
+    };
+    let scale = pango::SCALE as f64;
+    let (origin_x, origin_y) = layout_origin(&layout, x, y);
+
+    let mut lines = String::new();
+    let mut iter = layout.iter();
+    loop {
+        if let Some(line) = iter.line_readonly() {
+            let start = line.start_index() as usize;
+            let end = start + line.length() as usize;
+            let content = text.get(start..end).unwrap_or_default();
+            let content = content.trim_end_matches(['\n', '\r']);
+            if !content.is_empty() {
+                let (_ink, logical) = iter.line_extents();
+                let _ = write!(
+                    lines,
+                    r#"<tspan x="{}" y="{}">{}</tspan>"#,
+                    num(origin_x + logical.x() as f64 / scale),
+                    num(origin_y + iter.baseline() as f64 / scale),
+                    escape(content)
+                );
+            }
+        }
+        if !iter.next_line() {
+            break;
+        }
+    }
+    if lines.is_empty() {
+        return;
+    }
+
+    let font = format!(
+        r#" font-family="{}" font-size="{}px" font-weight="{}" font-style="{}" xml:space="preserve""#,
+        escape(&font_descriptor.family),
+        num(size.round() * PANGO_DPI / 72.0),
+        font_weight(&font_descriptor.weight),
+        escape(&font_descriptor.style.to_lowercase())
+    );
+
+    let _ = writeln!(svg, "  <g{transform}>");
+    let mut outline = String::new();
+    match *effect {
+        TextEffect::None => {}
+        TextEffect::Background {
+            color: effect_color,
+            padding,
+        } => {
+            let (ink, _logical) = layout.extents();
+            if ink.width() > 0 && ink.height() > 0 {
+                let background = effect_color.unwrap_or_else(|| contrast_color(color, 0.3));
+                let _ = writeln!(
+                    svg,
+                    r#"    <rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+                    num(origin_x + ink.x() as f64 / scale - padding),
+                    num(origin_y + ink.y() as f64 / scale - padding),
+                    num(ink.width() as f64 / scale + padding * 2.0),
+                    num(ink.height() as f64 / scale + padding * 2.0),
+                    paint("fill", background)
+                );
+            }
+        }
+        TextEffect::Shadow {
+            color: effect_color,
+            offset,
+        } => {
+            let shadow = effect_color.unwrap_or(DEFAULT_SHADOW_COLOR);
+            let _ = writeln!(
+                svg,
+                r#"    <text{font}{} transform="translate({} {})" aria-hidden="true">{lines}</text>"#,
+                paint("fill", shadow),
+                num(offset),
+                num(offset)
+            );
+        }
+        TextEffect::Outline {
+            color: effect_color,
+            width,
+        } => {
+            // Stroke under the fill, like the overlay draws it
+            let outline_color = effect_color.unwrap_or_else(|| contrast_color(color, 1.0));
+            outline = format!(
+                r#"{} stroke-linejoin="round" paint-order="stroke""#,
+                stroke(outline_color, width * 2.0)
+            );
+        }
+    }
+    let _ = writeln!(
+        svg,
+        "    <text{font}{}{outline}>{lines}</text>",
+        paint("fill", color)
+    );
+    let _ = writeln!(svg, "  </g>");
+}
+
+/// Formats `stroke` and `stroke-width` attributes.
+fn stroke(color: Color, width: f64) -> String {
+    format!(
+        r#"{} stroke-width="{}""#,
+        paint("stroke", color),
+        num(width)
+    )
+}
+
+/// Formats a color attribute, with its opacity when not fully opaque.
+fn paint(attribute: &str, color: Color) -> String {
+    let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let mut out = format!(
+        r##" {attribute}="#{:02x}{:02x}{:02x}""##,
+        channel(color.r),
+        channel(color.g),
+        channel(color.b)
+    );
+    if color.a < 1.0 {
+        let _ = write!(
+            out,
+            r#" {attribute}-opacity="{}""#,
+            num(color.a.clamp(0.0, 1.0))
+        );
+    }
+    out
+}
+
+/// Maps a Pango weight name to a CSS `font-weight`.
+fn font_weight(weight: &str) -> String {
+    let weight = weight.to_lowercase();
+    if weight.parse::<u16>().is_ok() {
+        return weight;
+    }
+    let value = match weight.as_str() {
+        "thin" => "100",
+        "ultralight" | "extralight" => "200",
+        "light" => "300",
+        "medium" => "500",
+        "semibold" | "demibold" => "600",
+        "bold" => "bold",
+        "ultrabold" | "extrabold" => "800",
+        "heavy" | "black" | "ultraheavy" => "900",
+        _ => "normal",
+    };
+    value.to_string()
+}
+
+/// Formats a coordinate with at most two decimals.
+fn num(value: f64) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded == 0.0 {
+        // Avoid "-0"
+        return "0".to_string();
+    }
+    format!("{rounded}")
+}
+
+/// Escapes text for use in XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ImageFit, TextAlign};
+    use crate::draw::background::BackgroundImage;
+    use crate::draw::color::{BLUE, RED, WHITE};
+    use std::rc::Rc;
+
+    fn text(text: &str, effect: TextEffect, format: TextFormat) -> Shape {
+        Shape::Text {
+            x: 100,
+            y: 100,
+            text: text.to_string(),
+            color: BLUE,
+            size: 24.0,
+            font_descriptor: FontDescriptor::default(),
+            effect,
+            format,
+            rotation: 0.0,
+        }
+    }
+
+    #[test]
+    fn every_shape_becomes_an_svg_element() {
+        let mut frame = Frame::new();
+        frame.add_shape(Shape::Freehand {
+            points: vec![(0, 0), (10, 5), (20, 0)],
+            color: RED,
+            thick: 3.0,
+        });
+        frame.add_shape(Shape::Line {
+            x1: 0,
+            y1: 0,
+            x2: 50,
+            y2: 50,
+            color: RED,
+            thick: 2.0,
+        });
+        frame.add_shape(Shape::Rect {
+            x: 40,
+            y: 40,
+            w: -20,
+            h: 10,
+            color: RED,
+            thick: 2.0,
+            rotation: std::f64::consts::FRAC_PI_2,
+        });
+        frame.add_shape(Shape::Ellipse {
+            cx: 50,
+            cy: 50,
+            rx: 10,
+            ry: 5,
+            color: Color { a: 0.5, ..RED },
+            thick: 2.0,
+            rotation: 0.0,
+        });
+        frame.add_shape(Shape::Arrow {
+            x1: 0,
+            y1: 0,
+            x2: 100,
+            y2: 0,
+            color: RED,
+            thick: 2.0,
+            arrow_length: 10.0,
+            arrow_angle: 30.0,
+        });
+
+        let svg = frame_to_svg(&frame, &Some(WHITE).into(), 640, 480);
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains(r#"width="640" height="480" viewBox="0 0 640 480""#));
+        assert!(svg.contains(r##"<rect width="640" height="480" fill="#ffffff"/>"##));
+        assert!(svg.contains(r#"<polyline points="0,0 10,5 20,0""#));
+        assert!(svg.contains(r#"<line x1="0" y1="0" x2="50" y2="50""#));
+        // Negative sizes are normalized and rotation is kept
+        assert!(svg.contains(r#"<rect x="20" y="40" width="20" height="10""#));
+        assert!(svg.contains(r#"transform="rotate(90 30 45)""#));
+        assert!(svg.contains(r##"stroke="#ff0000" stroke-opacity="0.5""##));
+        assert!(svg.contains(r#"<path d="M 100 0 L 0 0 M "#));
+        assert!(svg.trim_end().ends_with("</svg>"));
+
+        // Transparent boards have no background
+        let svg = frame_to_svg(&Frame::new(), &BoardBackground::default(), 640, 480);
+        assert!(!svg.contains("<rect"));
+    }
+
+    #[test]
+    fn background_images_are_embedded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("template.png");
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 320, 120).unwrap();
+        surface
+            .write_to_png(&mut std::fs::File::create(&path).unwrap())
+            .unwrap();
+        let image = BackgroundImage::load(&path).unwrap();
+        let background = BoardBackground {
+            color: Some(WHITE),
+            image: Some((Rc::new(image), ImageFit::Fit)),
+        };
+
+        let svg = frame_to_svg(&Frame::new(), &background, 640, 480);
+        assert!(svg.contains(r#"<image x="0" y="120" width="640" height="240""#));
+        assert!(svg.contains(r#"href="data:image/png;base64,iVBORw0KGgo"#));
+    }
+
+    #[test]
+    fn text_is_exported_as_selectable_lines() {
+        let mut frame = Frame::new();
+        frame.add_shape(text(
+            "a < b\nc & d",
+            TextEffect::Outline {
+                color: None,
+                width: 1.0,
+            },
+            TextFormat::default(),
+        ));
+        let svg = frame_to_svg(&frame, &BoardBackground::default(), 640, 480);
+        assert!(svg.contains(r#"font-family="Sans""#));
+        assert!(svg.contains(r#"x="100""#));
+        assert!(svg.contains(">a &lt; b</tspan>"));
+        assert!(svg.contains(">c &amp; d</tspan>"));
+        // Outline defaults to a contrasting color stroked under the fill
+        assert!(svg.contains(
+            r##"stroke="#ffffff" stroke-width="2" stroke-linejoin="round" paint-order="stroke""##
+        ));
+
+        // Wrapped text keeps the on-screen line breaks
+        let mut frame = Frame::new();
+        frame.add_shape(text(
+            "one two three four five six",
+            TextEffect::Shadow {
+                color: None,
+                offset: 2.0,
+            },
+            TextFormat {
+                align: TextAlign::Center,
+                wrap_width: Some(80.0),
+                line_spacing: 1.0,
+            },
+        ));
+        let svg = frame_to_svg(&frame, &BoardBackground::default(), 640, 480);
+        let text_element = svg
+            .lines()
+            .find(|line| line.contains("<text") && !line.contains("aria-hidden"));
+        assert!(text_element.unwrap().matches("<tspan").count() > 1);
+        assert!(svg.contains(r#"transform="translate(2 2)" aria-hidden="true""#));
+    }
+}
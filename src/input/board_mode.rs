@@ -1,33 +1,84 @@
 //! Board/canvas mode selection.
 
-use crate::config::BoardConfig;
+use crate::config::{BoardConfig, CustomBoardConfig};
 use crate::draw::Color;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 /// Board rendering mode
 ///
 /// Determines the background and visual style of the drawing canvas.
 /// Each mode maintains its own isolated frame of shapes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Modes are saved by name, so documents keep the shapes of custom boards even
+/// when the board is later removed from the config.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum BoardMode {
     /// Transparent overlay showing underlying screen (default)
+    #[default]
     Transparent,
     /// White/light background for drawing (whiteboard)
     Whiteboard,
     /// Dark/black background for drawing (blackboard)
     Blackboard,
+    /// User-defined board from a `[[board.custom]]` config entry
+    Custom(String),
 }
 
-impl Default for BoardMode {
-    fn default() -> Self {
-        Self::Transparent
+impl BoardMode {
+    /// Looks up a built-in board or a board defined in `config` by name.
+    ///
+    /// Names are matched case-insensitively; `None` means no such board.
+    pub fn from_name(name: &str, config: &BoardConfig) -> Option<Self> {
+        name.parse().ok().or_else(|| {
+            config
+                .custom
+                .iter()
+                .find(|board| board.name.eq_ignore_ascii_case(name.trim()))
+                .map(|board| Self::Custom(board.name.clone()))
+        })
+    }
+
+    /// Returns the board as named in `config`, for boards read from files.
+    ///
+    /// Custom board names are matched case-insensitively, like
+    /// [`BoardMode::from_name`]; boards missing from `config` are kept as is.
+    pub fn configured(&self, config: &BoardConfig) -> Self {
+        match self {
+            Self::Custom(name) => Self::from_name(name, config).unwrap_or_else(|| self.clone()),
+            mode => mode.clone(),
+        }
+    }
+
+    /// Returns the `[[board.custom]]` entry of a custom board.
+    fn custom_config<'a>(&self, config: &'a BoardConfig) -> Option<&'a CustomBoardConfig> {
+        let Self::Custom(name) = self else {
+            return None;
+        };
+        config
+            .custom
+            .iter()
+            .find(|board| board.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the name the board is saved and selected by.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Transparent => "transparent",
+            Self::Whiteboard => "whiteboard",
+            Self::Blackboard => "blackboard",
+            Self::Custom(name) => name,
+        }
     }
-}
 
-impl BoardMode {
     /// Returns the background color for this mode, if any.
     ///
     /// Transparent mode returns None (no background fill).
-    /// Whiteboard and Blackboard return their respective colors from config.
+    /// Whiteboard and Blackboard return their respective colors from config,
+    /// and custom boards their configured color and alpha. Custom boards that
+    /// are no longer configured have no background.
     pub fn background_color(&self, config: &BoardConfig) -> Option<Color> {
         match self {
             Self::Transparent => None,
@@ -49,6 +100,16 @@ impl BoardMode {
                     a: 1.0,
                 })
             }
+            Self::Custom(_) => {
+                let board = self.custom_config(config)?;
+                let rgb = board.background_color;
+                Some(Color {
+                    r: rgb[0],
+                    g: rgb[1],
+                    b: rgb[2],
+                    a: board.background_alpha,
+                })
+            }
         }
     }
 
@@ -77,10 +138,52 @@ impl BoardMode {
                     a: 1.0,
                 })
             }
+            Self::Custom(_) => {
+                let board = self.custom_config(config)?;
+                let rgb = board.pen_color?;
+                Some(Color {
+                    r: rgb[0],
+                    g: rgb[1],
+                    b: rgb[2],
+                    a: 1.0,
+                })
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for BoardMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<String> for BoardMode {
+    fn from(name: String) -> Self {
+        name.parse().unwrap_or(Self::Custom(name))
+    }
+}
+
+impl From<BoardMode> for String {
+    fn from(mode: BoardMode) -> Self {
+        match mode {
+            BoardMode::Custom(name) => name,
+            mode => mode.name().to_string(),
         }
     }
 }
 
+impl JsonSchema for BoardMode {
+    fn schema_name() -> Cow<'static, str> {
+        "BoardMode".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        String::json_schema(generator)
+    }
+}
+
+/// Parses the built-in board names; custom boards need [`BoardMode::from_name`].
 impl std::str::FromStr for BoardMode {
     type Err = ();
 
@@ -159,4 +262,37 @@ mod tests {
         );
         assert!(BoardMode::from_str("invalid").is_err());
     }
+
+    #[test]
+    fn custom_boards_are_found_by_name_and_saved_as_names() {
+        let mut config = BoardConfig::default();
+        config.custom.push(crate::config::CustomBoardConfig {
+            name: "Tinted".to_string(),
+            background_color: [0.0, 0.0, 0.0],
+            background_alpha: 0.4,
+            pen_color: None,
+            keys: Vec::new(),
+        });
+
+        let tinted = BoardMode::from_name("tinted", &config).unwrap();
+        assert_eq!(tinted, BoardMode::Custom("Tinted".to_string()));
+        assert_eq!(tinted.background_color(&config).unwrap().a, 0.4);
+        assert_eq!(tinted.default_pen_color(&config), None);
+        assert_eq!(
+            BoardMode::from_name("Whiteboard", &config),
+            Some(BoardMode::Whiteboard)
+        );
+        assert_eq!(BoardMode::from_name("blueprint", &config), None);
+
+        // Names read from files pick up the configured spelling
+        let saved = BoardMode::Custom("TINTED".to_string());
+        assert_eq!(saved.configured(&config), tinted);
+        assert_eq!(saved.background_color(&config).unwrap().a, 0.4);
+        let removed = BoardMode::Custom("Blueprint".to_string());
+        assert_eq!(removed.configured(&config), removed);
+
+        assert_eq!(serde_json::to_string(&tinted).unwrap(), r#""Tinted""#);
+        let parsed: BoardMode = serde_json::from_str(r#""blackboard""#).unwrap();
+        assert_eq!(parsed, BoardMode::Blackboard);
+    }
 }
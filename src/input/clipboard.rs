@@ -0,0 +1,146 @@
+//! Shape clipboard shared by every board.
+
+use crate::draw::{BoundingBox, Shape};
+use serde::{Deserialize, Serialize};
+
+/// MIME type used when shapes are placed on the system clipboard.
+pub const SHAPES_MIME_TYPE: &str = "application/x-wayscriber-shapes";
+
+/// Version of the serialized clipboard format.
+const FORMAT_VERSION: u32 = 1;
+
+/// Serialized form of copied shapes, as placed on the system clipboard.
+#[derive(Serialize, Deserialize)]
+struct ClipboardPayload {
+    version: u32,
+    shapes: Vec<Shape>,
+}
+
+/// Shapes copied or cut with the clipboard actions.
+///
+/// The clipboard lives outside the canvas frames, so shapes copied on one
+/// board can be pasted onto another.
+#[derive(Debug, Clone, Default)]
+pub struct ShapeClipboard {
+    shapes: Vec<Shape>,
+}
+
+impl ShapeClipboard {
+    /// Creates an empty clipboard.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if nothing has been copied yet.
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    /// Returns the copied shapes in draw order.
+    pub fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+
+    /// Replaces the clipboard contents.
+    pub fn set(&mut self, shapes: Vec<Shape>) {
+        self.shapes = shapes;
+    }
+}
+
+/// Returns the box enclosing the given shapes.
+pub fn bounds_of(shapes: &[Shape]) -> Option<BoundingBox> {
+    shapes
+        .iter()
+        .filter_map(Shape::bounding_box)
+        .reduce(|combined, bounds| combined.union(&bounds))
+}
+
+/// Serializes shapes for the system clipboard.
+pub fn serialize_shapes(shapes: &[Shape]) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec(&ClipboardPayload {
+        version: FORMAT_VERSION,
+        shapes: shapes.to_vec(),
+    })
+}
+
+/// Parses shapes read from the system clipboard.
+///
+/// Fails if the data is malformed or was written by a newer format version.
+pub fn deserialize_shapes(data: &[u8]) -> Result<Vec<Shape>, serde_json::Error> {
+    let payload: ClipboardPayload = serde_json::from_slice(data)?;
+    if payload.version > FORMAT_VERSION {
+        return Err(serde::de::Error::custom(format!(
+            "unsupported clipboard format version {}",
+            payload.version
+        )));
+    }
+    Ok(payload.shapes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TextAlign;
+    use crate::draw::text::{TextEffect, TextFormat};
+    use crate::draw::{Color, FontDescriptor};
+
+    #[test]
+    fn shapes_survive_a_round_trip() {
+        let shapes = vec![
+            Shape::Rect {
+                x: 10,
+                y: 20,
+                w: 30,
+                h: 40,
+                color: Color {
+                    r: 1.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 1.0,
+                },
+                thick: 3.0,
+                rotation: 0.5,
+            },
+            Shape::Text {
+                x: 5,
+                y: 15,
+                text: "Hello\nworld".to_string(),
+                color: Color {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 1.0,
+                    a: 1.0,
+                },
+                size: 24.0,
+                font_descriptor: FontDescriptor::default(),
+                effect: TextEffect::Outline {
+                    color: None,
+                    width: 2.0,
+                },
+                format: TextFormat {
+                    align: TextAlign::Center,
+                    wrap_width: Some(200.0),
+                    line_spacing: 1.2,
+                },
+                rotation: 0.0,
+            },
+        ];
+
+        let data = serialize_shapes(&shapes).unwrap();
+        let parsed = deserialize_shapes(&data).unwrap();
+        assert_eq!(format!("{parsed:?}"), format!("{shapes:?}"));
+    }
+
+    #[test]
+    fn rejects_foreign_and_newer_data() {
+        assert!(deserialize_shapes(b"not json").is_err());
+        assert!(deserialize_shapes(br#"{"version":99,"shapes":[]}"#).is_err());
+        // Rotation may be left out for unrotated shapes
+        let shapes = deserialize_shapes(
+            br#"{"version":1,"shapes":[{"type":"ellipse","cx":1,"cy":2,"rx":3,"ry":4,
+                "color":{"r":0.0,"g":0.0,"b":0.0,"a":1.0},"thick":2.0}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(shapes[0], Shape::Ellipse { rotation, .. } if rotation == 0.0));
+    }
+}
@@ -15,6 +15,24 @@ pub enum Key {
     Return,
     /// Backspace key
     Backspace,
+    /// Delete key
+    Delete,
+    /// Left arrow key
+    Left,
+    /// Right arrow key
+    Right,
+    /// Up arrow key
+    Up,
+    /// Down arrow key
+    Down,
+    /// Home key
+    Home,
+    /// End key
+    End,
+    /// Page Up key
+    PageUp,
+    /// Page Down key
+    PageDown,
     /// Tab key
     Tab,
     /// Space bar
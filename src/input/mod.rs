@@ -5,9 +5,12 @@
 //! and manages the state machine for different drawing modes (idle, drawing, text input).
 
 pub mod board_mode;
+pub mod clipboard;
 pub mod events;
 pub mod modifiers;
+pub mod selection;
 pub mod state;
+pub mod text_buffer;
 pub mod tool;
 
 // Re-export commonly used types at module level
@@ -18,4 +21,10 @@ pub use tool::Tool;
 
 // Re-export for public API (unused internally but part of public interface)
 #[allow(unused_imports)]
+pub use clipboard::ShapeClipboard;
+#[allow(unused_imports)]
 pub use modifiers::Modifiers;
+#[allow(unused_imports)]
+pub use selection::Selection;
+#[allow(unused_imports)]
+pub use text_buffer::TextBuffer;
@@ -0,0 +1,102 @@
+//! Shape selection for the selection tool.
+
+use crate::draw::{BoundingBox, Frame};
+
+/// Shapes picked with the selection tool.
+///
+/// Shapes are referenced by their index in the active frame, so the selection
+/// must be cleared whenever shapes are added, removed or reordered by other
+/// means (undo, clear, board switch, text editing).
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    /// Selected shape indices, sorted ascending without duplicates
+    indices: Vec<usize>,
+}
+
+impl Selection {
+    /// Creates an empty selection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if no shapes are selected.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Returns the selected shape indices in draw order.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Returns true if the shape at `index` is selected.
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices.binary_search(&index).is_ok()
+    }
+
+    /// Deselects everything.
+    pub fn clear(&mut self) {
+        self.indices.clear();
+    }
+
+    /// Replaces the selection with the given shapes.
+    pub fn set(&mut self, indices: impl IntoIterator<Item = usize>) {
+        self.indices.clear();
+        self.extend(indices);
+    }
+
+    /// Adds the given shapes to the selection.
+    pub fn extend(&mut self, indices: impl IntoIterator<Item = usize>) {
+        self.indices.extend(indices);
+        self.indices.sort_unstable();
+        self.indices.dedup();
+    }
+
+    /// Selects the shapes if the first one is unselected, or deselects them otherwise.
+    ///
+    /// Used to toggle a shape together with the rest of its group.
+    pub fn toggle(&mut self, indices: &[usize]) {
+        let Some(&first) = indices.first() else {
+            return;
+        };
+        if self.contains(first) {
+            self.indices.retain(|index| !indices.contains(index));
+        } else {
+            self.extend(indices.iter().copied());
+        }
+    }
+
+    /// Returns the box enclosing every selected shape in `frame`.
+    ///
+    /// Indices that no longer exist in the frame are ignored.
+    pub fn bounds(&self, frame: &Frame) -> Option<BoundingBox> {
+        self.indices
+            .iter()
+            .filter_map(|&index| frame.shapes.get(index)?.bounding_box())
+            .reduce(|combined, bounds| combined.union(&bounds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_stays_sorted_and_unique() {
+        let mut selection = Selection::new();
+        selection.set([4, 1, 4]);
+        selection.extend([2, 1]);
+        assert_eq!(selection.indices(), &[1, 2, 4]);
+
+        selection.toggle(&[2]);
+        selection.toggle(&[3]);
+        assert_eq!(selection.indices(), &[1, 3, 4]);
+
+        selection.toggle(&[0, 2]);
+        assert_eq!(selection.indices(), &[0, 1, 2, 3, 4]);
+        selection.toggle(&[0, 2]);
+        assert_eq!(selection.indices(), &[1, 3, 4]);
+        assert!(selection.contains(3));
+        assert!(!selection.contains(2));
+    }
+}
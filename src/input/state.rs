@@ -1,15 +1,25 @@
 //! Drawing state machine and input state management.
 
 use super::board_mode::BoardMode;
+use super::clipboard::{self, ShapeClipboard};
 use super::events::{Key, MouseButton};
 use super::modifiers::Modifiers;
+use super::selection::Selection;
+use super::text_buffer::TextBuffer;
 use super::tool::Tool;
-use crate::config::{Action, BoardConfig, KeyBinding};
-use crate::draw::{CanvasSet, Color, FontDescriptor, Shape};
+use crate::config::{Action, BoardConfig, KeyBinding, PaletteConfig};
+use crate::document::SaveMode;
+use crate::draw::frame::ShapeId;
+use crate::draw::handles::SelectionHandle;
+use crate::draw::shape::rotate_point;
+use crate::draw::text::{TextEffect, TextFormat, text_index_at_point};
+use crate::draw::{BoundingBox, CanvasSet, Color, FontDescriptor, Frame, Shape};
+use crate::export::ExportFormat;
 use crate::legacy;
 use crate::util;
 use std::collections::HashMap;
 use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 
 /// Current drawing mode state machine.
 ///
@@ -36,9 +46,84 @@ pub enum DrawingState {
         x: i32,
         /// Y coordinate where text will be placed
         y: i32,
-        /// Accumulated text buffer
-        buffer: String,
+        /// Text being edited, with caret and selection
+        buffer: TextBuffer,
+        /// Existing text shape reopened for editing, if any
+        editing: Option<Box<EditedText>>,
     },
+    /// Dragging a rubber-band rectangle with the selection tool
+    SelectingRegion {
+        /// X coordinate where the drag started
+        start_x: i32,
+        /// Y coordinate where the drag started
+        start_y: i32,
+    },
+    /// Dragging the selected shapes with the selection tool
+    MovingSelection {
+        /// X coordinate the shapes were last moved to
+        last_x: i32,
+        /// Y coordinate the shapes were last moved to
+        last_y: i32,
+        /// Selected shapes as they were before the drag, with their indices
+        originals: Vec<(usize, Shape)>,
+    },
+    /// Dragging a scale or rotate handle of the selection
+    TransformingSelection {
+        /// Handle being dragged
+        handle: SelectionHandle,
+        /// X coordinate where the drag started
+        start_x: i32,
+        /// Y coordinate where the drag started
+        start_y: i32,
+        /// Bounds of the selection when the drag started
+        bounds: BoundingBox,
+        /// Selected shapes as they were before the drag, with their indices
+        originals: Vec<(usize, Shape)>,
+    },
+}
+
+/// Distance in pixels within which a click still hits a shape's stroke.
+const SELECTION_HIT_TOLERANCE: f64 = 4.0;
+
+/// Distance in pixels moved by arrow keys with Shift held (1 pixel otherwise).
+const SELECTION_NUDGE_LARGE: i32 = 10;
+
+/// Rotation step in degrees when rotating with Shift held.
+const ROTATION_SNAP_DEGREES: f64 = 15.0;
+
+/// Releasing the peek key sooner than this keeps the annotations hidden
+/// (a tap toggles peeking); releasing it later shows them again.
+const PEEK_HOLD_THRESHOLD: Duration = Duration::from_millis(300);
+
+/// How the peek action is hiding the annotations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Peek {
+    /// The peek key is down
+    Held {
+        /// Key that started peeking
+        key: Key,
+        /// When it was pressed
+        since: Instant,
+    },
+    /// Peeking until the peek action is used again
+    Toggled,
+}
+
+/// A committed text shape that was reopened in text mode.
+///
+/// The shape is taken out of its frame while being edited so it is not drawn
+/// twice; it goes back at its old position in draw order when editing ends,
+/// and the edit is recorded as a single change to the shape.
+#[derive(Debug, Clone)]
+pub struct EditedText {
+    /// Index the shape had in the active frame
+    pub index: usize,
+    /// Stable ID of the shape in the active frame
+    pub id: ShapeId,
+    /// The shape as it was before editing (restored if editing is cancelled)
+    pub original: Shape,
+    /// Paragraph format applied while editing (starts as the original's)
+    pub format: TextFormat,
 }
 
 /// Main input state containing all drawing session state.
@@ -49,7 +134,7 @@ pub enum DrawingState {
 pub struct InputState {
     /// Multi-frame canvas management (transparent, whiteboard, blackboard)
     pub canvas_set: CanvasSet,
-    /// Current drawing color (changed with color keys: R, G, B, etc.)
+    /// Current drawing color (changed with palette keys: R, G, B, etc.)
     pub current_color: Color,
     /// Current pen/line thickness in pixels (changed with +/- keys)
     pub current_thickness: f64,
@@ -57,8 +142,10 @@ pub struct InputState {
     pub current_font_size: f64,
     /// Font descriptor for text rendering (family, weight, style)
     pub font_descriptor: FontDescriptor,
-    /// Whether to draw background behind text
-    pub text_background_enabled: bool,
+    /// Readability effect for new text (background box, outline or shadow)
+    pub text_effect: TextEffect,
+    /// Paragraph format for new text (alignment cycled at runtime)
+    pub text_format: TextFormat,
     /// Arrowhead length in pixels (from config)
     pub arrow_length: f64,
     /// Arrowhead angle in degrees (from config)
@@ -81,10 +168,40 @@ pub struct InputState {
     pub board_previous_color: Option<Color>,
     /// Board mode configuration
     pub board_config: BoardConfig,
+    /// Palette colors, indexed by `Action::SetColor`
+    pub palette: Vec<Color>,
+    /// Recently used colors, most recent first (shown as status bar swatches)
+    pub recent_colors: Vec<Color>,
+    /// Maximum number of colors kept in `recent_colors`
+    pub recent_colors_limit: usize,
+    /// Whether the selection tool is active (left drag selects instead of drawing)
+    pub selection_mode: bool,
+    /// Shapes selected in the active frame
+    pub selection: Selection,
+    /// Shapes copied or cut, shared across boards
+    pub clipboard: ShapeClipboard,
     /// Keybinding action map for efficient lookup
     action_map: HashMap<KeyBinding, Action>,
     /// Pending capture action (to be handled by WaylandState)
     pending_capture_action: Option<Action>,
+    /// Shapes to place on the system clipboard (to be handled by WaylandState)
+    pending_clipboard_export: Option<Vec<Shape>>,
+    /// Whether a paste is waiting for shapes from the system clipboard
+    pending_clipboard_import: bool,
+    /// Requested export of the boards (to be handled by WaylandState)
+    pending_export: Option<ExportFormat>,
+    /// Requested save of the document (to be handled by WaylandState)
+    pending_document_save: Option<SaveMode>,
+    /// Whether the "restore unsaved drawing?" prompt is showing
+    recovery_prompt: bool,
+    /// Answer to the recovery prompt (to be handled by WaylandState)
+    recovery_answer: Option<bool>,
+    /// Whether annotations are hidden to show the bare screen, and why
+    peek: Option<Peek>,
+    /// Whether pointer and keyboard input pass through to the apps below
+    click_through: bool,
+    /// Last known pointer position, used as the paste target
+    pointer_position: Option<(i32, i32)>,
 }
 
 impl InputState {
@@ -98,10 +215,12 @@ impl InputState {
     /// * `thickness` - Initial pen thickness in pixels
     /// * `font_size` - Font size for text mode in points
     /// * `font_descriptor` - Font configuration for text rendering
-    /// * `text_background_enabled` - Whether to draw background behind text
+    /// * `text_effect` - Readability effect drawn with text
+    /// * `text_format` - Alignment, wrap width and line spacing for text
     /// * `arrow_length` - Arrowhead length in pixels
     /// * `arrow_angle` - Arrowhead angle in degrees
     /// * `board_config` - Board mode configuration
+    /// * `palette_config` - Palette colors and recent color history size
     /// * `action_map` - Keybinding action map
     #[allow(clippy::too_many_arguments)]
     pub fn with_defaults(
@@ -109,19 +228,34 @@ impl InputState {
         thickness: f64,
         font_size: f64,
         font_descriptor: FontDescriptor,
-        text_background_enabled: bool,
+        text_effect: TextEffect,
+        text_format: TextFormat,
         arrow_length: f64,
         arrow_angle: f64,
         board_config: BoardConfig,
+        palette_config: PaletteConfig,
         action_map: HashMap<KeyBinding, Action>,
     ) -> Self {
+        let palette = palette_config
+            .colors
+            .iter()
+            .map(|entry| entry.color.to_color())
+            .collect();
+        let recent_colors_limit = palette_config.recent_colors;
+        let recent_colors = if recent_colors_limit > 0 {
+            vec![color]
+        } else {
+            Vec::new()
+        };
+
         Self {
             canvas_set: CanvasSet::new(),
             current_color: color,
             current_thickness: thickness,
             current_font_size: font_size,
             font_descriptor,
-            text_background_enabled,
+            text_effect,
+            text_format,
             arrow_length,
             arrow_angle,
             modifiers: Modifiers::new(),
@@ -133,8 +267,23 @@ impl InputState {
             screen_height: 0,
             board_previous_color: None,
             board_config,
+            palette,
+            recent_colors,
+            recent_colors_limit,
+            selection_mode: false,
+            selection: Selection::new(),
+            clipboard: ShapeClipboard::new(),
             action_map,
             pending_capture_action: None,
+            pending_clipboard_export: None,
+            pending_clipboard_import: false,
+            pending_export: None,
+            pending_document_save: None,
+            recovery_prompt: false,
+            recovery_answer: None,
+            peek: None,
+            click_through: false,
+            pointer_position: None,
         }
     }
 
@@ -177,7 +326,7 @@ impl InputState {
     }
 
     /// Returns the current board mode.
-    pub fn board_mode(&self) -> BoardMode {
+    pub fn board_mode(&self) -> &BoardMode {
         self.canvas_set.active_mode()
     }
 
@@ -210,6 +359,49 @@ impl InputState {
         log::debug!("Font size adjusted to {:.1}px", self.current_font_size);
     }
 
+    /// Selects a new drawing color and records it in the recent colors list.
+    ///
+    /// The color moves to the front of the list; the oldest entry is dropped
+    /// once the list exceeds `recent_colors_limit`.
+    pub fn select_color(&mut self, color: Color) {
+        self.current_color = color;
+        self.needs_redraw = true;
+
+        if self.recent_colors_limit == 0 {
+            return;
+        }
+        self.recent_colors.retain(|recent| *recent != color);
+        self.recent_colors.insert(0, color);
+        self.recent_colors.truncate(self.recent_colors_limit);
+    }
+
+    /// Steps through the recent colors list without reordering it.
+    ///
+    /// # Arguments
+    /// * `forward` - `true` to move to the next (older) color, `false` for the previous one
+    pub fn cycle_recent_color(&mut self, forward: bool) {
+        let len = self.recent_colors.len();
+        if len == 0 {
+            return;
+        }
+
+        let index = match self
+            .recent_colors
+            .iter()
+            .position(|recent| *recent == self.current_color)
+        {
+            Some(pos) if forward => (pos + 1) % len,
+            Some(pos) => (pos + len - 1) % len,
+            None => 0,
+        };
+
+        let color = self.recent_colors[index];
+        if color != self.current_color {
+            self.current_color = color;
+            self.needs_redraw = true;
+        }
+    }
+
     /// Takes and clears any pending capture action.
     ///
     /// This is called by WaylandState to retrieve capture actions that need
@@ -221,6 +413,130 @@ impl InputState {
         self.pending_capture_action.take()
     }
 
+    /// Takes shapes copied since the last call, for the system clipboard.
+    ///
+    /// Called by WaylandState after key presses, like
+    /// [`Self::take_pending_capture_action`].
+    pub fn take_pending_clipboard_export(&mut self) -> Option<Vec<Shape>> {
+        self.pending_clipboard_export.take()
+    }
+
+    /// Returns true once if a paste found the internal clipboard empty.
+    ///
+    /// The backend then reads shapes from the system clipboard (for example,
+    /// copied in another wayscriber session) and hands them to
+    /// [`Self::paste_shapes`].
+    pub fn take_pending_clipboard_import(&mut self) -> bool {
+        std::mem::take(&mut self.pending_clipboard_import)
+    }
+
+    /// Takes the export format requested since the last call.
+    ///
+    /// The backend writes the active board in that format, since it knows
+    /// the screen size and where files are saved.
+    pub fn take_pending_export(&mut self) -> Option<ExportFormat> {
+        self.pending_export.take()
+    }
+
+    /// Takes the document save requested since the last call.
+    ///
+    /// The backend knows which file is open and where new documents go.
+    pub fn take_pending_document_save(&mut self) -> Option<SaveMode> {
+        self.pending_document_save.take()
+    }
+
+    /// Asks whether to restore a session that was not closed normally.
+    ///
+    /// Until answered, `Enter`/`Y` restores, `Escape`/`N` discards, and all
+    /// other keys and clicks are ignored.
+    pub fn show_recovery_prompt(&mut self) {
+        self.recovery_prompt = true;
+        self.needs_redraw = true;
+    }
+
+    /// Returns `true` while the recovery prompt is showing.
+    pub fn recovery_prompt_visible(&self) -> bool {
+        self.recovery_prompt
+    }
+
+    /// Takes the answer to the recovery prompt: `true` to restore.
+    pub fn take_recovery_answer(&mut self) -> Option<bool> {
+        self.recovery_answer.take()
+    }
+
+    /// Returns `true` while the peek action hides all annotations.
+    ///
+    /// Nothing is drawn while peeking, so the overlay shows the bare screen.
+    /// Shapes are untouched and come back as they were.
+    pub fn is_peeking(&self) -> bool {
+        self.peek.is_some()
+    }
+
+    /// Handles a press of the key bound to the peek action.
+    ///
+    /// Peeking starts on press. If the key is released within
+    /// [`PEEK_HOLD_THRESHOLD`] it stays on until the key is pressed again;
+    /// otherwise it ends on release. Key repeats are ignored.
+    fn press_peek(&mut self, key: Key, now: Instant) {
+        match self.peek {
+            None => self.peek = Some(Peek::Held { key, since: now }),
+            Some(Peek::Toggled) => self.peek = None,
+            Some(Peek::Held { .. }) => return,
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Handles a key release while the peek key may be held.
+    fn release_peek(&mut self, key: Key, now: Instant) {
+        let Some(Peek::Held { key: held, since }) = self.peek else {
+            return;
+        };
+        if !same_key(held, key) {
+            return;
+        }
+        if now.duration_since(since) < PEEK_HOLD_THRESHOLD {
+            self.peek = Some(Peek::Toggled);
+        } else {
+            self.peek = None;
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Returns `true` while input passes through the overlay to the apps below.
+    pub fn is_click_through(&self) -> bool {
+        self.click_through
+    }
+
+    /// Records that the overlay stopped (or resumed) taking input.
+    ///
+    /// Releases and drags that start in click-through mode never reach us, so
+    /// held modifiers are released, peeking stops, and shapes being drawn or
+    /// dragged are cancelled. Text being typed is kept so it can be finished
+    /// once input comes back.
+    pub fn set_click_through(&mut self, enabled: bool) {
+        if self.click_through == enabled {
+            return;
+        }
+        self.click_through = enabled;
+        if enabled {
+            self.modifiers = Modifiers::new();
+            self.peek = None;
+            if !matches!(self.state, DrawingState::TextInput { .. }) {
+                self.cancel_current_action();
+            }
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Runs the action bound to a pressed key.
+    fn handle_key_action(&mut self, key: Key, action: Action) {
+        if action == Action::Peek {
+            self.press_peek(key, Instant::now());
+        } else {
+            self.handle_action(action);
+        }
+    }
+
     /// Switches to a different board mode with color auto-adjustment.
     ///
     /// Handles mode transitions with automatic color adjustment for contrast:
@@ -230,7 +546,7 @@ impl InputState {
     ///
     /// Also resets drawing state to prevent partial shapes crossing modes.
     pub fn switch_board_mode(&mut self, new_mode: BoardMode) {
-        let current_mode = self.canvas_set.active_mode();
+        let current_mode = self.canvas_set.active_mode().clone();
 
         // Toggle behavior: if already in target mode, return to transparent
         let target_mode = if current_mode == new_mode && new_mode != BoardMode::Transparent {
@@ -246,9 +562,9 @@ impl InputState {
 
         // Handle color auto-adjustment based on transition type (if enabled)
         if self.board_config.auto_adjust_pen {
-            match (current_mode, target_mode) {
+            match (&current_mode, &target_mode) {
                 // Entering board mode from transparent
-                (BoardMode::Transparent, BoardMode::Whiteboard | BoardMode::Blackboard) => {
+                (BoardMode::Transparent, _) => {
                     // Save current color and apply board default
                     self.board_previous_color = Some(self.current_color);
                     if let Some(default_color) = target_mode.default_pen_color(&self.board_config) {
@@ -256,7 +572,7 @@ impl InputState {
                     }
                 }
                 // Exiting board mode to transparent
-                (BoardMode::Whiteboard | BoardMode::Blackboard, BoardMode::Transparent) => {
+                (_, BoardMode::Transparent) => {
                     // Restore previous color if we saved one
                     if let Some(prev_color) = self.board_previous_color {
                         self.current_color = prev_color;
@@ -264,28 +580,508 @@ impl InputState {
                     }
                 }
                 // Switching between board modes
-                (BoardMode::Whiteboard, BoardMode::Blackboard)
-                | (BoardMode::Blackboard, BoardMode::Whiteboard) => {
+                _ => {
                     // Apply new board's default color
                     if let Some(default_color) = target_mode.default_pen_color(&self.board_config) {
                         self.current_color = default_color;
                     }
                 }
-                // All other transitions (shouldn't happen, but handle gracefully)
-                _ => {}
             }
         }
 
+        // Reset drawing state to prevent partial shapes crossing modes.
+        // Done before switching so reopened text goes back to its own frame.
+        self.cancel_current_action();
+        self.selection.clear();
+
+        log::info!("Switched from {:?} to {:?} mode", current_mode, target_mode);
+
         // Switch the active frame
         self.canvas_set.switch_mode(target_mode);
 
-        // Reset drawing state to prevent partial shapes crossing modes
-        self.state = DrawingState::Idle;
-
         // Trigger redraw
         self.needs_redraw = true;
+    }
 
-        log::info!("Switched from {:?} to {:?} mode", current_mode, target_mode);
+    /// Applies a page change to the active board.
+    ///
+    /// Like switching boards, this first cancels whatever is in progress so
+    /// partial shapes don't move to another page. `change` returns whether a
+    /// different page is now showing.
+    fn change_page(&mut self, change: impl FnOnce(&mut CanvasSet) -> bool) {
+        self.cancel_current_action();
+        self.selection.clear();
+        if change(&mut self.canvas_set) {
+            let (index, count) = self.canvas_set.page_position();
+            log::info!("Showing page {}/{}", index + 1, count);
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Applies a layer change to the active frame, after finishing whatever
+    /// was in progress and dropping the selection.
+    fn change_layer(&mut self, change: impl FnOnce(&mut Frame)) {
+        self.cancel_current_action();
+        self.selection.clear();
+        let frame = self.canvas_set.active_frame_mut();
+        change(frame);
+        let layer = &frame.layers()[frame.active_layer()];
+        log::info!(
+            "Layer {} is active{}",
+            layer.name,
+            if layer.visible { "" } else { " (hidden)" }
+        );
+        self.needs_redraw = true;
+    }
+
+    /// Returns the text shape being typed in text mode, styled as it will be committed.
+    ///
+    /// New text uses the current color, font size, font and paragraph format;
+    /// reopened text keeps the style it was created with. The text may be empty.
+    pub fn text_input_shape(&self) -> Option<Shape> {
+        let DrawingState::TextInput {
+            x,
+            y,
+            buffer,
+            editing,
+        } = &self.state
+        else {
+            return None;
+        };
+
+        if let Some(EditedText {
+            original:
+                Shape::Text {
+                    color,
+                    size,
+                    font_descriptor,
+                    effect,
+                    rotation,
+                    ..
+                },
+            format,
+            ..
+        }) = editing.as_deref()
+        {
+            return Some(Shape::Text {
+                x: *x,
+                y: *y,
+                text: buffer.text().to_string(),
+                color: *color,
+                size: *size,
+                font_descriptor: font_descriptor.clone(),
+                effect: *effect,
+                format: *format,
+                rotation: *rotation,
+            });
+        }
+
+        Some(Shape::Text {
+            x: *x,
+            y: *y,
+            text: buffer.text().to_string(),
+            color: self.current_color,
+            size: self.current_font_size,
+            font_descriptor: self.font_descriptor.clone(),
+            effect: self.text_effect,
+            format: self.text_format,
+            rotation: 0.0,
+        })
+    }
+
+    /// Finishes text input, adding the typed text to the active frame.
+    ///
+    /// Reopened text goes back at its original position in draw order; if all
+    /// of its text was deleted, the shape is removed.
+    fn commit_text_input(&mut self) {
+        let shape = self.text_input_shape();
+        let DrawingState::TextInput {
+            buffer, editing, ..
+        } = std::mem::replace(&mut self.state, DrawingState::Idle)
+        else {
+            return;
+        };
+
+        let frame = self.canvas_set.active_frame_mut();
+        match (shape, editing) {
+            (Some(shape), Some(editing)) => {
+                let EditedText {
+                    index,
+                    id,
+                    original,
+                    ..
+                } = *editing;
+                frame.restore_shape(index, id, original);
+                if buffer.is_empty() {
+                    frame.remove_shape(index);
+                } else {
+                    frame.replace_shape(index, shape);
+                }
+            }
+            (Some(shape), None) if !buffer.is_empty() => {
+                frame.add_shape(shape);
+            }
+            _ => {}
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Cancels drawing or text input, restoring any text shape reopened for editing.
+    fn cancel_current_action(&mut self) {
+        match std::mem::replace(&mut self.state, DrawingState::Idle) {
+            DrawingState::TextInput {
+                editing: Some(editing),
+                ..
+            } => {
+                self.canvas_set.active_frame_mut().restore_shape(
+                    editing.index,
+                    editing.id,
+                    editing.original,
+                );
+            }
+            DrawingState::MovingSelection { originals, .. }
+            | DrawingState::TransformingSelection { originals, .. } => {
+                // Put the dragged shapes back as they were
+                let frame = self.canvas_set.active_frame_mut();
+                for (index, original) in originals {
+                    if let Some(shape) = frame.shape_mut(index) {
+                        *shape = original;
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Reapplies the handle drag in progress for the pointer at `(x, y)`.
+    ///
+    /// The transform is always computed from the shapes as they were when the
+    /// drag started, so rounding errors don't accumulate while dragging.
+    fn update_selection_transform(&mut self, x: i32, y: i32) {
+        let DrawingState::TransformingSelection {
+            handle,
+            start_x,
+            start_y,
+            bounds,
+            originals,
+        } = &self.state
+        else {
+            return;
+        };
+        let start = (*start_x as f64, *start_y as f64);
+        let current = (x as f64, y as f64);
+        let frame = self.canvas_set.active_frame_mut();
+
+        if let Some((mut factor_x, mut factor_y)) = handle.scale_factors(bounds, start, current) {
+            let corner = !matches!(
+                handle,
+                SelectionHandle::Top
+                    | SelectionHandle::Bottom
+                    | SelectionHandle::Left
+                    | SelectionHandle::Right
+            );
+            if self.modifiers.shift && corner {
+                // Keep proportions
+                let factor = factor_x.abs().max(factor_y.abs());
+                factor_x = factor.copysign(factor_x);
+                factor_y = factor.copysign(factor_y);
+            }
+            let (anchor_x, anchor_y) = handle.anchor(bounds);
+            for (index, original) in originals {
+                if let Some(shape) = frame.shape_mut(*index) {
+                    *shape = original.clone();
+                    shape.scale(anchor_x, anchor_y, factor_x, factor_y);
+                }
+            }
+        } else {
+            let (center_x, center_y) = bounds.center();
+            let mut angle = (current.1 - center_y).atan2(current.0 - center_x)
+                - (start.1 - center_y).atan2(start.0 - center_x);
+            if self.modifiers.shift {
+                let step = ROTATION_SNAP_DEGREES.to_radians();
+                angle = (angle / step).round() * step;
+            }
+            for (index, original) in originals {
+                if let Some(shape) = frame.shape_mut(*index) {
+                    *shape = original.clone();
+                    shape.rotate(center_x, center_y, angle);
+                }
+            }
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Returns the rubber-band rectangle while a selection drag is in progress.
+    ///
+    /// # Arguments
+    /// * `current_x` - Current mouse X coordinate
+    /// * `current_y` - Current mouse Y coordinate
+    pub fn selection_region(&self, current_x: i32, current_y: i32) -> Option<BoundingBox> {
+        if let DrawingState::SelectingRegion { start_x, start_y } = self.state {
+            Some(BoundingBox::from_corners(
+                start_x as f64,
+                start_y as f64,
+                current_x as f64,
+                current_y as f64,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the box enclosing the selected shapes, if any are selected.
+    pub fn selection_bounds(&self) -> Option<BoundingBox> {
+        self.selection.bounds(self.canvas_set.active_frame())
+    }
+
+    /// Returns the selected shapes along with their indices.
+    fn selected_shapes(&self) -> Vec<(usize, Shape)> {
+        let frame = self.canvas_set.active_frame();
+        self.selection
+            .indices()
+            .iter()
+            .filter_map(|&index| Some((index, frame.shapes.get(index)?.clone())))
+            .collect()
+    }
+
+    /// Moves every selected shape by the given offset.
+    ///
+    /// The move is not recorded for undo; see [`Self::nudge_selection`].
+    fn translate_selection(&mut self, dx: i32, dy: i32) {
+        if dx == 0 && dy == 0 {
+            return;
+        }
+        let frame = self.canvas_set.active_frame_mut();
+        for &index in self.selection.indices() {
+            if let Some(shape) = frame.shape_mut(index) {
+                shape.translate(dx, dy);
+            }
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Moves every selected shape by the given offset as one undo step.
+    fn nudge_selection(&mut self, dx: i32, dy: i32) {
+        let originals = self.selected_shapes();
+        self.translate_selection(dx, dy);
+        self.canvas_set.active_frame_mut().record_changes(originals);
+    }
+
+    /// Removes the selected shapes from the active frame.
+    fn delete_selection(&mut self) {
+        if self.selection.is_empty() {
+            return;
+        }
+        self.canvas_set
+            .active_frame_mut()
+            .remove_shapes(self.selection.indices());
+        self.selection.clear();
+        self.needs_redraw = true;
+    }
+
+    /// Returns the indices of the shapes the clipboard and arrange actions work on.
+    ///
+    /// That is the selection if there is one, otherwise the topmost shape
+    /// under the pointer along with the rest of its group.
+    fn action_targets(&self) -> Vec<usize> {
+        if !self.selection.is_empty() {
+            return self.selection.indices().to_vec();
+        }
+        let frame = self.canvas_set.active_frame();
+        self.pointer_position
+            .and_then(|(x, y)| frame.shape_at(x, y, SELECTION_HIT_TOLERANCE))
+            .map(|index| frame.with_groups([index]))
+            .unwrap_or_default()
+    }
+
+    /// Moves the targeted shapes to the top or bottom of the draw order.
+    fn restack_shapes(&mut self, to_front: bool) {
+        let indices = self.action_targets();
+        if indices.is_empty() {
+            return;
+        }
+        let frame = self.canvas_set.active_frame_mut();
+        let moved = if to_front {
+            frame.bring_to_front(&indices)
+        } else {
+            frame.send_to_back(&indices)
+        };
+        if !self.selection.is_empty() {
+            self.selection.set(moved);
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Copies the targeted shapes to the clipboard, removing them if `cut` is set.
+    fn copy_shapes(&mut self, cut: bool) {
+        let indices = self.action_targets();
+        if indices.is_empty() {
+            return;
+        }
+        let frame = self.canvas_set.active_frame_mut();
+        let shapes: Vec<Shape> = indices
+            .iter()
+            .filter_map(|&index| frame.shapes.get(index).cloned())
+            .collect();
+        if cut {
+            frame.remove_shapes(&indices);
+            self.selection.clear();
+            self.needs_redraw = true;
+        }
+        log::debug!(
+            "{} {} shape(s)",
+            if cut { "Cut" } else { "Copied" },
+            shapes.len()
+        );
+        self.pending_clipboard_export = Some(shapes.clone());
+        self.clipboard.set(shapes);
+    }
+
+    /// Adds shapes to the active frame, centered on the pointer.
+    ///
+    /// Without a known pointer position the shapes keep their coordinates.
+    /// With the selection tool active, the pasted shapes become the selection.
+    pub fn paste_shapes(&mut self, mut shapes: Vec<Shape>) {
+        if shapes.is_empty() || !matches!(self.state, DrawingState::Idle) {
+            return;
+        }
+        if let Some((x, y)) = self.pointer_position
+            && let Some(bounds) = clipboard::bounds_of(&shapes)
+        {
+            let (center_x, center_y) = bounds.center();
+            let dx = x - center_x.round() as i32;
+            let dy = y - center_y.round() as i32;
+            for shape in &mut shapes {
+                shape.translate(dx, dy);
+            }
+        }
+
+        let pasted = self.canvas_set.active_frame_mut().add_shapes(shapes);
+        if self.selection_mode {
+            self.selection.set(pasted);
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Starts a selection tool drag at the given point.
+    ///
+    /// Pressing on a handle of the current selection scales or rotates it.
+    /// Pressing on a shape (or inside the current selection) starts moving the
+    /// selection; pressing on empty space starts a rubber-band selection. With
+    /// Shift held, clicking a shape toggles it and rubber-banding adds to the
+    /// existing selection.
+    fn begin_selection_drag(&mut self, x: i32, y: i32) {
+        // Handles sit on top of everything else
+        if let Some(bounds) = self.selection_bounds()
+            && let Some(handle) = SelectionHandle::at(&bounds, x as f64, y as f64)
+        {
+            self.state = DrawingState::TransformingSelection {
+                handle,
+                start_x: x,
+                start_y: y,
+                bounds,
+                originals: self.selected_shapes(),
+            };
+            self.needs_redraw = true;
+            return;
+        }
+
+        let frame = self.canvas_set.active_frame();
+        let hit = frame.shape_at(x, y, SELECTION_HIT_TOLERANCE);
+        // Grouped shapes are always picked together
+        let picked = hit.map(|index| frame.with_groups([index]));
+
+        if self.modifiers.shift {
+            if let Some(picked) = picked {
+                self.selection.toggle(&picked);
+                self.needs_redraw = true;
+                return;
+            }
+        } else if let Some(picked) = picked {
+            if !self.selection.contains(picked[0]) {
+                self.selection.set(picked);
+            }
+        } else if !self
+            .selection_bounds()
+            .is_some_and(|bounds| bounds.contains(x as f64, y as f64))
+        {
+            self.selection.clear();
+        }
+
+        self.state = if self.selection.is_empty() || (hit.is_none() && self.modifiers.shift) {
+            DrawingState::SelectingRegion {
+                start_x: x,
+                start_y: y,
+            }
+        } else {
+            DrawingState::MovingSelection {
+                last_x: x,
+                last_y: y,
+                originals: self.selected_shapes(),
+            }
+        };
+        self.needs_redraw = true;
+    }
+
+    /// Takes a committed text shape out of the active frame and edits it in text mode.
+    ///
+    /// The caret is placed at the clicked point.
+    fn reopen_text_shape(&mut self, index: usize, click_x: i32, click_y: i32) {
+        let Some((id, original)) = self.canvas_set.active_frame_mut().take_shape(index) else {
+            return;
+        };
+        let Shape::Text {
+            x,
+            y,
+            text,
+            size,
+            font_descriptor,
+            format,
+            rotation,
+            ..
+        } = &original
+        else {
+            // Not a text shape; put it back untouched
+            self.canvas_set
+                .active_frame_mut()
+                .restore_shape(index, id, original);
+            return;
+        };
+
+        let mut buffer = TextBuffer::from_text(text.clone());
+        // Find the caret in the text's own (unrotated) frame
+        let (click_x, click_y) = rotate_point(
+            click_x as f64,
+            click_y as f64,
+            *x as f64,
+            *y as f64,
+            -rotation,
+        );
+        let caret = text_index_at_point(
+            *x,
+            *y,
+            text,
+            *size,
+            font_descriptor,
+            format,
+            click_x.round() as i32,
+            click_y.round() as i32,
+        );
+        buffer.move_to(caret, false);
+
+        self.state = DrawingState::TextInput {
+            x: *x,
+            y: *y,
+            buffer,
+            editing: Some(Box::new(EditedText {
+                index,
+                id,
+                format: *format,
+                original,
+            })),
+        };
+        self.needs_redraw = true;
     }
 
     /// Processes a key press event.
@@ -298,7 +1094,18 @@ impl InputState {
     /// - Thickness adjustment (configurable)
     /// - Help toggle (configurable)
     /// - Modifier key tracking
+    #[allow(dead_code)] // The Wayland backend passes key text via on_key_press_with_text
     pub fn on_key_press(&mut self, key: Key) {
+        self.on_key_press_with_text(key, None);
+    }
+
+    /// Processes a key press along with the text it produced under the current layout.
+    ///
+    /// Behaves like [`Self::on_key_press`], except that in text mode any printable
+    /// `text` is inserted as typed when the key does not trigger an action. This lets
+    /// characters such as `+`, `é` or `€` reach the text buffer even though their key
+    /// maps to an action key or has no [`Key`] of its own.
+    pub fn on_key_press_with_text(&mut self, key: Key, text: Option<&str>) {
         // Handle modifier keys first
         match key {
             Key::Shift => {
@@ -320,11 +1127,33 @@ impl InputState {
             _ => {}
         }
 
-        // In text input mode, only check actions if modifiers are pressed or it's a special key
-        // This allows plain letters to be typed without triggering color/tool actions
-        if matches!(&self.state, DrawingState::TextInput { .. }) {
-            // Only check for actions if:
-            // 1. Modifiers are held (Ctrl, Alt, Shift for special commands)
+        if self.recovery_prompt {
+            let answer = match key {
+                Key::Return | Key::Char('y' | 'Y') => true,
+                Key::Escape | Key::Char('n' | 'N') => false,
+                _ => return,
+            };
+            self.recovery_prompt = false;
+            self.recovery_answer = Some(answer);
+            self.needs_redraw = true;
+            return;
+        }
+
+        // Hidden annotations can't be edited: keys only stop peeking or exit
+        if self.peek.is_some() {
+            if let Some(key_str) = key_binding_name(key)
+                && let Some(action @ (Action::Peek | Action::Exit)) = self.find_action(&key_str)
+            {
+                self.handle_key_action(key, action);
+            }
+            return;
+        }
+
+        // In text input mode, only check actions if modifiers are pressed or it's a special key
+        // This allows plain letters to be typed without triggering color/tool actions
+        if matches!(&self.state, DrawingState::TextInput { .. }) {
+            // Only check for actions if:
+            // 1. Modifiers are held (Ctrl, Alt, Shift for special commands)
             // 2. OR it's a special non-character key (Escape, F10, etc.)
             let should_check_actions = match key {
                 // Special keys always check for actions
@@ -335,90 +1164,133 @@ impl InputState {
                 _ => self.modifiers.ctrl || self.modifiers.alt,
             };
 
-            if should_check_actions {
-                // Convert key to string for action lookup
-                let key_str = match key {
-                    Key::Char(c) => c.to_string(),
-                    Key::Escape => "Escape".to_string(),
-                    Key::Return => "Return".to_string(),
-                    Key::Backspace => "Backspace".to_string(),
-                    Key::Space => "Space".to_string(),
-                    Key::Plus => "+".to_string(),
-                    Key::Minus => "-".to_string(),
-                    Key::Equals => "=".to_string(),
-                    Key::Underscore => "_".to_string(),
-                    Key::F10 => "F10".to_string(),
-                    Key::F11 => "F11".to_string(),
-                    _ => String::new(),
-                };
-
-                // Check if this key combination triggers an action
-                if !key_str.is_empty()
-                    && let Some(action) = self.find_action(&key_str)
-                {
-                    // Actions work in text mode
-                    // Note: Exit action has special logic in handle_action - it cancels
-                    // text mode if in TextInput state, or exits app if in Idle state
-                    self.handle_action(action);
-                    return;
-                }
+            if should_check_actions
+                && let Some(key_str) = key_binding_name(key)
+                && let Some(action) = self.find_action(&key_str)
+            {
+                // Actions work in text mode
+                // Note: Exit action has special logic in handle_action - it cancels
+                // text mode if in TextInput state, or exits app if in Idle state
+                self.handle_key_action(key, action);
+                return;
             }
 
             // No action triggered, handle as text input
             // Handle Return key for finalizing text input (only plain Return, not Shift+Return)
-            if matches!(key, Key::Return)
-                && !self.modifiers.shift
-                && let DrawingState::TextInput { x, y, buffer } = &self.state
-            {
-                if !buffer.is_empty() {
-                    let x = *x;
-                    let y = *y;
-                    let text = buffer.clone();
-
-                    self.canvas_set.active_frame_mut().add_shape(Shape::Text {
-                        x,
-                        y,
-                        text,
-                        color: self.current_color,
-                        size: self.current_font_size,
-                        font_descriptor: self.font_descriptor.clone(),
-                        background_enabled: self.text_background_enabled,
-                    });
-                    self.needs_redraw = true;
-                }
-                self.state = DrawingState::Idle;
+            if matches!(key, Key::Return) && !self.modifiers.shift {
+                self.commit_text_input();
                 return;
             }
 
-            // Regular text input - add character to buffer
+            // Regular text editing - Shift extends the selection, Ctrl works on words
             if let DrawingState::TextInput { buffer, .. } = &mut self.state {
-                match key {
-                    Key::Char(c) => {
-                        buffer.push(c);
-                        self.needs_redraw = true;
-                        return;
+                if let Some(text) = text
+                    && !self.modifiers.ctrl
+                    && !self.modifiers.alt
+                    && !text.is_empty()
+                    && !text.chars().any(char::is_control)
+                {
+                    buffer.insert_str(text);
+                    self.needs_redraw = true;
+                    return;
+                }
+
+                let extend = self.modifiers.shift;
+                let word = self.modifiers.ctrl;
+                let handled = match key {
+                    Key::Char('a' | 'A') if self.modifiers.ctrl => {
+                        buffer.select_all();
+                        true
                     }
-                    Key::Backspace => {
-                        buffer.pop();
-                        self.needs_redraw = true;
-                        return;
+                    Key::Char(c) if !self.modifiers.ctrl && !self.modifiers.alt => {
+                        buffer.insert_char(c);
+                        true
                     }
                     Key::Space => {
-                        buffer.push(' ');
-                        self.needs_redraw = true;
-                        return;
+                        buffer.insert_char(' ');
+                        true
                     }
                     Key::Return if self.modifiers.shift => {
                         // Shift+Enter: insert newline
-                        buffer.push('\n');
-                        self.needs_redraw = true;
-                        return;
+                        buffer.insert_char('\n');
+                        true
                     }
-                    _ => {
-                        // Ignore other keys in text mode
-                        return;
+                    Key::Backspace if word => buffer.delete_word_backward(),
+                    Key::Backspace => buffer.delete_backward(),
+                    Key::Delete if word => buffer.delete_word_forward(),
+                    Key::Delete => buffer.delete_forward(),
+                    Key::Left if word => {
+                        buffer.move_word_left(extend);
+                        true
                     }
+                    Key::Left => {
+                        buffer.move_left(extend);
+                        true
+                    }
+                    Key::Right if word => {
+                        buffer.move_word_right(extend);
+                        true
+                    }
+                    Key::Right => {
+                        buffer.move_right(extend);
+                        true
+                    }
+                    Key::Up => {
+                        buffer.move_up(extend);
+                        true
+                    }
+                    Key::Down => {
+                        buffer.move_down(extend);
+                        true
+                    }
+                    Key::Home if word => {
+                        buffer.move_to_start(extend);
+                        true
+                    }
+                    Key::Home => {
+                        buffer.move_line_start(extend);
+                        true
+                    }
+                    Key::End if word => {
+                        buffer.move_to_end(extend);
+                        true
+                    }
+                    Key::End => {
+                        buffer.move_line_end(extend);
+                        true
+                    }
+                    // Ignore other keys in text mode
+                    _ => false,
+                };
+                if handled {
+                    self.needs_redraw = true;
                 }
+                return;
+            }
+        }
+
+        // Arrow keys nudge the selected shapes (Shift moves further)
+        if self.selection_mode
+            && matches!(self.state, DrawingState::Idle)
+            && !self.selection.is_empty()
+            && !self.modifiers.ctrl
+            && !self.modifiers.alt
+        {
+            let step = if self.modifiers.shift {
+                SELECTION_NUDGE_LARGE
+            } else {
+                1
+            };
+            let offset = match key {
+                Key::Left => Some((-step, 0)),
+                Key::Right => Some((step, 0)),
+                Key::Up => Some((0, -step)),
+                Key::Down => Some((0, step)),
+                _ => None,
+            };
+            if let Some((dx, dy)) = offset {
+                self.nudge_selection(dx, dy);
+                return;
             }
         }
 
@@ -433,36 +1305,39 @@ impl InputState {
         }
 
         // Convert key to string for action lookup
-        let key_str = match key {
-            Key::Char(c) => c.to_string(),
-            Key::Escape => "Escape".to_string(),
-            Key::Return => "Return".to_string(),
-            Key::Backspace => "Backspace".to_string(),
-            Key::Space => "Space".to_string(),
-            Key::Plus => "+".to_string(),
-            Key::Minus => "-".to_string(),
-            Key::Equals => "=".to_string(),
-            Key::Underscore => "_".to_string(),
-            Key::F10 => "F10".to_string(),
-            Key::F11 => "F11".to_string(),
-            _ => return,
+        let Some(key_str) = key_binding_name(key) else {
+            return;
         };
 
         // Look up action based on keybinding
         if let Some(action) = self.find_action(&key_str) {
-            self.handle_action(action);
+            self.handle_key_action(key, action);
         }
     }
 
     /// Handle an action triggered by a keybinding.
     fn handle_action(&mut self, action: Action) {
         match action {
+            Action::Exit if self.peek.is_some() => {
+                // Stop peeking before anything else
+                self.peek = None;
+                self.needs_redraw = true;
+            }
             Action::Exit => {
                 // Exit drawing mode or cancel current action
                 match &self.state {
-                    DrawingState::TextInput { .. } | DrawingState::Drawing { .. } => {
+                    DrawingState::TextInput { .. }
+                    | DrawingState::Drawing { .. }
+                    | DrawingState::SelectingRegion { .. }
+                    | DrawingState::MovingSelection { .. }
+                    | DrawingState::TransformingSelection { .. } => {
                         // Cancel current action
-                        self.state = DrawingState::Idle;
+                        self.cancel_current_action();
+                    }
+                    DrawingState::Idle if self.selection_mode => {
+                        // Leave the selection tool before exiting
+                        self.selection_mode = false;
+                        self.selection.clear();
                         self.needs_redraw = true;
                     }
                     DrawingState::Idle => {
@@ -473,20 +1348,101 @@ impl InputState {
             }
             Action::EnterTextMode => {
                 if matches!(self.state, DrawingState::Idle) {
+                    // Committed text may shift shape indices
+                    self.selection.clear();
                     self.state = DrawingState::TextInput {
                         x: (self.screen_width / 2) as i32,
                         y: (self.screen_height / 2) as i32,
-                        buffer: String::new(),
+                        buffer: TextBuffer::new(),
+                        editing: None,
                     };
                     self.needs_redraw = true;
                 }
             }
             Action::ClearCanvas => {
+                // Text being edited or shapes being dragged go back first,
+                // so nothing is put back into the cleared frame afterwards
+                self.cancel_current_action();
                 self.canvas_set.clear_active();
+                self.selection.clear();
                 self.needs_redraw = true;
             }
             Action::Undo => {
-                if self.canvas_set.active_frame_mut().undo() {
+                if matches!(self.state, DrawingState::Idle) && self.canvas_set.undo() {
+                    self.selection.clear();
+                    self.needs_redraw = true;
+                }
+            }
+            Action::Redo => {
+                if matches!(self.state, DrawingState::Idle) && self.canvas_set.redo() {
+                    self.selection.clear();
+                    self.needs_redraw = true;
+                }
+            }
+            Action::StartFresh => {
+                // Drops a restored session; each board's clear can still be undone
+                self.cancel_current_action();
+                self.canvas_set.clear_all();
+                self.selection.clear();
+                self.needs_redraw = true;
+            }
+            Action::ToggleSelectionMode => {
+                if matches!(self.state, DrawingState::Idle) {
+                    self.selection_mode = !self.selection_mode;
+                    self.selection.clear();
+                    self.needs_redraw = true;
+                    log::debug!("Selection mode: {}", self.selection_mode);
+                }
+            }
+            Action::DeleteSelection => {
+                if matches!(self.state, DrawingState::Idle) {
+                    self.delete_selection();
+                }
+            }
+            Action::CopyShapes | Action::CutShapes => {
+                if matches!(self.state, DrawingState::Idle) {
+                    self.copy_shapes(action == Action::CutShapes);
+                }
+            }
+            Action::PasteShapes => {
+                if !matches!(self.state, DrawingState::Idle) {
+                    return;
+                }
+                if self.clipboard.is_empty() {
+                    self.pending_clipboard_import = true;
+                } else {
+                    self.paste_shapes(self.clipboard.shapes().to_vec());
+                }
+            }
+            Action::BringToFront | Action::SendToBack => {
+                if matches!(self.state, DrawingState::Idle) {
+                    self.restack_shapes(action == Action::BringToFront);
+                }
+            }
+            Action::GroupShapes => {
+                if matches!(self.state, DrawingState::Idle)
+                    && let Some(group) = self
+                        .canvas_set
+                        .active_frame_mut()
+                        .group(self.selection.indices())
+                {
+                    // Grouping may pull in the rest of existing groups
+                    let members = self
+                        .canvas_set
+                        .active_frame()
+                        .with_groups(self.selection.indices().iter().copied());
+                    log::debug!("Grouped {} shapes as group {group}", members.len());
+                    self.selection.set(members);
+                    self.needs_redraw = true;
+                }
+            }
+            Action::UngroupShapes => {
+                if matches!(self.state, DrawingState::Idle)
+                    && self
+                        .canvas_set
+                        .active_frame_mut()
+                        .ungroup(self.selection.indices())
+                {
                     self.needs_redraw = true;
                 }
             }
@@ -504,6 +1460,19 @@ impl InputState {
             Action::DecreaseFontSize => {
                 self.adjust_font_size(-2.0);
             }
+            Action::CycleTextAlignment => {
+                // Text reopened for editing cycles its own alignment
+                if let DrawingState::TextInput {
+                    editing: Some(editing),
+                    ..
+                } = &mut self.state
+                {
+                    editing.format.align = editing.format.align.next();
+                } else {
+                    self.text_format.align = self.text_format.align.next();
+                }
+                self.needs_redraw = true;
+            }
             Action::ToggleWhiteboard => {
                 if self.board_config.enabled {
                     log::info!("Toggling whiteboard mode");
@@ -522,6 +1491,16 @@ impl InputState {
                     self.switch_board_mode(BoardMode::Transparent);
                 }
             }
+            Action::ToggleCustomBoard(index) => {
+                if self.board_config.enabled {
+                    if let Some(board) = self.board_config.custom.get(index) {
+                        log::info!("Toggling {} board", board.name);
+                        self.switch_board_mode(BoardMode::Custom(board.name.clone()));
+                    } else {
+                        log::warn!("Custom board index {} out of range", index);
+                    }
+                }
+            }
             Action::ToggleHelp => {
                 self.show_help = !self.show_help;
                 self.needs_redraw = true;
@@ -529,37 +1508,18 @@ impl InputState {
             Action::OpenConfigurator => {
                 self.launch_configurator();
             }
-            Action::SetColorRed => {
-                self.current_color = util::key_to_color('r').unwrap();
-                self.needs_redraw = true;
-            }
-            Action::SetColorGreen => {
-                self.current_color = util::key_to_color('g').unwrap();
-                self.needs_redraw = true;
-            }
-            Action::SetColorBlue => {
-                self.current_color = util::key_to_color('b').unwrap();
-                self.needs_redraw = true;
-            }
-            Action::SetColorYellow => {
-                self.current_color = util::key_to_color('y').unwrap();
-                self.needs_redraw = true;
-            }
-            Action::SetColorOrange => {
-                self.current_color = util::key_to_color('o').unwrap();
-                self.needs_redraw = true;
+            Action::SetColor(index) => {
+                if let Some(color) = self.palette.get(index).copied() {
+                    self.select_color(color);
+                } else {
+                    log::warn!("Palette color index {} out of range", index);
+                }
             }
-            Action::SetColorPink => {
-                self.current_color = util::key_to_color('p').unwrap();
-                self.needs_redraw = true;
+            Action::NextRecentColor => {
+                self.cycle_recent_color(true);
             }
-            Action::SetColorWhite => {
-                self.current_color = util::key_to_color('w').unwrap();
-                self.needs_redraw = true;
-            }
-            Action::SetColorBlack => {
-                self.current_color = util::key_to_color('k').unwrap();
-                self.needs_redraw = true;
+            Action::PreviousRecentColor => {
+                self.cycle_recent_color(false);
             }
             Action::CaptureFullScreen
             | Action::CaptureActiveWindow
@@ -582,19 +1542,78 @@ impl InputState {
                 self.modifiers.shift = false;
                 self.modifiers.alt = false;
             }
+            Action::ExportSvg => {
+                self.pending_export = Some(ExportFormat::Svg);
+            }
+            Action::ExportPdf => {
+                self.pending_export = Some(ExportFormat::Pdf);
+            }
+            Action::SaveDocument => {
+                self.pending_document_save = Some(SaveMode::Current);
+            }
+            Action::SaveDocumentAs => {
+                self.pending_document_save = Some(SaveMode::NewFile);
+            }
+            Action::NextPage => self.change_page(CanvasSet::next_page),
+            Action::PreviousPage => self.change_page(CanvasSet::previous_page),
+            Action::NewPage => self.change_page(|canvas_set| {
+                canvas_set.new_page();
+                true
+            }),
+            Action::DeletePage => self.change_page(CanvasSet::delete_page),
+            Action::NextLayer => self.change_layer(Frame::next_layer),
+            Action::ToggleLayerVisibility => self.change_layer(|frame| {
+                frame.toggle_layer_visibility();
+            }),
+            Action::ClearLayer => self.change_layer(Frame::clear_layer),
+            Action::Peek => {
+                // Without a key to hold, peeking toggles
+                self.peek = match self.peek {
+                    None => Some(Peek::Toggled),
+                    Some(_) => None,
+                };
+                self.needs_redraw = true;
+            }
         }
     }
 
     /// Processes a key release event.
     ///
-    /// Currently only tracks modifier key releases to update the modifier state.
+    /// Tracks modifier key releases to update the modifier state, and ends
+    /// peeking when the held peek key is let go.
     pub fn on_key_release(&mut self, key: Key) {
+        self.on_key_release_at(key, Instant::now());
+    }
+
+    fn on_key_release_at(&mut self, key: Key, now: Instant) {
         match key {
             Key::Shift => self.modifiers.shift = false,
             Key::Ctrl => self.modifiers.ctrl = false,
             Key::Alt => self.modifiers.alt = false,
             Key::Tab => self.modifiers.tab = false,
-            _ => {}
+            _ => self.release_peek(key, now),
+        }
+    }
+
+    /// Inserts text committed by an input method at the caret.
+    ///
+    /// Only has an effect in text mode. Any preedit string is replaced, since
+    /// the input method commits what it was composing.
+    pub fn on_ime_commit(&mut self, text: &str) {
+        if let DrawingState::TextInput { buffer, .. } = &mut self.state {
+            buffer.clear_preedit();
+            buffer.insert_str(text);
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Updates the input method preedit string shown at the caret.
+    ///
+    /// `cursor` is a byte offset into `text`; an empty `text` clears the preedit.
+    pub fn on_ime_preedit(&mut self, text: &str, cursor: Option<usize>) {
+        if let DrawingState::TextInput { buffer, .. } = &mut self.state {
+            buffer.set_preedit(text, cursor);
+            self.needs_redraw = true;
         }
     }
 
@@ -606,14 +1625,23 @@ impl InputState {
     /// * `y` - Mouse Y coordinate
     ///
     /// # Behavior
-    /// - Left click while Idle: Starts drawing with the current tool (based on modifiers)
-    /// - Left click during TextInput: Updates text position
+    /// - Left click while Idle: Starts drawing with the current tool (based on modifiers),
+    ///   or selects and starts moving shapes when the selection tool is active
+    /// - Left click during TextInput on existing text: Commits the current text
+    ///   and reopens the clicked text for editing
+    /// - Left click during TextInput elsewhere: Updates text position
     /// - Right click: Cancels current action
     pub fn on_mouse_press(&mut self, button: MouseButton, x: i32, y: i32) {
+        self.pointer_position = Some((x, y));
+        if self.recovery_prompt || self.peek.is_some() {
+            return;
+        }
         match button {
             MouseButton::Left => {
                 // Start drawing with current tool
-                if matches!(self.state, DrawingState::Idle) {
+                if matches!(self.state, DrawingState::Idle) && self.selection_mode {
+                    self.begin_selection_drag(x, y);
+                } else if matches!(self.state, DrawingState::Idle) {
                     let tool = self.modifiers.current_tool();
                     self.state = DrawingState::Drawing {
                         tool,
@@ -622,19 +1650,24 @@ impl InputState {
                         points: vec![(x, y)],
                     };
                     self.needs_redraw = true;
-                } else if let DrawingState::TextInput { x: tx, y: ty, .. } = &mut self.state {
-                    // Update text position if in text mode
-                    *tx = x;
-                    *ty = y;
-                    self.needs_redraw = true;
+                } else if matches!(self.state, DrawingState::TextInput { .. }) {
+                    if self.canvas_set.active_frame().text_shape_at(x, y).is_some() {
+                        // Committing first may add a shape, so hit-test again afterwards
+                        self.commit_text_input();
+                        if let Some(index) = self.canvas_set.active_frame().text_shape_at(x, y) {
+                            self.reopen_text_shape(index, x, y);
+                        }
+                    } else if let DrawingState::TextInput { x: tx, y: ty, .. } = &mut self.state {
+                        // Update text position if in text mode
+                        *tx = x;
+                        *ty = y;
+                        self.needs_redraw = true;
+                    }
                 }
             }
-            MouseButton::Right => {
-                // Right-click could cancel or exit
-                if !matches!(self.state, DrawingState::Idle) {
-                    self.state = DrawingState::Idle;
-                    self.needs_redraw = true;
-                }
+            MouseButton::Right if !matches!(self.state, DrawingState::Idle) => {
+                // Right-click cancels the current action
+                self.cancel_current_action();
             }
             _ => {}
         }
@@ -649,14 +1682,32 @@ impl InputState {
     /// # Behavior
     /// - When drawing with Pen tool: Adds points to the freehand stroke
     /// - When drawing with other tools: Triggers redraw for live preview
+    /// - When moving a selection: Moves the selected shapes with the pointer
+    /// - When dragging a selection handle: Scales or rotates the selected shapes
     pub fn on_mouse_motion(&mut self, x: i32, y: i32) {
-        if let DrawingState::Drawing { tool, points, .. } = &mut self.state {
-            if *tool == Tool::Pen {
-                // Add point to freehand stroke
-                points.push((x, y));
+        self.pointer_position = Some((x, y));
+        match &mut self.state {
+            DrawingState::Drawing { tool, points, .. } => {
+                if *tool == Tool::Pen {
+                    // Add point to freehand stroke
+                    points.push((x, y));
+                }
+                // For other tools, we'll update the end point in release
+                self.needs_redraw = true;
             }
-            // For other tools, we'll update the end point in release
-            self.needs_redraw = true;
+            DrawingState::SelectingRegion { .. } => {
+                self.needs_redraw = true;
+            }
+            DrawingState::MovingSelection { last_x, last_y, .. } => {
+                let (dx, dy) = (x - *last_x, y - *last_y);
+                *last_x = x;
+                *last_y = y;
+                self.translate_selection(dx, dy);
+            }
+            DrawingState::TransformingSelection { .. } => {
+                self.update_selection_transform(x, y);
+            }
+            _ => {}
         }
     }
 
@@ -672,11 +1723,42 @@ impl InputState {
     /// - Finalizes the shape using start position and current position
     /// - Adds the completed shape to the frame
     /// - Returns to Idle state
+    ///
+    /// When released during a selection drag, selects the shapes inside the
+    /// rubber-band rectangle or finishes moving or transforming the selection.
     pub fn on_mouse_release(&mut self, button: MouseButton, x: i32, y: i32) {
         if button != MouseButton::Left {
             return;
         }
 
+        match self.state {
+            DrawingState::SelectingRegion { .. } => {
+                if let Some(region) = self.selection_region(x, y) {
+                    let indices = self.canvas_set.active_frame().shapes_within(&region);
+                    if self.modifiers.shift {
+                        self.selection.extend(indices);
+                    } else {
+                        self.selection.set(indices);
+                    }
+                }
+                self.state = DrawingState::Idle;
+                self.needs_redraw = true;
+                return;
+            }
+            DrawingState::MovingSelection { .. } | DrawingState::TransformingSelection { .. } => {
+                self.on_mouse_motion(x, y);
+                if let DrawingState::MovingSelection { originals, .. }
+                | DrawingState::TransformingSelection { originals, .. } =
+                    std::mem::replace(&mut self.state, DrawingState::Idle)
+                {
+                    self.canvas_set.active_frame_mut().record_changes(originals);
+                }
+                self.needs_redraw = true;
+                return;
+            }
+            _ => {}
+        }
+
         if let DrawingState::Drawing {
             tool,
             start_x,
@@ -717,6 +1799,7 @@ impl InputState {
                         h,
                         color: self.current_color,
                         thick: self.current_thickness,
+                        rotation: 0.0,
                     }
                 }
                 Tool::Ellipse => {
@@ -728,6 +1811,7 @@ impl InputState {
                         ry,
                         color: self.current_color,
                         thick: self.current_thickness,
+                        rotation: 0.0,
                     }
                 }
                 Tool::Arrow => Shape::Arrow {
@@ -806,6 +1890,7 @@ impl InputState {
                         h,
                         color: self.current_color,
                         thick: self.current_thickness,
+                        rotation: 0.0,
                     })
                 }
                 Tool::Ellipse => {
@@ -818,6 +1903,7 @@ impl InputState {
                         ry,
                         color: self.current_color,
                         thick: self.current_thickness,
+                        rotation: 0.0,
                     })
                 }
                 Tool::Arrow => Some(Shape::Arrow {
@@ -887,19 +1973,72 @@ impl InputState {
             false
         }
     }
+
+    /// Returns the area covered by the provisional shape, if one is being drawn.
+    ///
+    /// Like [`Self::render_provisional_shape`], this avoids cloning freehand points.
+    pub fn provisional_bounds(&self, current_x: i32, current_y: i32) -> Option<BoundingBox> {
+        match &self.state {
+            DrawingState::Drawing {
+                tool: Tool::Pen,
+                points,
+                ..
+            } => BoundingBox::from_points(points.iter().map(|&(x, y)| (x as f64, y as f64)))
+                .map(|bounds| bounds.inflate(self.current_thickness / 2.0)),
+            DrawingState::Drawing { .. } => self
+                .get_provisional_shape(current_x, current_y)?
+                .bounding_box(),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the keybinding name used in config strings for a key, if it can be bound.
+fn key_binding_name(key: Key) -> Option<String> {
+    let name = match key {
+        Key::Char(c) => return Some(c.to_string()),
+        Key::Escape => "Escape",
+        Key::Return => "Return",
+        Key::Backspace => "Backspace",
+        Key::Delete => "Delete",
+        Key::Left => "Left",
+        Key::Right => "Right",
+        Key::Up => "Up",
+        Key::Down => "Down",
+        Key::Home => "Home",
+        Key::End => "End",
+        Key::PageUp => "PageUp",
+        Key::PageDown => "PageDown",
+        Key::Space => "Space",
+        Key::Plus => "+",
+        Key::Minus => "-",
+        Key::Equals => "=",
+        Key::Underscore => "_",
+        Key::F10 => "F10",
+        Key::F11 => "F11",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Returns whether a released key is the one that was pressed.
+///
+/// Letters may come back in another case if Shift changed in between.
+fn same_key(pressed: Key, released: Key) -> bool {
+    match (pressed, released) {
+        (Key::Char(a), Key::Char(b)) => a.to_lowercase().eq(b.to_lowercase()),
+        _ => pressed == released,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{Action, BoardConfig};
+    use crate::config::{Action, BoardConfig, Config, PaletteConfig, TextAlign};
     use crate::draw::{Color, FontDescriptor};
 
     fn create_test_input_state() -> InputState {
-        use crate::config::KeybindingsConfig;
-
-        let keybindings = KeybindingsConfig::default();
-        let action_map = keybindings.build_action_map().unwrap();
+        let action_map = Config::default().build_action_map().unwrap();
 
         InputState::with_defaults(
             Color {
@@ -915,11 +2054,13 @@ mod tests {
                 weight: "bold".to_string(),
                 style: "normal".to_string(),
             },
-            false,                  // text_background_enabled
-            20.0,                   // arrow_length
-            30.0,                   // arrow_angle
-            BoardConfig::default(), // board_config
-            action_map,             // action_map
+            TextEffect::default(),    // text_effect
+            TextFormat::default(),    // text_format
+            20.0,                     // arrow_length
+            30.0,                     // arrow_angle
+            BoardConfig::default(),   // board_config
+            PaletteConfig::default(), // palette_config
+            action_map,               // action_map
         )
     }
 
@@ -1003,7 +2144,8 @@ mod tests {
         state.state = DrawingState::TextInput {
             x: 100,
             y: 100,
-            buffer: String::new(),
+            buffer: TextBuffer::new(),
+            editing: None,
         };
 
         // Type 'r' - should add to buffer, not change color
@@ -1012,7 +2154,7 @@ mod tests {
 
         // Check that 'r' was added to buffer
         if let DrawingState::TextInput { buffer, .. } = &state.state {
-            assert_eq!(buffer, "r");
+            assert_eq!(buffer.text(), "r");
         } else {
             panic!("Should still be in text input mode");
         }
@@ -1026,7 +2168,7 @@ mod tests {
         state.on_key_press(Key::Char('t'));
 
         if let DrawingState::TextInput { buffer, .. } = &state.state {
-            assert_eq!(buffer, "rgbt");
+            assert_eq!(buffer.text(), "rgbt");
         } else {
             panic!("Should still be in text input mode");
         }
@@ -1043,7 +2185,8 @@ mod tests {
         state.state = DrawingState::TextInput {
             x: 100,
             y: 100,
-            buffer: String::from("test"),
+            buffer: TextBuffer::from_text("test"),
+            editing: None,
         };
 
         // Press Ctrl (modifier)
@@ -1077,7 +2220,8 @@ mod tests {
         state.state = DrawingState::TextInput {
             x: 100,
             y: 100,
-            buffer: String::from("test"),
+            buffer: TextBuffer::from_text("test"),
+            editing: None,
         };
 
         // Press Escape (should cancel text input)
@@ -1096,7 +2240,8 @@ mod tests {
         state.state = DrawingState::TextInput {
             x: 100,
             y: 100,
-            buffer: String::new(),
+            buffer: TextBuffer::new(),
+            editing: None,
         };
 
         assert!(!state.show_help);
@@ -1154,10 +2299,10 @@ mod tests {
     fn board_mode_toggle_restores_previous_color() {
         let mut state = create_test_input_state();
         let initial_color = state.current_color;
-        assert_eq!(state.board_mode(), BoardMode::Transparent);
+        assert_eq!(*state.board_mode(), BoardMode::Transparent);
 
         state.switch_board_mode(BoardMode::Whiteboard);
-        assert_eq!(state.board_mode(), BoardMode::Whiteboard);
+        assert_eq!(*state.board_mode(), BoardMode::Whiteboard);
         assert_eq!(state.board_previous_color, Some(initial_color));
         let expected_pen = BoardMode::Whiteboard
             .default_pen_color(&state.board_config)
@@ -1165,11 +2310,43 @@ mod tests {
         assert_eq!(state.current_color, expected_pen);
 
         state.switch_board_mode(BoardMode::Whiteboard);
-        assert_eq!(state.board_mode(), BoardMode::Transparent);
+        assert_eq!(*state.board_mode(), BoardMode::Transparent);
         assert_eq!(state.current_color, initial_color);
         assert!(state.board_previous_color.is_none());
     }
 
+    #[test]
+    fn custom_board_action_toggles_the_configured_board() {
+        let mut state = create_test_input_state();
+        let initial_color = state.current_color;
+        state
+            .board_config
+            .custom
+            .push(crate::config::CustomBoardConfig {
+                name: "greenboard".to_string(),
+                background_color: [0.1, 0.3, 0.2],
+                background_alpha: 1.0,
+                pen_color: Some([1.0, 1.0, 1.0]),
+                keys: vec!["Ctrl+Shift+G".to_string()],
+            });
+
+        state.handle_action(Action::ToggleCustomBoard(0));
+        assert_eq!(
+            *state.board_mode(),
+            BoardMode::Custom("greenboard".to_string())
+        );
+        assert_eq!(state.current_color, crate::draw::color::WHITE);
+
+        state.handle_action(Action::ToggleBlackboard);
+        state.handle_action(Action::ToggleCustomBoard(0));
+        state.handle_action(Action::ToggleCustomBoard(0));
+        assert_eq!(*state.board_mode(), BoardMode::Transparent);
+        assert_eq!(state.current_color, initial_color);
+
+        state.handle_action(Action::ToggleCustomBoard(3));
+        assert_eq!(*state.board_mode(), BoardMode::Transparent);
+    }
+
     #[test]
     fn mouse_drag_creates_shapes_for_each_tool() {
         let mut state = create_test_input_state();
@@ -1208,4 +2385,851 @@ mod tests {
         state.on_mouse_release(MouseButton::Left, 6, 6);
         assert_eq!(state.canvas_set.active_frame().shapes.len(), 5);
     }
+
+    #[test]
+    fn selecting_colors_builds_recent_history() {
+        let mut state = create_test_input_state();
+        assert_eq!(state.recent_colors, vec![state.current_color]);
+
+        state.on_key_press(Key::Char('g'));
+        state.on_key_press(Key::Char('b'));
+        state.on_key_press(Key::Char('g'));
+
+        let green = util::key_to_color('g').unwrap();
+        let blue = util::key_to_color('b').unwrap();
+        let red = util::key_to_color('r').unwrap();
+        assert_eq!(state.recent_colors, vec![green, blue, red]);
+    }
+
+    #[test]
+    fn recent_colors_respect_limit() {
+        let mut state = create_test_input_state();
+        state.recent_colors_limit = 2;
+
+        state.on_key_press(Key::Char('g'));
+        state.on_key_press(Key::Char('b'));
+        state.on_key_press(Key::Char('y'));
+
+        assert_eq!(state.recent_colors.len(), 2);
+        assert_eq!(state.recent_colors[0], util::key_to_color('y').unwrap());
+    }
+
+    #[test]
+    fn cycle_recent_colors_wraps_without_reordering() {
+        let mut state = create_test_input_state();
+        state.on_key_press(Key::Char('g'));
+        state.on_key_press(Key::Char('b'));
+        let history = state.recent_colors.clone();
+
+        state.handle_action(Action::NextRecentColor);
+        assert_eq!(state.current_color, history[1]);
+        state.handle_action(Action::NextRecentColor);
+        assert_eq!(state.current_color, history[2]);
+        state.handle_action(Action::NextRecentColor);
+        assert_eq!(state.current_color, history[0]);
+        state.handle_action(Action::PreviousRecentColor);
+        assert_eq!(state.current_color, history[2]);
+
+        assert_eq!(state.recent_colors, history);
+    }
+
+    #[test]
+    fn set_color_out_of_range_is_ignored() {
+        let mut state = create_test_input_state();
+        let original = state.current_color;
+
+        state.handle_action(Action::SetColor(99));
+
+        assert_eq!(state.current_color, original);
+    }
+
+    #[test]
+    fn text_mode_editing_keys_move_caret_and_edit() {
+        let mut state = create_test_input_state();
+        state.state = DrawingState::TextInput {
+            x: 100,
+            y: 100,
+            buffer: TextBuffer::from_text("hello world"),
+            editing: None,
+        };
+
+        // Ctrl+Backspace removes the last word
+        state.on_key_press(Key::Ctrl);
+        state.on_key_press(Key::Backspace);
+        state.on_key_release(Key::Ctrl);
+
+        // Home, then type at the start of the line
+        state.on_key_press(Key::Home);
+        state.on_key_press(Key::Char('>'));
+
+        // Shift+End selects the rest, typing replaces it
+        state.on_key_press(Key::Shift);
+        state.on_key_press(Key::End);
+        state.on_key_release(Key::Shift);
+        state.on_key_press(Key::Char('x'));
+
+        if let DrawingState::TextInput { buffer, .. } = &state.state {
+            assert_eq!(buffer.text(), ">x");
+            assert_eq!(buffer.display_caret(), 2);
+        } else {
+            panic!("Should still be in text input mode");
+        }
+    }
+
+    #[test]
+    fn text_mode_unbound_ctrl_letters_are_not_inserted() {
+        let mut state = create_test_input_state();
+        state.state = DrawingState::TextInput {
+            x: 100,
+            y: 100,
+            buffer: TextBuffer::from_text("abc"),
+            editing: None,
+        };
+
+        state.on_key_press(Key::Ctrl);
+        state.on_key_press(Key::Char('J'));
+        state.on_key_press(Key::Char('A'));
+        state.on_key_release(Key::Ctrl);
+        state.on_key_press(Key::Delete);
+
+        if let DrawingState::TextInput { buffer, .. } = &state.state {
+            assert!(
+                buffer.is_empty(),
+                "Ctrl+A then Delete should clear the text"
+            );
+        } else {
+            panic!("Should still be in text input mode");
+        }
+    }
+
+    fn add_text_shape(state: &mut InputState, text: &str, color: Color) {
+        state.canvas_set.active_frame_mut().add_shape(Shape::Text {
+            x: 100,
+            y: 100,
+            text: text.to_string(),
+            color,
+            size: 24.0,
+            font_descriptor: FontDescriptor::default(),
+            effect: TextEffect::None,
+            format: TextFormat::default(),
+            rotation: 0.0,
+        });
+    }
+
+    #[test]
+    fn clicking_text_in_text_mode_reopens_it_with_its_style() {
+        let mut state = create_test_input_state();
+        add_text_shape(&mut state, "hello", util::key_to_color('g').unwrap());
+        state.canvas_set.active_frame_mut().add_shape(Shape::Line {
+            x1: 0,
+            y1: 0,
+            x2: 10,
+            y2: 10,
+            color: util::key_to_color('r').unwrap(),
+            thick: 2.0,
+        });
+
+        state.handle_action(Action::EnterTextMode);
+        state.current_font_size = 48.0;
+        state.on_mouse_press(MouseButton::Left, 102, 92);
+
+        let DrawingState::TextInput {
+            buffer, editing, ..
+        } = &state.state
+        else {
+            panic!("Should be editing text");
+        };
+        assert_eq!(buffer.text(), "hello");
+        assert_eq!(
+            buffer.display_caret(),
+            0,
+            "caret lands where the text was clicked"
+        );
+        assert_eq!(editing.as_ref().map(|e| e.index), Some(0));
+        assert_eq!(state.canvas_set.active_frame().shapes.len(), 1);
+
+        state.on_key_press(Key::End);
+        state.on_key_press(Key::Char('!'));
+        state.on_key_press(Key::Return);
+
+        let shapes = &state.canvas_set.active_frame().shapes;
+        assert_eq!(shapes.len(), 2);
+        match &shapes[0] {
+            Shape::Text {
+                text, color, size, ..
+            } => {
+                assert_eq!(text, "hello!");
+                assert_eq!(*color, util::key_to_color('g').unwrap());
+                assert_eq!(*size, 24.0, "reopened text keeps its font size");
+            }
+            other => panic!("Expected edited text to keep its z-order, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cancelling_reopened_text_restores_original() {
+        let mut state = create_test_input_state();
+        add_text_shape(&mut state, "hello", util::key_to_color('r').unwrap());
+
+        state.handle_action(Action::EnterTextMode);
+        state.on_mouse_press(MouseButton::Left, 110, 92);
+        state.on_key_press(Key::Backspace);
+        state.on_key_press(Key::Escape);
+
+        assert!(matches!(state.state, DrawingState::Idle));
+        match state.canvas_set.active_frame().shapes.as_slice() {
+            [Shape::Text { text, .. }] => assert_eq!(text, "hello"),
+            other => panic!("Expected original text back, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn clearing_while_editing_reopened_text_leaves_the_canvas_empty() {
+        let mut state = create_test_input_state();
+        add_text_shape(&mut state, "hello", util::key_to_color('r').unwrap());
+
+        state.handle_action(Action::EnterTextMode);
+        state.on_mouse_press(MouseButton::Left, 110, 92);
+        state.handle_action(Action::ClearCanvas);
+        assert!(matches!(state.state, DrawingState::Idle));
+        assert!(state.canvas_set.active_frame().shapes.is_empty());
+
+        state.handle_action(Action::Undo);
+        match state.canvas_set.active_frame().shapes.as_slice() {
+            [Shape::Text { text, .. }] => assert_eq!(text, "hello"),
+            other => panic!("Expected original text back, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn clicking_empty_space_in_text_mode_moves_text_position() {
+        let mut state = create_test_input_state();
+        add_text_shape(&mut state, "hello", util::key_to_color('r').unwrap());
+
+        state.handle_action(Action::EnterTextMode);
+        state.on_mouse_press(MouseButton::Left, 600, 400);
+
+        match &state.state {
+            DrawingState::TextInput {
+                x: 600,
+                y: 400,
+                editing: None,
+                ..
+            } => {}
+            other => panic!("Expected repositioned text input, got {:?}", other),
+        }
+        assert_eq!(state.canvas_set.active_frame().shapes.len(), 1);
+    }
+
+    #[test]
+    fn cycle_text_alignment_applies_to_new_or_reopened_text() {
+        let mut state = create_test_input_state();
+        add_text_shape(&mut state, "hello", util::key_to_color('r').unwrap());
+
+        state.handle_action(Action::CycleTextAlignment);
+        assert_eq!(state.text_format.align, TextAlign::Center);
+
+        state.handle_action(Action::EnterTextMode);
+        state.on_mouse_press(MouseButton::Left, 110, 92);
+        state.handle_action(Action::CycleTextAlignment);
+        assert_eq!(
+            state.text_format.align,
+            TextAlign::Center,
+            "editing existing text leaves the default alone"
+        );
+
+        state.on_key_press(Key::Return);
+        match &state.canvas_set.active_frame().shapes[0] {
+            Shape::Text { format, .. } => assert_eq!(format.align, TextAlign::Center),
+            other => panic!("Expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ime_commit_and_preedit_only_apply_in_text_mode() {
+        let mut state = create_test_input_state();
+        state.on_ime_commit("無視");
+        assert!(matches!(state.state, DrawingState::Idle));
+
+        state.handle_action(Action::EnterTextMode);
+        state.on_ime_preedit("かん", None);
+        state.on_ime_commit("漢");
+        state.on_ime_commit("字😀");
+
+        let DrawingState::TextInput { buffer, .. } = &state.state else {
+            panic!("Should still be in text input mode");
+        };
+        assert_eq!(buffer.display_text(), "漢字😀");
+        assert!(buffer.preedit_range().is_none());
+
+        state.on_key_press(Key::Return);
+        match state.canvas_set.active_frame().shapes.as_slice() {
+            [Shape::Text { text, .. }] => assert_eq!(text, "漢字😀"),
+            other => panic!("Expected committed text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn typed_text_is_inserted_in_text_mode_instead_of_triggering_actions() {
+        let mut state = create_test_input_state();
+        let thickness = state.current_thickness;
+
+        // Outside text mode, action keys keep their bindings
+        state.on_key_press_with_text(Key::Plus, Some("="));
+        assert!(state.current_thickness > thickness);
+        let thickness = state.current_thickness;
+
+        state.handle_action(Action::EnterTextMode);
+        state.on_key_press_with_text(Key::Plus, Some("="));
+        state.on_key_press_with_text(Key::Minus, Some("_"));
+        state.on_key_press_with_text(Key::Char('é'), Some("é"));
+        state.on_key_press_with_text(Key::Unknown, Some("€"));
+        // Control characters fall back to key handling
+        state.on_key_press_with_text(Key::Backspace, Some("\u{8}"));
+        // Dead keys produce no text while composing
+        state.on_key_press_with_text(Key::Unknown, None);
+        state.on_key_press_with_text(Key::Char('ß'), Some("ß"));
+
+        assert_eq!(state.current_thickness, thickness);
+        let DrawingState::TextInput { buffer, .. } = &state.state else {
+            panic!("Should still be in text input mode");
+        };
+        assert_eq!(buffer.text(), "=_éß");
+    }
+
+    fn add_rect(state: &mut InputState, x: i32, y: i32) {
+        state.canvas_set.active_frame_mut().add_shape(Shape::Rect {
+            x,
+            y,
+            w: 50,
+            h: 50,
+            color: util::key_to_color('r').unwrap(),
+            thick: 2.0,
+            rotation: 0.0,
+        });
+    }
+
+    fn rect_origin(state: &InputState, index: usize) -> (i32, i32) {
+        match &state.canvas_set.active_frame().shapes[index] {
+            Shape::Rect { x, y, .. } => (*x, *y),
+            other => panic!("Expected a rectangle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn selection_tool_clicks_and_drags_shapes() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        add_rect(&mut state, 200, 200);
+        state.handle_action(Action::ToggleSelectionMode);
+
+        // Clicking a shape's edge selects it and dragging moves it
+        state.on_mouse_press(MouseButton::Left, 50, 25);
+        assert_eq!(state.selection.indices(), &[0]);
+        state.on_mouse_motion(60, 35);
+        state.on_mouse_release(MouseButton::Left, 70, 45);
+        assert!(matches!(state.state, DrawingState::Idle));
+        assert_eq!(rect_origin(&state, 0), (20, 20));
+        assert_eq!(rect_origin(&state, 1), (200, 200));
+        assert!(
+            state.canvas_set.active_frame().shapes.len() == 2,
+            "nothing drawn"
+        );
+
+        // Arrow keys nudge the selection
+        state.on_key_press(Key::Left);
+        state.on_key_press(Key::Shift);
+        state.on_key_press(Key::Down);
+        state.on_key_release(Key::Shift);
+        assert_eq!(rect_origin(&state, 0), (19, 30));
+
+        // Clicking empty space outside the selection clears it
+        state.on_mouse_press(MouseButton::Left, 150, 150);
+        state.on_mouse_release(MouseButton::Left, 150, 150);
+        assert!(state.selection.is_empty());
+    }
+
+    #[test]
+    fn rubber_band_selects_enclosed_shapes_and_delete_removes_them() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        add_rect(&mut state, 100, 100);
+        add_rect(&mut state, 300, 300);
+        state.handle_action(Action::ToggleSelectionMode);
+
+        state.on_mouse_press(MouseButton::Left, -10, -10);
+        assert!(state.selection_region(200, 200).is_some());
+        state.on_mouse_release(MouseButton::Left, 200, 200);
+        assert_eq!(state.selection.indices(), &[0, 1]);
+
+        state.handle_action(Action::DeleteSelection);
+        assert!(state.selection.is_empty());
+        assert_eq!(state.canvas_set.active_frame().shapes.len(), 1);
+        assert_eq!(rect_origin(&state, 0), (300, 300));
+    }
+
+    #[test]
+    fn cancelling_a_selection_drag_restores_positions() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        state.handle_action(Action::ToggleSelectionMode);
+
+        state.on_mouse_press(MouseButton::Left, 0, 25);
+        state.on_mouse_motion(40, 65);
+        assert_eq!(rect_origin(&state, 0), (40, 40));
+        state.on_mouse_press(MouseButton::Right, 40, 65);
+        assert_eq!(rect_origin(&state, 0), (0, 0));
+        assert!(matches!(state.state, DrawingState::Idle));
+
+        // Clearing mid-drag ends the drag instead of moving shapes afterwards
+        state.on_mouse_press(MouseButton::Left, 0, 25);
+        state.on_mouse_motion(40, 65);
+        state.handle_action(Action::ClearCanvas);
+        state.on_mouse_motion(80, 65);
+        state.on_mouse_release(MouseButton::Left, 80, 65);
+        assert!(state.canvas_set.active_frame().shapes.is_empty());
+        add_rect(&mut state, 0, 0);
+
+        // Escape leaves the selection tool before it exits the app
+        state.on_key_press(Key::Escape);
+        assert!(!state.selection_mode);
+        assert!(!state.should_exit);
+    }
+
+    #[test]
+    fn selection_is_per_frame() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        state.handle_action(Action::ToggleSelectionMode);
+        state.on_mouse_press(MouseButton::Left, 0, 25);
+        state.on_mouse_release(MouseButton::Left, 0, 25);
+        assert!(!state.selection.is_empty());
+
+        state.switch_board_mode(BoardMode::Whiteboard);
+        assert!(state.selection.is_empty());
+        assert!(state.selection_mode, "the tool stays active across boards");
+
+        add_rect(&mut state, 10, 10);
+        state.on_mouse_press(MouseButton::Left, 10, 35);
+        state.on_mouse_release(MouseButton::Left, 10, 35);
+        state.handle_action(Action::DeleteSelection);
+        assert!(state.canvas_set.active_frame().shapes.is_empty());
+
+        state.switch_board_mode(BoardMode::Transparent);
+        assert_eq!(state.canvas_set.active_frame().shapes.len(), 1);
+    }
+
+    #[test]
+    fn copied_shape_under_pointer_pastes_onto_another_board() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+
+        // Nothing under the pointer: nothing to copy
+        state.on_mouse_motion(400, 400);
+        state.handle_action(Action::CopyShapes);
+        assert!(state.clipboard.is_empty());
+        assert!(state.take_pending_clipboard_export().is_none());
+
+        state.on_mouse_motion(50, 25);
+        state.handle_action(Action::CopyShapes);
+        assert_eq!(state.clipboard.shapes().len(), 1);
+        assert_eq!(
+            state
+                .take_pending_clipboard_export()
+                .map(|shapes| shapes.len()),
+            Some(1)
+        );
+        assert_eq!(state.canvas_set.active_frame().shapes.len(), 1);
+
+        state.switch_board_mode(BoardMode::Whiteboard);
+        state.on_mouse_motion(300, 200);
+        state.handle_action(Action::PasteShapes);
+        assert!(!state.take_pending_clipboard_import());
+        let pasted = state.canvas_set.active_frame().shapes[0]
+            .bounding_box()
+            .unwrap();
+        let (center_x, center_y) = pasted.center();
+        assert!((center_x - 300.0).abs() <= 1.0 && (center_y - 200.0).abs() <= 1.0);
+
+        // The original stays on its own board
+        state.switch_board_mode(BoardMode::Transparent);
+        assert_eq!(rect_origin(&state, 0), (0, 0));
+    }
+
+    #[test]
+    fn cutting_the_selection_removes_it_and_paste_selects_the_copies() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        add_rect(&mut state, 100, 0);
+        add_rect(&mut state, 300, 300);
+        state.handle_action(Action::ToggleSelectionMode);
+        state.on_mouse_press(MouseButton::Left, -10, -10);
+        state.on_mouse_release(MouseButton::Left, 200, 100);
+        assert_eq!(state.selection.indices(), &[0, 1]);
+
+        state.handle_action(Action::CutShapes);
+        assert!(state.selection.is_empty());
+        assert_eq!(state.canvas_set.active_frame().shapes.len(), 1);
+        assert_eq!(state.clipboard.shapes().len(), 2);
+
+        state.handle_action(Action::PasteShapes);
+        assert_eq!(state.canvas_set.active_frame().shapes.len(), 3);
+        assert_eq!(state.selection.indices(), &[1, 2]);
+        // The pasted shapes keep their layout
+        let (x1, y1) = rect_origin(&state, 1);
+        let (x2, y2) = rect_origin(&state, 2);
+        assert_eq!((x2 - x1, y2 - y1), (100, 0));
+    }
+
+    #[test]
+    fn paste_with_empty_clipboard_asks_the_backend() {
+        let mut state = create_test_input_state();
+        state.handle_action(Action::PasteShapes);
+        assert!(state.take_pending_clipboard_import());
+        assert!(!state.take_pending_clipboard_import());
+
+        // Shapes from the system clipboard are pasted like copied ones
+        let mut other = create_test_input_state();
+        add_rect(&mut other, 10, 10);
+        state.paste_shapes(other.canvas_set.active_frame().shapes.clone());
+        assert_eq!(rect_origin(&state, 0), (10, 10));
+    }
+
+    #[test]
+    fn grouped_shapes_select_move_and_delete_together() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        add_rect(&mut state, 100, 0);
+        add_rect(&mut state, 300, 300);
+        state.handle_action(Action::ToggleSelectionMode);
+        state.on_mouse_press(MouseButton::Left, -10, -10);
+        state.on_mouse_release(MouseButton::Left, 200, 100);
+        state.handle_action(Action::GroupShapes);
+
+        // Clicking one member selects the whole group and drags it
+        state.on_mouse_press(MouseButton::Left, 150, 150);
+        state.on_mouse_release(MouseButton::Left, 150, 150);
+        assert!(state.selection.is_empty());
+        state.on_mouse_press(MouseButton::Left, 150, 25);
+        assert_eq!(state.selection.indices(), &[0, 1]);
+        state.on_mouse_release(MouseButton::Left, 160, 35);
+        assert_eq!(rect_origin(&state, 0), (10, 10));
+        assert_eq!(rect_origin(&state, 1), (110, 10));
+
+        // A rubber band must enclose the whole group
+        state.on_mouse_press(MouseButton::Left, -10, -10);
+        state.on_mouse_release(MouseButton::Left, 100, 100);
+        assert!(state.selection.is_empty());
+
+        // Bringing the group forward keeps it selected, and it is deleted as one unit
+        state.on_mouse_press(MouseButton::Left, 10, 35);
+        state.on_mouse_release(MouseButton::Left, 10, 35);
+        state.handle_action(Action::BringToFront);
+        assert_eq!(state.selection.indices(), &[1, 2]);
+        state.handle_action(Action::DeleteSelection);
+        assert_eq!(state.canvas_set.active_frame().shapes.len(), 1);
+        assert_eq!(rect_origin(&state, 0), (300, 300));
+    }
+
+    #[test]
+    fn clear_move_and_delete_can_be_undone_and_redone() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        add_rect(&mut state, 100, 0);
+
+        // An accidental clear is recoverable
+        state.handle_action(Action::ClearCanvas);
+        assert!(state.canvas_set.active_frame().shapes.is_empty());
+        state.handle_action(Action::Undo);
+        assert_eq!(rect_origin(&state, 1), (100, 0));
+        state.handle_action(Action::Redo);
+        assert!(state.canvas_set.active_frame().shapes.is_empty());
+        state.handle_action(Action::Undo);
+
+        // A drag is one undo step, however many motion events it took
+        state.handle_action(Action::ToggleSelectionMode);
+        state.on_mouse_press(MouseButton::Left, 0, 25);
+        state.on_mouse_motion(20, 25);
+        state.on_mouse_motion(50, 45);
+        state.on_mouse_release(MouseButton::Left, 50, 45);
+        assert_eq!(rect_origin(&state, 0), (50, 20));
+        state.handle_action(Action::Undo);
+        assert_eq!(rect_origin(&state, 0), (0, 0));
+        assert!(state.selection.is_empty());
+        state.handle_action(Action::Redo);
+        assert_eq!(rect_origin(&state, 0), (50, 20));
+
+        // Deleting the selection comes back at the same place in draw order
+        state.on_mouse_press(MouseButton::Left, 50, 45);
+        state.on_mouse_release(MouseButton::Left, 50, 45);
+        state.handle_action(Action::DeleteSelection);
+        assert_eq!(rect_origin(&state, 0), (100, 0));
+        state.handle_action(Action::Undo);
+        assert_eq!(rect_origin(&state, 0), (50, 20));
+        assert_eq!(rect_origin(&state, 1), (100, 0));
+
+        // A cancelled drag is not recorded, so undo reverts the earlier one
+        state.on_mouse_press(MouseButton::Left, 50, 45);
+        state.on_mouse_motion(80, 45);
+        state.on_key_press(Key::Escape);
+        assert_eq!(rect_origin(&state, 0), (50, 20));
+        state.handle_action(Action::Undo);
+        assert_eq!(rect_origin(&state, 0), (0, 0));
+    }
+
+    #[test]
+    fn start_fresh_clears_every_board() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        state.switch_board_mode(BoardMode::Whiteboard);
+        add_rect(&mut state, 100, 0);
+
+        state.handle_action(Action::StartFresh);
+        assert!(state.canvas_set.active_frame().shapes.is_empty());
+        state.switch_board_mode(BoardMode::Transparent);
+        assert!(state.canvas_set.active_frame().shapes.is_empty());
+
+        // Each board keeps its own undo step
+        state.handle_action(Action::Undo);
+        assert_eq!(rect_origin(&state, 0), (0, 0));
+    }
+
+    #[test]
+    fn page_actions_navigate_the_active_board() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        state.handle_action(Action::NewPage);
+        add_rect(&mut state, 100, 0);
+        assert_eq!(state.canvas_set.page_position(), (1, 2));
+
+        state.handle_action(Action::PreviousPage);
+        assert_eq!(rect_origin(&state, 0), (0, 0));
+        state.handle_action(Action::PreviousPage);
+        assert_eq!(state.canvas_set.page_position(), (0, 2));
+
+        state.handle_action(Action::NextPage);
+        assert_eq!(rect_origin(&state, 0), (100, 0));
+        state.handle_action(Action::DeletePage);
+        assert_eq!(state.canvas_set.page_position(), (0, 1));
+        assert_eq!(rect_origin(&state, 0), (0, 0));
+
+        // Undo puts the deleted page and its shapes back
+        state.handle_action(Action::Undo);
+        assert_eq!(state.canvas_set.page_position(), (1, 2));
+        assert_eq!(rect_origin(&state, 0), (100, 0));
+    }
+
+    #[test]
+    fn layer_actions_change_only_the_active_layer() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        state.handle_action(Action::NextLayer);
+        add_rect(&mut state, 100, 0);
+        add_rect(&mut state, 200, 0);
+        assert_eq!(state.canvas_set.active_frame().active_layer(), 1);
+
+        state.handle_action(Action::ToggleLayerVisibility);
+        assert!(!state.canvas_set.active_frame().layers()[1].visible);
+        state.handle_action(Action::ToggleLayerVisibility);
+
+        state.handle_action(Action::ClearLayer);
+        assert_eq!(state.canvas_set.active_frame().shapes.len(), 1);
+        assert_eq!(rect_origin(&state, 0), (0, 0));
+        state.handle_action(Action::Undo);
+        assert_eq!(rect_origin(&state, 2), (200, 0));
+    }
+
+    #[test]
+    fn peek_hides_annotations_while_held_or_toggled() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        let start = Instant::now();
+
+        // Holding the key peeks until it is released; repeats change nothing
+        state.on_key_press(Key::Space);
+        assert!(state.is_peeking());
+        state.on_key_press(Key::Space);
+        let later = Instant::now() + Duration::from_secs(1);
+        state.on_key_release_at(Key::Char('x'), later);
+        assert!(state.is_peeking(), "only the peek key ends peeking");
+        state.on_key_release_at(Key::Space, later);
+        assert!(!state.is_peeking());
+
+        // A quick tap keeps peeking until the next press
+        state.press_peek(Key::Space, start);
+        state.on_key_release_at(Key::Space, start + Duration::from_millis(100));
+        assert!(state.is_peeking());
+        state.on_mouse_press(MouseButton::Left, 50, 50);
+        assert!(
+            matches!(state.state, DrawingState::Idle),
+            "clicks are ignored"
+        );
+        state.press_peek(Key::Space, start + Duration::from_secs(2));
+        assert!(!state.is_peeking());
+        assert_eq!(rect_origin(&state, 0), (0, 0), "no shapes are lost");
+
+        // Escape stops peeking instead of exiting
+        state.handle_action(Action::Peek);
+        state.handle_action(Action::Exit);
+        assert!(!state.is_peeking());
+        assert!(!state.should_exit);
+    }
+
+    #[test]
+    fn keys_other_than_peek_and_exit_are_ignored_while_peeking() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        state.handle_action(Action::Peek);
+
+        state.on_key_press(Key::Char('e'));
+        assert!(state.is_peeking());
+        assert_eq!(
+            state.canvas_set.active_frame().shapes.len(),
+            1,
+            "clearing is ignored"
+        );
+
+        state.on_key_press(Key::Escape);
+        assert!(!state.is_peeking());
+        state.on_key_press(Key::Char('e'));
+        assert!(state.canvas_set.active_frame().shapes.is_empty());
+    }
+
+    #[test]
+    fn click_through_drops_held_input_but_keeps_text() {
+        let mut state = create_test_input_state();
+        state.on_mouse_press(MouseButton::Left, 10, 10);
+        state.on_mouse_motion(40, 40);
+        state.on_key_press(Key::Space);
+        state.modifiers.shift = true;
+        assert!(matches!(state.state, DrawingState::Drawing { .. }));
+        assert!(state.is_peeking());
+
+        state.set_click_through(true);
+        assert!(state.is_click_through());
+        assert!(!state.modifiers.shift);
+        assert!(!state.is_peeking());
+        assert!(matches!(state.state, DrawingState::Idle));
+        assert!(state.canvas_set.active_frame().shapes.is_empty());
+
+        state.set_click_through(false);
+        state.state = DrawingState::TextInput {
+            x: 10,
+            y: 10,
+            buffer: TextBuffer::from_text("draft"),
+            editing: None,
+        };
+        state.set_click_through(true);
+        assert!(matches!(state.state, DrawingState::TextInput { .. }));
+    }
+
+    #[test]
+    fn recovery_prompt_captures_input_until_answered() {
+        let mut state = create_test_input_state();
+        state.show_recovery_prompt();
+
+        state.on_key_press(Key::Char('e'));
+        state.on_mouse_press(MouseButton::Left, 10, 10);
+        assert!(matches!(state.state, DrawingState::Idle));
+        assert!(state.recovery_prompt_visible());
+        assert_eq!(state.take_recovery_answer(), None);
+
+        state.on_key_press(Key::Escape);
+        assert!(!state.should_exit, "Escape answers the prompt");
+        assert!(!state.recovery_prompt_visible());
+        assert_eq!(state.take_recovery_answer(), Some(false));
+
+        state.show_recovery_prompt();
+        state.on_key_press(Key::Return);
+        assert_eq!(state.take_recovery_answer(), Some(true));
+    }
+
+    #[test]
+    fn editing_text_is_a_single_undo_step() {
+        let mut state = create_test_input_state();
+        add_text_shape(&mut state, "hello", util::key_to_color('r').unwrap());
+
+        state.handle_action(Action::EnterTextMode);
+        state.on_mouse_press(MouseButton::Left, 110, 92);
+        state.on_key_press(Key::Char('!'));
+        state.on_key_press(Key::Return);
+
+        state.handle_action(Action::Undo);
+        match state.canvas_set.active_frame().shapes.as_slice() {
+            [Shape::Text { text, .. }] => assert_eq!(text, "hello"),
+            other => panic!("Expected original text back, got {:?}", other),
+        }
+        state.handle_action(Action::Redo);
+        match state.canvas_set.active_frame().shapes.as_slice() {
+            [Shape::Text { text, .. }] => assert_eq!(text, "!hello"),
+            other => panic!("Expected edited text again, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn arrange_actions_work_on_the_shape_under_the_pointer() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        add_rect(&mut state, 100, 0);
+        add_rect(&mut state, 200, 0);
+
+        state.on_mouse_motion(200, 25);
+        state.handle_action(Action::SendToBack);
+        assert_eq!(rect_origin(&state, 0), (200, 0));
+        state.on_mouse_motion(0, 25);
+        state.handle_action(Action::BringToFront);
+        assert_eq!(rect_origin(&state, 2), (0, 0));
+
+        // Group and ungroup need a selection
+        state.handle_action(Action::GroupShapes);
+        assert!(state.canvas_set.active_frame().group_of(0).is_none());
+        state.handle_action(Action::ToggleSelectionMode);
+        state.on_mouse_press(MouseButton::Left, -10, -10);
+        state.on_mouse_release(MouseButton::Left, 400, 100);
+        state.handle_action(Action::GroupShapes);
+        assert!(state.canvas_set.active_frame().group_of(2).is_some());
+        state.handle_action(Action::UngroupShapes);
+        assert!(state.canvas_set.active_frame().group_of(2).is_none());
+    }
+
+    #[test]
+    fn dragging_selection_handles_scales_and_rotates() {
+        let mut state = create_test_input_state();
+        add_rect(&mut state, 0, 0);
+        state.handle_action(Action::ToggleSelectionMode);
+        state.on_mouse_press(MouseButton::Left, 0, 25);
+        state.on_mouse_release(MouseButton::Left, 0, 25);
+
+        // Drag the bottom-right handle out to double the size
+        let bounds = state.selection_bounds().unwrap();
+        let (hx, hy) = SelectionHandle::BottomRight.position(&bounds);
+        let (ax, ay) = SelectionHandle::BottomRight.anchor(&bounds);
+        let target = (ax + (hx - ax) * 2.0, ay + (hy - ay) * 2.0);
+        state.on_mouse_press(MouseButton::Left, hx as i32, hy as i32);
+        assert!(matches!(
+            state.state,
+            DrawingState::TransformingSelection { .. }
+        ));
+        state.on_mouse_release(MouseButton::Left, target.0 as i32, target.1 as i32);
+        let scaled = state.canvas_set.active_frame().shapes[0]
+            .bounding_box()
+            .unwrap();
+        assert!((scaled.width - bounds.width * 2.0).abs() <= 16.0);
+
+        // Rotating and then cancelling leaves the shape as it was
+        let bounds = state.selection_bounds().unwrap();
+        let (rx, ry) = SelectionHandle::Rotate.position(&bounds);
+        state.on_mouse_press(MouseButton::Left, rx as i32, ry as i32);
+        state.on_mouse_motion(bounds.right() as i32 + 100, bounds.center().1 as i32);
+        assert!(matches!(
+            &state.canvas_set.active_frame().shapes[0],
+            Shape::Rect { rotation, .. } if *rotation != 0.0
+        ));
+        state.on_mouse_press(MouseButton::Right, 0, 0);
+        assert!(matches!(
+            &state.canvas_set.active_frame().shapes[0],
+            Shape::Rect { rotation, .. } if *rotation == 0.0
+        ));
+        assert_eq!(state.selection_bounds(), Some(bounds));
+    }
 }
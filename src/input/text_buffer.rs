@@ -0,0 +1,462 @@
+//! Editable text buffer with caret and selection for text mode.
+
+use std::ops::Range;
+
+/// Text being edited in text mode, with a caret and an optional selection.
+///
+/// Positions are byte offsets into the UTF-8 text and always sit on a char
+/// boundary, so they can be passed straight to Pango cursor APIs.
+///
+/// An input method can attach a preedit string (text still being composed);
+/// it is shown at the caret but is not part of [`TextBuffer::text`] until the
+/// input method commits it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextBuffer {
+    text: String,
+    /// Caret position (byte offset)
+    caret: usize,
+    /// Selection anchor; the selection spans from here to the caret
+    anchor: Option<usize>,
+    /// Input method composition shown at the caret, if any
+    preedit: Option<Preedit>,
+}
+
+/// Text being composed by an input method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Preedit {
+    text: String,
+    /// Cursor inside the preedit text (byte offset), if the input method shows one
+    cursor: Option<usize>,
+}
+
+impl TextBuffer {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a buffer holding `text` with the caret at the end.
+    pub fn from_text(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let caret = text.len();
+        Self {
+            text,
+            caret,
+            anchor: None,
+            preedit: None,
+        }
+    }
+
+    /// Returns the current text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns true if the buffer holds no text.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Returns the selected byte range, if a non-empty selection exists.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        if anchor == self.caret {
+            return None;
+        }
+        Some(anchor.min(self.caret)..anchor.max(self.caret))
+    }
+
+    /// Sets the input method preedit string shown at the caret.
+    ///
+    /// `cursor` is a byte offset into `text`; an empty `text` clears the preedit.
+    pub fn set_preedit(&mut self, text: impl Into<String>, cursor: Option<usize>) {
+        let text = text.into();
+        self.preedit = (!text.is_empty()).then(|| Preedit {
+            cursor: cursor.filter(|&offset| text.is_char_boundary(offset)),
+            text,
+        });
+    }
+
+    /// Removes any preedit string.
+    pub fn clear_preedit(&mut self) {
+        self.preedit = None;
+    }
+
+    /// Returns the text as displayed: the committed text with any preedit
+    /// string inserted at the caret.
+    pub fn display_text(&self) -> std::borrow::Cow<'_, str> {
+        match &self.preedit {
+            Some(preedit) => {
+                let mut text = self.text.clone();
+                text.insert_str(self.caret, &preedit.text);
+                text.into()
+            }
+            None => self.text.as_str().into(),
+        }
+    }
+
+    /// Returns the caret position within [`TextBuffer::display_text`].
+    pub fn display_caret(&self) -> usize {
+        match &self.preedit {
+            Some(preedit) => self.caret + preedit.cursor.unwrap_or(preedit.text.len()),
+            None => self.caret,
+        }
+    }
+
+    /// Returns the byte range of the preedit string within [`TextBuffer::display_text`].
+    pub fn preedit_range(&self) -> Option<Range<usize>> {
+        self.preedit
+            .as_ref()
+            .map(|preedit| self.caret..self.caret + preedit.text.len())
+    }
+
+    /// Selects the whole text, leaving the caret at the end.
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.caret = self.text.len();
+    }
+
+    /// Inserts a character at the caret, replacing any selection.
+    pub fn insert_char(&mut self, c: char) {
+        let mut buf = [0u8; 4];
+        self.insert_str(c.encode_utf8(&mut buf));
+    }
+
+    /// Inserts a string at the caret, replacing any selection.
+    pub fn insert_str(&mut self, s: &str) {
+        self.delete_selection();
+        self.text.insert_str(self.caret, s);
+        self.caret += s.len();
+    }
+
+    /// Deletes the selection, or the character before the caret.
+    ///
+    /// Returns true if any text was removed.
+    pub fn delete_backward(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let start = self.prev_char_boundary(self.caret);
+        self.delete_range(start..self.caret)
+    }
+
+    /// Deletes the selection, or the character after the caret.
+    pub fn delete_forward(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let end = self.next_char_boundary(self.caret);
+        self.delete_range(self.caret..end)
+    }
+
+    /// Deletes the selection, or the word before the caret.
+    pub fn delete_word_backward(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let start = self.prev_word_boundary(self.caret);
+        self.delete_range(start..self.caret)
+    }
+
+    /// Deletes the selection, or the word after the caret.
+    pub fn delete_word_forward(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        let end = self.next_word_boundary(self.caret);
+        self.delete_range(self.caret..end)
+    }
+
+    /// Moves the caret to a byte offset, snapped back to a char boundary.
+    pub fn move_to(&mut self, position: usize, extend: bool) {
+        let mut position = position.min(self.text.len());
+        while !self.text.is_char_boundary(position) {
+            position -= 1;
+        }
+        self.set_caret(position, extend);
+    }
+
+    /// Moves the caret one character left.
+    ///
+    /// Without `extend`, an active selection collapses to its start instead.
+    pub fn move_left(&mut self, extend: bool) {
+        if !extend && let Some(range) = self.selection() {
+            self.set_caret(range.start, false);
+            return;
+        }
+        let target = self.prev_char_boundary(self.caret);
+        self.set_caret(target, extend);
+    }
+
+    /// Moves the caret one character right.
+    ///
+    /// Without `extend`, an active selection collapses to its end instead.
+    pub fn move_right(&mut self, extend: bool) {
+        if !extend && let Some(range) = self.selection() {
+            self.set_caret(range.end, false);
+            return;
+        }
+        let target = self.next_char_boundary(self.caret);
+        self.set_caret(target, extend);
+    }
+
+    /// Moves the caret to the start of the previous word.
+    pub fn move_word_left(&mut self, extend: bool) {
+        let target = self.prev_word_boundary(self.caret);
+        self.set_caret(target, extend);
+    }
+
+    /// Moves the caret to the end of the next word.
+    pub fn move_word_right(&mut self, extend: bool) {
+        let target = self.next_word_boundary(self.caret);
+        self.set_caret(target, extend);
+    }
+
+    /// Moves the caret to the start of the current line.
+    pub fn move_line_start(&mut self, extend: bool) {
+        let target = self.line_start(self.caret);
+        self.set_caret(target, extend);
+    }
+
+    /// Moves the caret to the end of the current line.
+    pub fn move_line_end(&mut self, extend: bool) {
+        let target = self.line_end(self.caret);
+        self.set_caret(target, extend);
+    }
+
+    /// Moves the caret to the start of the text.
+    pub fn move_to_start(&mut self, extend: bool) {
+        self.set_caret(0, extend);
+    }
+
+    /// Moves the caret to the end of the text.
+    pub fn move_to_end(&mut self, extend: bool) {
+        self.set_caret(self.text.len(), extend);
+    }
+
+    /// Moves the caret to the same column on the previous line.
+    pub fn move_up(&mut self, extend: bool) {
+        let start = self.line_start(self.caret);
+        if start == 0 {
+            self.set_caret(0, extend);
+            return;
+        }
+        let column = self.text[start..self.caret].chars().count();
+        let prev_start = self.line_start(start - 1);
+        let target = self.offset_at_column(prev_start, column);
+        self.set_caret(target, extend);
+    }
+
+    /// Moves the caret to the same column on the next line.
+    pub fn move_down(&mut self, extend: bool) {
+        let end = self.line_end(self.caret);
+        if end == self.text.len() {
+            self.set_caret(end, extend);
+            return;
+        }
+        let column = self.text[self.line_start(self.caret)..self.caret]
+            .chars()
+            .count();
+        let target = self.offset_at_column(end + 1, column);
+        self.set_caret(target, extend);
+    }
+
+    fn set_caret(&mut self, position: usize, extend: bool) {
+        if extend {
+            self.anchor.get_or_insert(self.caret);
+        } else {
+            self.anchor = None;
+        }
+        self.caret = position;
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some(range) => self.delete_range(range),
+            None => {
+                self.anchor = None;
+                false
+            }
+        }
+    }
+
+    fn delete_range(&mut self, range: Range<usize>) -> bool {
+        self.anchor = None;
+        if range.is_empty() {
+            return false;
+        }
+        self.caret = range.start;
+        self.text.replace_range(range, "");
+        true
+    }
+
+    fn prev_char_boundary(&self, offset: usize) -> usize {
+        self.text[..offset]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn next_char_boundary(&self, offset: usize) -> usize {
+        self.text[offset..]
+            .chars()
+            .next()
+            .map_or(offset, |c| offset + c.len_utf8())
+    }
+
+    /// Skips whitespace, then the word before `offset`.
+    fn prev_word_boundary(&self, offset: usize) -> usize {
+        let mut chars = self.text[..offset].char_indices().rev().peekable();
+        let mut boundary = offset;
+        while let Some((index, c)) = chars.peek().copied() {
+            if !c.is_whitespace() {
+                break;
+            }
+            boundary = index;
+            chars.next();
+        }
+        for (index, c) in chars {
+            if c.is_whitespace() {
+                break;
+            }
+            boundary = index;
+        }
+        boundary
+    }
+
+    /// Skips whitespace, then the word after `offset`.
+    fn next_word_boundary(&self, offset: usize) -> usize {
+        let mut chars = self.text[offset..].char_indices().peekable();
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        while chars.next_if(|(_, c)| !c.is_whitespace()).is_some() {}
+        chars
+            .peek()
+            .map_or(self.text.len(), |(index, _)| offset + index)
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.text[..offset].rfind('\n').map_or(0, |index| index + 1)
+    }
+
+    fn line_end(&self, offset: usize) -> usize {
+        self.text[offset..]
+            .find('\n')
+            .map_or(self.text.len(), |index| offset + index)
+    }
+
+    /// Byte offset of `column` chars into the line starting at `line_start`,
+    /// clamped to the end of that line.
+    fn offset_at_column(&self, line_start: usize, column: usize) -> usize {
+        let line_end = self.line_end(line_start);
+        self.text[line_start..line_end]
+            .char_indices()
+            .nth(column)
+            .map_or(line_end, |(index, _)| line_start + index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_delete_at_caret() {
+        let mut buffer = TextBuffer::from_text("helo");
+        buffer.move_left(false);
+        buffer.insert_char('l');
+        assert_eq!(buffer.text(), "hello");
+        assert_eq!(buffer.caret, 4);
+
+        assert!(buffer.delete_forward());
+        assert_eq!(buffer.text(), "hell");
+        assert!(buffer.delete_backward());
+        assert_eq!(buffer.text(), "hel");
+        assert_eq!(buffer.caret, 3);
+    }
+
+    #[test]
+    fn caret_moves_over_multibyte_chars() {
+        let mut buffer = TextBuffer::from_text("aé€");
+        buffer.move_left(false);
+        assert_eq!(buffer.caret, 3);
+        buffer.move_left(false);
+        assert_eq!(buffer.caret, 1);
+        buffer.delete_forward();
+        assert_eq!(buffer.text(), "a€");
+    }
+
+    #[test]
+    fn shift_selection_is_replaced_by_typing() {
+        let mut buffer = TextBuffer::from_text("hello world");
+        buffer.move_word_left(true);
+        assert_eq!(
+            buffer.selection().map(|range| &buffer.text()[range]),
+            Some("world")
+        );
+
+        buffer.insert_str("there");
+        assert_eq!(buffer.text(), "hello there");
+        assert!(buffer.selection().is_none());
+    }
+
+    #[test]
+    fn moving_without_shift_collapses_selection() {
+        let mut buffer = TextBuffer::from_text("abc");
+        buffer.select_all();
+        buffer.move_left(false);
+        assert_eq!(buffer.caret, 0);
+        assert!(buffer.selection().is_none());
+    }
+
+    #[test]
+    fn word_deletion_skips_trailing_whitespace() {
+        let mut buffer = TextBuffer::from_text("one two  ");
+        assert!(buffer.delete_word_backward());
+        assert_eq!(buffer.text(), "one ");
+
+        buffer.move_to_start(false);
+        assert!(buffer.delete_word_forward());
+        assert_eq!(buffer.text(), " ");
+    }
+
+    #[test]
+    fn home_end_and_vertical_moves_follow_lines() {
+        let mut buffer = TextBuffer::from_text("first\nab\nthird");
+        buffer.move_line_start(false);
+        assert_eq!(buffer.caret, 9);
+
+        buffer.move_right(false);
+        buffer.move_right(false);
+        buffer.move_up(false);
+        assert_eq!(buffer.caret, 8, "column clamps to the shorter line");
+
+        buffer.move_up(false);
+        assert_eq!(buffer.caret, 2);
+
+        buffer.move_line_end(true);
+        assert_eq!(
+            buffer.selection().map(|range| &buffer.text()[range]),
+            Some("rst")
+        );
+
+        buffer.move_down(false);
+        assert_eq!(buffer.caret, 8);
+    }
+
+    #[test]
+    fn preedit_is_displayed_at_caret_but_not_committed() {
+        let mut buffer = TextBuffer::from_text("ab");
+        buffer.move_left(false);
+        buffer.set_preedit("にほ", Some(3));
+
+        assert_eq!(buffer.text(), "ab");
+        assert_eq!(buffer.display_text(), "aにほb");
+        assert_eq!(buffer.display_caret(), 4);
+        assert_eq!(buffer.preedit_range(), Some(1..7));
+
+        buffer.clear_preedit();
+        buffer.insert_str("日本");
+        assert_eq!(buffer.display_text(), "a日本b");
+        assert!(buffer.preedit_range().is_none());
+    }
+}
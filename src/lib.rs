@@ -7,9 +7,12 @@
 //! logic and serialization code with the main binary.
 
 pub mod config;
+pub mod document;
 pub mod draw;
+pub mod export;
 pub mod input;
 pub mod legacy;
+pub mod session;
 pub mod ui;
 pub mod util;
 
@@ -1,4 +1,8 @@
-use clap::{ArgAction, Parser};
+use anyhow::Context;
+use clap::{ArgAction, Parser, Subcommand};
+use signal_hook::consts::signal::SIGUSR2;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::config::{MigrationActions, MigrationReport};
 
@@ -6,10 +10,13 @@ mod backend;
 mod capture;
 mod config;
 mod daemon;
+mod document;
 mod draw;
+mod export;
 mod input;
 mod legacy;
 mod notification;
+mod session;
 mod ui;
 mod util;
 
@@ -25,10 +32,18 @@ struct Cli {
     #[arg(long, short = 'a', action = ArgAction::SetTrue)]
     active: bool,
 
-    /// Initial board mode (transparent, whiteboard, or blackboard)
-    #[arg(long, short = 'm', value_name = "MODE")]
+    /// Initial board mode (transparent, whiteboard, blackboard, or a custom board name)
+    #[arg(long, short = 'm', value_name = "MODE", global = true)]
     mode: Option<String>,
 
+    /// Export the boards when the overlay closes (svg or pdf); requires --active
+    #[arg(long, value_name = "FORMAT", requires = "active")]
+    export: Vec<export::ExportFormat>,
+
+    /// Open a saved annotation document (implies --active)
+    #[arg(long, value_name = "FILE")]
+    open: Option<PathBuf>,
+
     /// Copy configuration files from ~/.config/hyprmarker to ~/.config/wayscriber
     #[arg(long, action = ArgAction::SetTrue)]
     migrate_config: bool,
@@ -36,6 +51,19 @@ struct Cli {
     /// Preview the migration without copying files (requires --migrate-config)
     #[arg(long = "dry-run", action = ArgAction::SetTrue, requires = "migrate_config")]
     migrate_config_dry_run: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Show the overlay and export the boards when it closes
+    Export {
+        /// Export format: svg (active board) or pdf (one page per board)
+        #[arg(value_name = "FORMAT")]
+        format: export::ExportFormat,
+    },
 }
 
 fn main() -> anyhow::Result<()> {
@@ -43,6 +71,14 @@ fn main() -> anyhow::Result<()> {
 
     let cli = Cli::parse();
 
+    // `export` is a one-shot session that saves the boards on close
+    let mut active = cli.active;
+    let mut exports = cli.export;
+    if let Some(Command::Export { format }) = cli.command {
+        active = true;
+        exports.push(format);
+    }
+
     maybe_print_alias_notice();
 
     if cli.migrate_config {
@@ -50,8 +86,19 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    // Opening a document is a one-shot session too; load it up front so a
+    // bad file is reported before the overlay appears
+    let opened = match cli.open {
+        Some(path) => {
+            active = true;
+            let loaded = document::load(&path)?;
+            Some((path, loaded))
+        }
+        None => None,
+    };
+
     // Check for Wayland environment
-    if std::env::var("WAYLAND_DISPLAY").is_err() && (cli.daemon || cli.active) {
+    if std::env::var("WAYLAND_DISPLAY").is_err() && (cli.daemon || active) {
         log::error!("WAYLAND_DISPLAY not set - this application requires Wayland.");
         log::error!("Please run on a Wayland compositor (Hyprland, Sway, etc.).");
         return Err(anyhow::anyhow!("Wayland environment required"));
@@ -60,9 +107,9 @@ fn main() -> anyhow::Result<()> {
     if cli.daemon {
         // Daemon mode: background service with toggle activation
         log::info!("Starting in daemon mode");
-        let mut daemon = daemon::Daemon::new(cli.mode);
+        let mut daemon = daemon::Daemon::new(cli.mode)?;
         daemon.run()?;
-    } else if cli.active {
+    } else if active {
         // One-shot mode: show overlay immediately and exit when done
         log::info!("Starting Wayland overlay...");
         log::info!("Starting annotation overlay...");
@@ -82,10 +129,22 @@ fn main() -> anyhow::Result<()> {
         log::info!("  - Decrease thickness: - or _ or scroll up");
         log::info!("  - Help: F10");
         log::info!("  - Exit: Escape");
+        log::info!("  - Click-through: pkill -SIGUSR2 wayscriber");
         log::info!("");
 
+        // SIGUSR2 toggles click-through, as in daemon mode
+        let control = Arc::new(
+            backend::OverlayControl::new().context("Failed to create overlay control socket")?,
+        );
+        control
+            .toggle_click_through_on(SIGUSR2)
+            .context("Failed to register signal handler")?;
+
         // Run Wayland backend
-        backend::run_wayland(cli.mode)?;
+        let exported = backend::run_wayland_oneshot(cli.mode, opened, exports, control)?;
+        for path in exported {
+            println!("Exported board to {}", path.display());
+        }
 
         log::info!("Annotation overlay closed.");
     } else {
@@ -95,6 +154,8 @@ fn main() -> anyhow::Result<()> {
         println!("Usage:");
         println!("  wayscriber --daemon    Run as background daemon (toggle with Super+D)");
         println!("  wayscriber --active    Show overlay immediately (one-shot mode)");
+        println!("  wayscriber export pdf  Show overlay, save all boards as a PDF on close");
+        println!("  wayscriber --open F    Show overlay with a saved document");
         println!("  wayscriber --help      Show help");
         println!();
         println!("Daemon mode (recommended):");
@@ -102,7 +163,9 @@ fn main() -> anyhow::Result<()> {
         println!("  2. Add to Hyprland config:");
         println!("     exec-once = wayscriber --daemon");
         println!("     bind = SUPER, D, exec, pkill -SIGUSR1 wayscriber");
+        println!("     bind = SUPER SHIFT, D, exec, pkill -SIGUSR2 wayscriber");
         println!("  3. Press Super+D to toggle overlay on/off");
+        println!("     and Super+Shift+D to let clicks through to your apps");
         println!();
         println!("Requirements:");
         println!("  - Wayland compositor (Hyprland, Sway, etc.)");
@@ -196,7 +259,8 @@ fn pluralize<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
 
 #[cfg(test)]
 mod tests {
-    use super::Cli;
+    use super::{Cli, Command};
+    use crate::export::ExportFormat;
     use clap::Parser;
 
     #[test]
@@ -218,4 +282,28 @@ mod tests {
         assert!(cli.active);
         assert_eq!(cli.mode.as_deref(), Some("whiteboard"));
     }
+
+    #[test]
+    fn export_subcommand_takes_format_and_mode() {
+        let cli = Cli::try_parse_from(["wayscriber", "export", "pdf", "-m", "blackboard"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Export {
+                format: ExportFormat::Pdf
+            })
+        ));
+        assert_eq!(cli.mode.as_deref(), Some("blackboard"));
+
+        assert!(Cli::try_parse_from(["wayscriber", "--export", "svg"]).is_err());
+    }
+
+    #[test]
+    fn open_takes_a_document_path() {
+        let cli = Cli::try_parse_from(["wayscriber", "--open", "notes.wayscriber"]).unwrap();
+        assert_eq!(
+            cli.open.as_deref(),
+            Some(std::path::Path::new("notes.wayscriber"))
+        );
+        assert!(!cli.active);
+    }
 }
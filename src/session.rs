@@ -0,0 +1,308 @@
+//! Drawing sessions saved across overlay restarts.
+//!
+//! Every toggle of the overlay builds a fresh [`InputState`]. With
+//! `[session] persist = true`, the backend saves the boards and pen settings
+//! as a [`Document`] when the overlay closes and restores them the next time
+//! it opens.
+//!
+//! Independently of that, [`Autosave`] periodically writes the open session
+//! to a recovery journal. The journal is removed when the overlay closes
+//! normally, so finding one on startup means the last session crashed.
+//!
+//! [`InputState`]: crate::input::InputState
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::document::{self, Document};
+use crate::input::InputState;
+
+/// Directory under the XDG state dir holding wayscriber's state files.
+const STATE_DIR: &str = "wayscriber";
+
+/// File name of the saved session.
+const SESSION_FILE: &str = "session.json";
+
+/// File name of the autosave journal.
+const JOURNAL_FILE: &str = "autosave.json";
+
+/// Returns where the session is saved (`$XDG_STATE_HOME/wayscriber/session.json`).
+pub fn session_path() -> Option<PathBuf> {
+    dirs::state_dir().map(|dir| dir.join(STATE_DIR).join(SESSION_FILE))
+}
+
+/// Returns where the autosave journal is written (`$XDG_STATE_HOME/wayscriber/autosave.json`).
+pub fn journal_path() -> Option<PathBuf> {
+    dirs::state_dir().map(|dir| dir.join(STATE_DIR).join(JOURNAL_FILE))
+}
+
+/// Loads a saved session, or `None` if there is none.
+pub fn load(path: &Path) -> Result<Option<Document>> {
+    match document::load(path) {
+        Ok(session) => Ok(Some(session)),
+        Err(e)
+            if e.downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.kind() == ErrorKind::NotFound) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Saves a session, replacing any previous one.
+pub fn save(path: &Path, session: &Document) -> Result<()> {
+    document::save(path, session)
+}
+
+/// Periodic autosave of the open session to a recovery journal.
+pub struct Autosave {
+    /// Journal file
+    path: PathBuf,
+    /// Minimum time between two saves
+    interval: Duration,
+    /// When the journal was last brought up to date
+    last_check: Instant,
+    /// Whether the session may have changed since the last check
+    pending: bool,
+    /// Session as last written (or as it was when autosaving started)
+    saved: Document,
+}
+
+impl Autosave {
+    /// Starts autosaving `state` to `path`.
+    ///
+    /// Nothing is written until the session differs from how it is now, so
+    /// an untouched overlay never leaves a journal behind.
+    pub fn new(path: PathBuf, interval: Duration, state: &InputState, now: Instant) -> Self {
+        Self {
+            path,
+            interval,
+            last_check: now,
+            pending: false,
+            saved: Document::capture(state, true),
+        }
+    }
+
+    /// Returns the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Notes that the session may have changed, so the journal is checked
+    /// once the interval has passed.
+    pub fn note_change(&mut self) {
+        self.pending = true;
+    }
+
+    /// Returns how long until a noted change is due to be journaled, or
+    /// `None` if nothing changed since the last check.
+    ///
+    /// The event loop sleeps at most this long, so changes are journaled even
+    /// when no further input arrives.
+    pub fn next_check_in(&self, now: Instant) -> Option<Duration> {
+        self.pending.then(|| {
+            self.interval
+                .saturating_sub(now.duration_since(self.last_check))
+        })
+    }
+
+    /// Writes the journal if the interval has passed and the session changed
+    /// since the last save.
+    ///
+    /// Returns `true` if the journal was written.
+    pub fn tick(&mut self, state: &InputState, now: Instant) -> Result<bool> {
+        if now.duration_since(self.last_check) < self.interval {
+            return Ok(false);
+        }
+        self.last_check = now;
+
+        let current = Document::capture(state, true);
+        let changed = current != self.saved;
+        if changed {
+            save(&self.path, &current)?;
+            self.saved = current;
+        }
+        self.pending = false;
+        Ok(changed)
+    }
+
+    /// Removes the journal, e.g. after the overlay closed normally.
+    pub fn remove_journal(&self) -> Result<()> {
+        remove_journal(&self.path)
+    }
+}
+
+/// Removes an autosave journal if there is one.
+pub fn remove_journal(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BoardConfig, PaletteConfig};
+    use crate::draw::color::{BLACK, RED};
+    use crate::draw::{FontDescriptor, Shape, TextEffect, TextFormat};
+    use crate::input::{BoardMode, InputState};
+    use std::collections::HashMap;
+
+    fn input_state() -> InputState {
+        InputState::with_defaults(
+            RED,
+            3.0,
+            32.0,
+            FontDescriptor::default(),
+            TextEffect::default(),
+            TextFormat::default(),
+            20.0,
+            30.0,
+            BoardConfig::default(),
+            PaletteConfig::default(),
+            HashMap::new(),
+        )
+    }
+
+    fn rect(x: i32) -> Shape {
+        Shape::Rect {
+            x,
+            y: 10,
+            w: 20,
+            h: 20,
+            color: RED,
+            thick: 2.0,
+            rotation: 0.0,
+        }
+    }
+
+    #[test]
+    fn session_round_trips_through_a_file() {
+        let mut state = input_state();
+        state.canvas_set.active_frame_mut().add_shape(rect(0));
+        state.switch_board_mode(BoardMode::Whiteboard);
+        state.canvas_set.active_frame_mut().add_shape(rect(50));
+        state.canvas_set.active_frame_mut().add_shape(rect(100));
+        state.current_thickness = 7.0;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join(SESSION_FILE);
+        assert_eq!(load(&path).unwrap(), None);
+        save(&path, &Document::capture(&state, true)).unwrap();
+        let saved = load(&path).unwrap().unwrap();
+
+        let mut restored = input_state();
+        saved.restore(&mut restored, true);
+        assert_eq!(*restored.board_mode(), BoardMode::Whiteboard);
+        assert_eq!(restored.current_color, BLACK);
+        assert_eq!(restored.board_previous_color, Some(RED));
+        assert_eq!(restored.current_thickness, 7.0);
+        assert_eq!(restored.canvas_set.active_frame().shapes.len(), 2);
+        // Restoring is not an undoable edit
+        assert!(!restored.canvas_set.active_frame_mut().undo());
+
+        restored.switch_board_mode(BoardMode::Transparent);
+        assert_eq!(restored.canvas_set.active_frame().shapes, vec![rect(0)]);
+        assert_eq!(restored.current_color, RED);
+    }
+
+    #[test]
+    fn autosave_writes_changes_once_per_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(JOURNAL_FILE);
+        let start = Instant::now();
+        let interval = Duration::from_secs(30);
+        let mut state = input_state();
+        let mut autosave = Autosave::new(path.clone(), interval, &state, start);
+
+        // Unchanged sessions are never written
+        assert!(!autosave.tick(&state, start + interval).unwrap());
+        assert!(!path.exists());
+
+        state.canvas_set.active_frame_mut().add_shape(rect(0));
+        assert!(!autosave.tick(&state, start + interval).unwrap());
+        assert!(autosave.tick(&state, start + interval * 2).unwrap());
+        let journal = load(&path).unwrap().unwrap();
+        assert_eq!(journal.boards[0].pages[0].shapes, vec![rect(0)]);
+
+        autosave.remove_journal().unwrap();
+        assert_eq!(load(&path).unwrap(), None);
+        autosave.remove_journal().unwrap();
+    }
+
+    #[test]
+    fn noted_changes_are_due_without_further_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(JOURNAL_FILE);
+        let start = Instant::now();
+        let interval = Duration::from_secs(30);
+        let mut state = input_state();
+        let mut autosave = Autosave::new(path.clone(), interval, &state, start);
+        assert_eq!(autosave.next_check_in(start), None, "nothing to wait for");
+
+        // An edit shortly after a check is due once the interval is up
+        let edited = start + Duration::from_secs(10);
+        state.canvas_set.active_frame_mut().add_shape(rect(0));
+        autosave.note_change();
+        assert!(!autosave.tick(&state, edited).unwrap());
+        let wait = autosave.next_check_in(edited).unwrap();
+        assert_eq!(wait, Duration::from_secs(20));
+
+        // Waking after that wait, with no input in between, writes the journal
+        assert!(autosave.tick(&state, edited + wait).unwrap());
+        assert!(path.exists());
+        assert_eq!(autosave.next_check_in(edited + wait), None);
+    }
+
+    #[test]
+    fn requested_mode_wins_over_the_saved_one() {
+        let mut state = input_state();
+        state.switch_board_mode(BoardMode::Blackboard);
+        state.canvas_set.active_frame_mut().add_shape(rect(0));
+        let saved = Document::capture(&state, true);
+
+        let mut restored = input_state();
+        saved.restore(&mut restored, false);
+        assert_eq!(*restored.board_mode(), BoardMode::Transparent);
+        assert_eq!(restored.current_color, RED);
+        restored.switch_board_mode(BoardMode::Blackboard);
+        assert_eq!(restored.canvas_set.active_frame().shapes, vec![rect(0)]);
+    }
+
+    #[test]
+    fn custom_boards_restore_regardless_of_case() {
+        let mut state = input_state();
+        state.switch_board_mode(BoardMode::Custom("Blueprint".to_string()));
+        state.canvas_set.active_frame_mut().add_shape(rect(0));
+        let saved = Document::capture(&state, false);
+
+        let mut restored = input_state();
+        restored
+            .board_config
+            .custom
+            .push(crate::config::CustomBoardConfig {
+                name: "blueprint".to_string(),
+                background_color: [0.1, 0.2, 0.5],
+                background_alpha: 1.0,
+                pen_color: None,
+                keys: Vec::new(),
+            });
+        saved.restore(&mut restored, true);
+        let blueprint = BoardMode::Custom("blueprint".to_string());
+        assert_eq!(*restored.board_mode(), blueprint);
+        assert_eq!(restored.canvas_set.active_frame().shapes, vec![rect(0)]);
+        assert!(
+            restored
+                .board_mode()
+                .background_color(&restored.board_config)
+                .is_some()
+        );
+    }
+}
@@ -1,5 +1,6 @@
 /// UI rendering: status bar, help overlay, visual indicators
 use crate::config::StatusPosition;
+use crate::draw::BoundingBox;
 use crate::input::{BoardMode, DrawingState, InputState, Tool};
 use std::f64::consts::{FRAC_PI_2, PI};
 
@@ -17,6 +18,8 @@ const STATUS_BG_WIDTH_PAD: f64 = 10.0;
 const STATUS_BG_HEIGHT_PAD: f64 = 8.0;
 /// Color indicator dot X offset
 const STATUS_DOT_OFFSET_X: f64 = 3.0;
+/// Gap between the status text and recent color swatches, and between swatches
+const STATUS_SWATCH_GAP: f64 = 6.0;
 
 fn fallback_text_extents(font_size: f64, text: &str) -> cairo::TextExtents {
     let width = text.len() as f64 * font_size * 0.5;
@@ -56,7 +59,9 @@ fn draw_rounded_rect(ctx: &cairo::Context, x: f64, y: f64, width: f64, height: f
     ctx.close_path();
 }
 
-/// Render status bar showing current color, thickness, and tool
+/// Render status bar showing current color, thickness, tool and recent colors
+///
+/// Returns the area the status bar covers, or `None` if nothing was drawn.
 pub fn render_status_bar(
     ctx: &cairo::Context,
     input_state: &InputState,
@@ -64,7 +69,7 @@ pub fn render_status_bar(
     style: &crate::config::StatusBarStyle,
     screen_width: u32,
     screen_height: u32,
-) {
+) -> Option<BoundingBox> {
     let color = &input_state.current_color;
     let thickness = input_state.current_thickness;
     let tool = input_state.modifiers.current_tool();
@@ -72,6 +77,10 @@ pub fn render_status_bar(
     // Determine tool name
     let tool_name = match &input_state.state {
         DrawingState::TextInput { .. } => "Text",
+        DrawingState::SelectingRegion { .. }
+        | DrawingState::MovingSelection { .. }
+        | DrawingState::TransformingSelection { .. } => "Select",
+        DrawingState::Idle if input_state.selection_mode => "Select",
         DrawingState::Drawing { tool, .. } => match tool {
             Tool::Pen => "Pen",
             Tool::Line => "Line",
@@ -93,16 +102,46 @@ pub fn render_status_bar(
 
     // Get board mode indicator
     let mode_badge = match input_state.board_mode() {
-        BoardMode::Transparent => "",
-        BoardMode::Whiteboard => "[WHITEBOARD] ",
-        BoardMode::Blackboard => "[BLACKBOARD] ",
+        BoardMode::Transparent => String::new(),
+        mode => format!("[{}] ", mode.name().to_uppercase()),
+    };
+
+    // Remind that clicks and keys currently go to the apps below
+    let click_through_badge = if input_state.is_click_through() {
+        "[CLICK-THROUGH] "
+    } else {
+        ""
+    };
+
+    // Show which page of the board is up once it has more than one
+    let (page_index, page_count) = input_state.canvas_set.page_position();
+    let page_badge = if page_count > 1 {
+        format!("[Page {}/{}] ", page_index + 1, page_count)
+    } else {
+        String::new()
+    };
+
+    // Show the layer new shapes go to, once the page has more than one
+    let frame = input_state.canvas_set.active_frame();
+    let layer = &frame.layers()[frame.active_layer()];
+    let layer_badge = match (frame.layers().len(), layer.visible) {
+        (1, true) => String::new(),
+        (_, true) => format!("[Layer: {}] ", layer.name),
+        (_, false) => format!("[Layer: {} (hidden)] ", layer.name),
     };
 
-    // Build status text with mode badge and font size
+    // Build status text with mode, page and layer badges and font size
     let font_size = input_state.current_font_size;
     let status_text = format!(
-        "{}[{}] [{}px] [{}] [Text {}px]  F10=Help",
-        mode_badge, color_name, thickness as i32, tool_name, font_size as i32
+        "{}{}{}{}[{}] [{}px] [{}] [Text {}px]  F10=Help",
+        click_through_badge,
+        mode_badge,
+        page_badge,
+        layer_badge,
+        color_name,
+        thickness as i32,
+        tool_name,
+        font_size as i32
     );
 
     // Set font
@@ -118,23 +157,34 @@ pub fn render_status_bar(
                 "Failed to measure status bar text: {}, skipping status bar",
                 e
             );
-            return; // Gracefully skip rendering if font measurement fails
+            return None; // Gracefully skip rendering if font measurement fails
         }
     };
     let text_width = extents.width();
     let text_height = extents.height();
 
+    // Reserve room for recent color swatches after the text
+    let swatches = &input_state.recent_colors;
+    let swatch_radius = style.dot_radius;
+    let swatch_step = swatch_radius * 2.0 + STATUS_SWATCH_GAP;
+    let swatch_width = if swatches.is_empty() {
+        0.0
+    } else {
+        STATUS_SWATCH_GAP + swatches.len() as f64 * swatch_step
+    };
+    let bar_width = text_width + swatch_width;
+
     // Calculate position using configurable padding
     let padding = style.padding;
     let (x, y) = match position {
         StatusPosition::TopLeft => (padding, padding + text_height),
         StatusPosition::TopRight => (
-            screen_width as f64 - text_width - padding,
+            screen_width as f64 - bar_width - padding,
             padding + text_height,
         ),
         StatusPosition::BottomLeft => (padding, screen_height as f64 - padding),
         StatusPosition::BottomRight => (
-            screen_width as f64 - text_width - padding,
+            screen_width as f64 - bar_width - padding,
             screen_height as f64 - padding,
         ),
     };
@@ -153,16 +203,33 @@ pub fn render_status_bar(
             // Light text and background on dark board
             ([0.8, 0.8, 0.8, 0.85], [1.0, 1.0, 1.0, 1.0])
         }
+        mode => match mode.background_color(&input_state.board_config) {
+            // Mostly opaque custom boards get the whiteboard or blackboard colors
+            Some(bg) if bg.a >= 0.5 => {
+                if bg.r * 0.299 + bg.g * 0.587 + bg.b * 0.114 > 0.5 {
+                    ([0.2, 0.2, 0.2, 0.85], [0.0, 0.0, 0.0, 1.0])
+                } else {
+                    ([0.8, 0.8, 0.8, 0.85], [1.0, 1.0, 1.0, 1.0])
+                }
+            }
+            _ => (style.bg_color, style.text_color),
+        },
     };
 
     // Draw semi-transparent background with adaptive color
+    let background = BoundingBox {
+        x: x - STATUS_BG_OFFSET_X,
+        y: y - text_height - STATUS_BG_OFFSET_Y,
+        width: bar_width + STATUS_BG_WIDTH_PAD,
+        height: text_height + STATUS_BG_HEIGHT_PAD,
+    };
     let [r, g, b, a] = bg_color;
     ctx.set_source_rgba(r, g, b, a);
     ctx.rectangle(
-        x - STATUS_BG_OFFSET_X,
-        y - text_height - STATUS_BG_OFFSET_Y,
-        text_width + STATUS_BG_WIDTH_PAD,
-        text_height + STATUS_BG_HEIGHT_PAD,
+        background.x,
+        background.y,
+        background.width,
+        background.height,
     );
     let _ = ctx.fill();
 
@@ -178,6 +245,123 @@ pub fn render_status_bar(
     ctx.set_source_rgba(r, g, b, a);
     ctx.move_to(x, y);
     let _ = ctx.show_text(&status_text);
+
+    // Draw recent color swatches, ringing the one currently in use
+    let mut swatch_x = x + text_width + STATUS_SWATCH_GAP * 2.0 + swatch_radius;
+    for swatch in swatches {
+        ctx.set_source_rgba(swatch.r, swatch.g, swatch.b, swatch.a);
+        ctx.arc(swatch_x, dot_y, swatch_radius, 0.0, 2.0 * PI);
+        let _ = ctx.fill_preserve();
+
+        let (ring_alpha, ring_width) = if swatch == color {
+            (a, 2.0)
+        } else {
+            (a * 0.4, 1.0)
+        };
+        ctx.set_source_rgba(r, g, b, ring_alpha);
+        ctx.set_line_width(ring_width);
+        let _ = ctx.stroke();
+
+        swatch_x += swatch_step;
+    }
+
+    // Glyphs and swatches may poke out of the background
+    let text_ink = BoundingBox {
+        x: x + extents.x_bearing(),
+        y: y + extents.y_bearing(),
+        width: text_width,
+        height: text_height,
+    };
+    let dots = BoundingBox::from_corners(
+        dot_x - swatch_radius,
+        dot_y - swatch_radius,
+        swatch_x + swatch_radius,
+        dot_y + swatch_radius,
+    );
+    Some(background.union(&text_ink).union(&dots))
+}
+
+/// Render the prompt offering to restore a session that crashed
+///
+/// `message` says what would be restored. The screen is dimmed behind the
+/// prompt, which is styled like the help overlay.
+pub fn render_recovery_prompt(
+    ctx: &cairo::Context,
+    style: &crate::config::HelpOverlayStyle,
+    message: &str,
+    screen_width: u32,
+    screen_height: u32,
+) {
+    let title = "Restore unsaved drawing?";
+    let hint = "Enter / Y = restore    •    Esc / N = discard";
+
+    let title_font_size = style.font_size + 6.0;
+    let body_font_size = style.font_size;
+    let line_gap = 10.0;
+
+    let title_extents = text_extents_for(
+        ctx,
+        "Sans",
+        cairo::FontSlant::Normal,
+        cairo::FontWeight::Bold,
+        title_font_size,
+        title,
+    );
+    let body_widths = [message, hint].map(|line| {
+        text_extents_for(
+            ctx,
+            "Sans",
+            cairo::FontSlant::Normal,
+            cairo::FontWeight::Normal,
+            body_font_size,
+            line,
+        )
+        .width()
+    });
+
+    let content_width = body_widths
+        .iter()
+        .fold(title_extents.width(), |width, line| width.max(*line));
+    let content_height = title_font_size + (line_gap + body_font_size) * 2.0 + line_gap;
+    let box_width = content_width + style.padding * 2.0;
+    let box_height = content_height + style.padding * 2.0;
+    let box_x = (screen_width as f64 - box_width) / 2.0;
+    let box_y = (screen_height as f64 - box_height) / 2.0;
+
+    // Dim background behind the prompt
+    ctx.set_source_rgba(0.0, 0.0, 0.0, 0.55);
+    ctx.rectangle(0.0, 0.0, screen_width as f64, screen_height as f64);
+    let _ = ctx.fill();
+
+    let [r, g, b, a] = style.bg_color;
+    ctx.set_source_rgba(r, g, b, a);
+    draw_rounded_rect(ctx, box_x, box_y, box_width, box_height, 12.0);
+    let _ = ctx.fill_preserve();
+    let [r, g, b, a] = style.border_color;
+    ctx.set_source_rgba(r, g, b, a);
+    ctx.set_line_width(style.border_width);
+    let _ = ctx.stroke();
+
+    let [r, g, b, a] = style.text_color;
+    let x = box_x + style.padding;
+    let mut baseline = box_y + style.padding + title_font_size;
+    ctx.set_source_rgba(r, g, b, a);
+    ctx.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Bold);
+    ctx.set_font_size(title_font_size);
+    ctx.move_to(x, baseline);
+    let _ = ctx.show_text(title);
+
+    ctx.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Normal);
+    ctx.set_font_size(body_font_size);
+    baseline += line_gap + body_font_size;
+    ctx.move_to(x, baseline);
+    let _ = ctx.show_text(message);
+
+    // Key hint a little fainter and further down
+    baseline += line_gap * 2.0 + body_font_size;
+    ctx.set_source_rgba(r, g, b, a * 0.7);
+    ctx.move_to(x, baseline);
+    let _ = ctx.show_text(hint);
 }
 
 /// Render help overlay showing all keybindings
@@ -225,6 +409,26 @@ pub fn render_help_overlay(
                             key: "Ctrl+Shift+T",
                             action: "Return to Transparent",
                         },
+                        Row {
+                            key: "PageDown/PageUp",
+                            action: "Next / previous page",
+                        },
+                        Row {
+                            key: "Ctrl+Alt+N",
+                            action: "New page",
+                        },
+                        Row {
+                            key: "Ctrl+Alt+D",
+                            action: "Delete page",
+                        },
+                        Row {
+                            key: "Ctrl+Alt+L",
+                            action: "Next layer",
+                        },
+                        Row {
+                            key: "Ctrl+Alt+H",
+                            action: "Show / hide layer",
+                        },
                     ],
                     badges: Vec::new(),
                 },
@@ -243,6 +447,10 @@ pub fn render_help_overlay(
                             key: "Shift+Scroll",
                             action: "Font size",
                         },
+                        Row {
+                            key: "Ctrl+Shift+A",
+                            action: "Cycle text alignment",
+                        },
                     ],
                     badges: Vec::new(),
                 },
@@ -273,6 +481,22 @@ pub fn render_help_overlay(
                             key: "Ctrl+Shift+I",
                             action: "Selection (capture defaults)",
                         },
+                        Row {
+                            key: "Ctrl+E",
+                            action: "Export board → SVG",
+                        },
+                        Row {
+                            key: "Ctrl+Shift+E",
+                            action: "Export all boards → PDF",
+                        },
+                        Row {
+                            key: "Ctrl+Alt+S",
+                            action: "Save document",
+                        },
+                        Row {
+                            key: "Ctrl+Alt+Shift+S",
+                            action: "Save as new document",
+                        },
                     ],
                     badges: Vec::new(),
                 },
@@ -307,12 +531,39 @@ pub fn render_help_overlay(
                             key: "T",
                             action: "Text mode",
                         },
+                        Row {
+                            key: "S",
+                            action: "Selection tool",
+                        },
+                        Row {
+                            key: "Arrows / Delete",
+                            action: "Nudge / delete selection",
+                        },
+                        Row {
+                            key: "Drag handles",
+                            action: "Scale / rotate selection",
+                        },
+                        Row {
+                            key: "Ctrl+Alt+C / Ctrl+X / Ctrl+V",
+                            action: "Copy / cut / paste shapes",
+                        },
+                        Row {
+                            key: "Ctrl+] / Ctrl+[",
+                            action: "Bring to front / send to back",
+                        },
+                        Row {
+                            key: "Ctrl+G / Ctrl+Shift+G",
+                            action: "Group / ungroup selection",
+                        },
                     ],
                     badges: Vec::new(),
                 },
                 Section {
                     title: "Colors",
-                    rows: Vec::new(),
+                    rows: vec![Row {
+                        key: "C / Shift+C",
+                        action: "Cycle recent colors",
+                    }],
                     badges: vec![
                         Badge {
                             label: "R",
@@ -355,10 +606,22 @@ pub fn render_help_overlay(
                             key: "E",
                             action: "Clear frame",
                         },
+                        Row {
+                            key: "Shift+E",
+                            action: "Clear layer",
+                        },
                         Row {
                             key: "Ctrl+Z",
                             action: "Undo",
                         },
+                        Row {
+                            key: "Ctrl+Shift+Z / Ctrl+Y",
+                            action: "Redo",
+                        },
+                        Row {
+                            key: "Ctrl+Shift+N",
+                            action: "Start fresh (clear all boards)",
+                        },
                         Row {
                             key: "Escape / Ctrl+Q",
                             action: "Exit",
@@ -367,6 +630,10 @@ pub fn render_help_overlay(
                             key: "F10",
                             action: "Toggle help",
                         },
+                        Row {
+                            key: "Space (hold / tap)",
+                            action: "Peek at the screen",
+                        },
                     ],
                     badges: Vec::new(),
                 },
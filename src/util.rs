@@ -4,8 +4,10 @@
 //! - Key-to-color mapping for keyboard shortcuts (constants moved to draw::color)
 //! - Arrowhead geometry calculations
 //! - Ellipse bounding box calculations
+//! - Home directory expansion in configured paths
 
 use crate::draw::{Color, color::*};
+use std::path::PathBuf;
 
 // ============================================================================
 // Arrowhead Geometry
@@ -90,6 +92,7 @@ pub fn calculate_arrowhead_custom(
 /// # Returns
 /// - `Some(Color)` if the character maps to a predefined color
 /// - `None` if the character doesn't correspond to any color
+#[allow(dead_code)] // Default palette keys; kept for library consumers and tests
 pub fn key_to_color(c: char) -> Option<Color> {
     match c.to_ascii_uppercase() {
         'R' => Some(RED),
@@ -215,6 +218,16 @@ pub fn ellipse_bounds(x1: i32, y1: i32, x2: i32, y2: i32) -> (i32, i32, i32, i32
     (cx, cy, rx, ry)
 }
 
+/// Expand tilde (~) in path strings.
+pub fn expand_tilde(path: &str) -> PathBuf {
+    if let Some(stripped) = path.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(stripped);
+    }
+    PathBuf::from(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,4 +276,13 @@ mod tests {
             "Custom"
         );
     }
+
+    #[test]
+    fn expand_tilde_resolves_home_paths() {
+        let expanded = expand_tilde("~/Pictures");
+        assert!(!expanded.to_string_lossy().starts_with("~"));
+
+        let no_tilde = expand_tilde("/absolute/path");
+        assert_eq!(no_tilde, PathBuf::from("/absolute/path"));
+    }
 }
@@ -38,6 +38,26 @@ fn dry_run_requires_migrate_flag() {
         ));
 }
 
+#[test]
+fn export_rejects_unknown_formats() {
+    wayscriber_cmd()
+        .env_remove("WAYLAND_DISPLAY")
+        .args(["--active", "--export", "png"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown export format 'png'"));
+}
+
+#[test]
+fn export_subcommand_requires_wayland_env() {
+    wayscriber_cmd()
+        .env_remove("WAYLAND_DISPLAY")
+        .args(["export", "pdf", "--mode", "whiteboard"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("WAYLAND_DISPLAY not set"));
+}
+
 #[test]
 fn migrate_dry_run_uses_temp_config_home() {
     let temp = TempDir::new().unwrap();
@@ -53,3 +73,24 @@ fn migrate_dry_run_uses_temp_config_home() {
         .success()
         .stdout(predicate::str::contains("Dry-run: would copy 1 file"));
 }
+
+#[test]
+fn open_reports_unreadable_documents() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("notes.wayscriber");
+    std::fs::write(
+        &path,
+        r#"{"version": 99, "active_board": "transparent", "boards": []}"#,
+    )
+    .unwrap();
+
+    wayscriber_cmd()
+        .env_remove("WAYLAND_DISPLAY")
+        .arg("--open")
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "newer than this wayscriber supports",
+        ));
+}
@@ -0,0 +1,175 @@
+//! Rendering tests and benchmarks for the committed shape cache.
+//!
+//! The benchmarks are ignored by default; run them in release mode with
+//! `cargo test --release --test render_bench -- --ignored --nocapture`.
+
+use std::time::{Duration, Instant};
+
+use cairo::{Context, Format, ImageSurface};
+use wayscriber::config::Config;
+use wayscriber::draw::cache::ShapeCache;
+use wayscriber::draw::{Color, Frame, Shape, render_freehand_borrowed, render_shapes};
+use wayscriber::input::{InputState, MouseButton};
+
+const WIDTH: i32 = 1920;
+const HEIGHT: i32 = 1080;
+const STROKE_EVENTS: usize = 200;
+
+const PEN: Color = Color {
+    r: 1.0,
+    g: 0.0,
+    b: 0.0,
+    a: 1.0,
+};
+
+/// Builds a frame with `count` freehand strokes and rectangles spread over the screen.
+fn busy_frame(count: usize, width: i32, height: i32) -> Frame {
+    let mut frame = Frame::new();
+    for i in 0..count as i32 {
+        let x = (i * 97) % (width - 200);
+        let y = (i * 61) % (height - 100);
+        if i % 2 == 0 {
+            let points = (0..100).map(|t| (x + t * 2, y + (t * 7) % 50)).collect();
+            frame.add_shape(Shape::Freehand {
+                points,
+                color: PEN,
+                thick: 3.0,
+            });
+        } else {
+            frame.add_shape(Shape::Rect {
+                x,
+                y,
+                w: 120,
+                h: 80,
+                color: PEN,
+                thick: 2.0,
+                rotation: 0.0,
+            });
+        }
+    }
+    frame
+}
+
+/// Points of a freehand stroke being drawn across the screen.
+fn stroke() -> Vec<(i32, i32)> {
+    (0..STROKE_EVENTS as i32)
+        .map(|i| (100 + i * 8, 300 + (i * 13) % 200))
+        .collect()
+}
+
+/// Renders one screen update per pointer event of a stroke being drawn,
+/// either from the shape cache or by rendering every shape each time.
+fn draw_stroke(frame: &Frame, cache: Option<&mut ShapeCache>) -> Duration {
+    let surface = ImageSurface::create(Format::ARgb32, WIDTH, HEIGHT).unwrap();
+    let ctx = Context::new(&surface).unwrap();
+    let points = stroke();
+    let start = Instant::now();
+    match cache {
+        Some(cache) => {
+            for end in 1..=points.len() {
+                cache.update(frame).unwrap();
+                cache.paint(&ctx).unwrap();
+                render_freehand_borrowed(&ctx, &points[..end], PEN, 3.0);
+            }
+        }
+        None => {
+            for end in 1..=points.len() {
+                ctx.set_operator(cairo::Operator::Clear);
+                ctx.paint().unwrap();
+                ctx.set_operator(cairo::Operator::Over);
+                render_shapes(&ctx, frame);
+                render_freehand_borrowed(&ctx, &points[..end], PEN, 3.0);
+            }
+        }
+    }
+    surface.flush();
+    start.elapsed()
+}
+
+fn make_input_state() -> InputState {
+    let action_map = Config::default().build_action_map().unwrap();
+    InputState::with_defaults(
+        PEN,
+        3.0,
+        32.0,
+        wayscriber::draw::FontDescriptor::default(),
+        wayscriber::draw::TextEffect::default(),
+        wayscriber::draw::TextFormat::default(),
+        20.0,
+        30.0,
+        wayscriber::config::BoardConfig::default(),
+        wayscriber::config::PaletteConfig::default(),
+        action_map,
+    )
+}
+
+fn pixels(surface: &mut ImageSurface) -> Vec<u8> {
+    surface.flush();
+    surface.data().unwrap().to_vec()
+}
+
+#[test]
+fn cached_render_matches_direct_render() {
+    let frame = busy_frame(200, 600, 400);
+    let mut cache = ShapeCache::new(600, 400).unwrap();
+    cache.update(&frame).unwrap();
+
+    let mut cached = ImageSurface::create(Format::ARgb32, 600, 400).unwrap();
+    cache.paint(&Context::new(&cached).unwrap()).unwrap();
+    let mut direct = ImageSurface::create(Format::ARgb32, 600, 400).unwrap();
+    render_shapes(&Context::new(&direct).unwrap(), &frame);
+
+    assert!(pixels(&mut cached) == pixels(&mut direct));
+}
+
+#[test]
+fn stroke_in_progress_redraws_no_shapes() {
+    let mut input = make_input_state();
+    *input.canvas_set.active_frame_mut() = busy_frame(1000, WIDTH, HEIGHT);
+    let mut cache = ShapeCache::new(WIDTH, HEIGHT).unwrap();
+    cache.update(input.canvas_set.active_frame()).unwrap();
+    assert_eq!(cache.shapes_rendered(), 1000);
+
+    // The stroke being drawn is painted over the cache, which stays as it is
+    let points = stroke();
+    input.on_mouse_press(MouseButton::Left, points[0].0, points[0].1);
+    for &(x, y) in &points[1..] {
+        input.on_mouse_motion(x, y);
+        assert!(input.get_provisional_shape(x, y).is_some());
+        let damage = cache.update(input.canvas_set.active_frame()).unwrap();
+        assert!(damage.is_empty(), "stroke in progress damaged {damage:?}");
+        assert_eq!(cache.shapes_rendered(), 0);
+    }
+
+    // Finishing it redraws only the shapes it crosses
+    let (x, y) = points[points.len() - 1];
+    input.on_mouse_release(MouseButton::Left, x, y);
+    let frame = input.canvas_set.active_frame();
+    assert_eq!(frame.shapes.len(), 1001);
+    assert!(!cache.update(frame).unwrap().is_empty());
+    assert!((1..1001).contains(&cache.shapes_rendered()));
+}
+
+#[test]
+#[ignore = "benchmark; run with --release -- --ignored --nocapture"]
+fn bench_freehand_stroke_over_busy_canvas() {
+    let empty = Frame::new();
+    let busy = busy_frame(1000, WIDTH, HEIGHT);
+
+    let mut cache = ShapeCache::new(WIDTH, HEIGHT).unwrap();
+    cache.update(&empty).unwrap();
+    let cached_empty = draw_stroke(&empty, Some(&mut cache));
+    let mut cache = ShapeCache::new(WIDTH, HEIGHT).unwrap();
+    cache.update(&busy).unwrap();
+    let cached_busy = draw_stroke(&busy, Some(&mut cache));
+    let direct_busy = draw_stroke(&busy, None);
+
+    let per_event = |total: Duration| total / STROKE_EVENTS as u32;
+    println!("stroke of {STROKE_EVENTS} pointer events at {WIDTH}x{HEIGHT}, per event:");
+    println!(
+        "  cached, empty canvas:       {:?}",
+        per_event(cached_empty)
+    );
+    println!("  cached, 1000 shapes:        {:?}", per_event(cached_busy));
+    println!("  uncached, 1000 shapes:      {:?}", per_event(direct_busy));
+}
@@ -1,11 +1,10 @@
 use cairo::{Context, ImageSurface};
-use wayscriber::config::{HelpOverlayStyle, KeybindingsConfig, StatusBarStyle, StatusPosition};
-use wayscriber::draw::Color;
+use wayscriber::config::{Config, HelpOverlayStyle, StatusBarStyle, StatusPosition};
+use wayscriber::draw::{BoundingBox, Color};
 use wayscriber::input::InputState;
 
 fn make_input_state() -> InputState {
-    let keybindings = KeybindingsConfig::default();
-    let action_map = keybindings.build_action_map().unwrap();
+    let action_map = Config::default().build_action_map().unwrap();
     InputState::with_defaults(
         Color {
             r: 1.0,
@@ -16,10 +15,12 @@ fn make_input_state() -> InputState {
         4.0,
         32.0,
         wayscriber::draw::FontDescriptor::default(),
-        false,
+        wayscriber::draw::TextEffect::default(),
+        wayscriber::draw::TextFormat::default(),
         20.0,
         30.0,
         wayscriber::config::BoardConfig::default(),
+        wayscriber::config::PaletteConfig::default(),
         action_map,
     )
 }
@@ -37,6 +38,18 @@ fn surface_has_pixels(surface: &mut ImageSurface) -> bool {
         .unwrap_or(false)
 }
 
+fn pixels_inside(surface: &mut ImageSurface, bounds: &BoundingBox) -> bool {
+    let width = surface.width() as usize;
+    let stride = surface.stride() as usize;
+    let data = surface.data().unwrap();
+    data.chunks(stride).enumerate().all(|(y, row)| {
+        row[..width * 4]
+            .chunks(4)
+            .enumerate()
+            .all(|(x, pixel)| pixel == [0, 0, 0, 0] || bounds.contains(x as f64, y as f64))
+    })
+}
+
 #[test]
 fn render_status_bar_draws_for_all_positions() {
     let mut input = make_input_state();
@@ -51,16 +64,40 @@ fn render_status_bar_draws_for_all_positions() {
 
     for position in positions {
         let (mut surface, ctx) = surface_with_context(400, 200);
-        wayscriber::ui::render_status_bar(&ctx, &input, position, &style, 400, 200);
+        let bounds = wayscriber::ui::render_status_bar(&ctx, &input, position, &style, 400, 200)
+            .expect("status bar should report its area");
         drop(ctx);
         assert!(
             surface_has_pixels(&mut surface),
             "status bar should render pixels for {:?}",
             position
         );
+        assert!(
+            pixels_inside(&mut surface, &bounds.inflate(1.0)),
+            "status bar should draw inside {:?} for {:?}",
+            bounds,
+            position
+        );
     }
 }
 
+#[test]
+fn render_status_bar_grows_a_page_indicator_for_multi_page_boards() {
+    let mut input = make_input_state();
+    input.update_screen_dimensions(800, 480);
+    let style = StatusBarStyle::default();
+    let width = |input: &InputState| {
+        let (_surface, ctx) = surface_with_context(800, 200);
+        wayscriber::ui::render_status_bar(&ctx, input, StatusPosition::TopLeft, &style, 800, 200)
+            .expect("status bar should report its area")
+            .width
+    };
+
+    let single_page = width(&input);
+    input.canvas_set.new_page();
+    assert!(width(&input) > single_page);
+}
+
 #[test]
 fn render_help_overlay_draws_content() {
     let style = HelpOverlayStyle::default();
@@ -69,3 +106,69 @@ fn render_help_overlay_draws_content() {
     drop(ctx);
     assert!(surface_has_pixels(&mut surface));
 }
+
+#[test]
+fn render_recovery_prompt_draws_content() {
+    let style = HelpOverlayStyle::default();
+    let (mut surface, ctx) = surface_with_context(800, 600);
+    wayscriber::ui::render_recovery_prompt(
+        &ctx,
+        &style,
+        "Drawing from 14:32 with 12 shapes",
+        800,
+        600,
+    );
+    drop(ctx);
+    assert!(surface_has_pixels(&mut surface));
+}
+
+#[test]
+fn text_effects_cover_more_pixels_than_plain_text() {
+    fn covered_pixels(effect: wayscriber::draw::TextEffect) -> usize {
+        let (mut surface, ctx) = surface_with_context(300, 120);
+        wayscriber::draw::render_text(
+            &ctx,
+            20,
+            80,
+            "Hello",
+            Color {
+                r: 1.0,
+                g: 1.0,
+                b: 0.0,
+                a: 1.0,
+            },
+            40.0,
+            &wayscriber::draw::FontDescriptor::default(),
+            &effect,
+            &wayscriber::draw::TextFormat::default(),
+        );
+        drop(ctx);
+        let data = surface.data().unwrap();
+        // ARGB32 stores alpha in the last byte of each pixel on little-endian
+        data.chunks(4).filter(|pixel| pixel[3] != 0).count()
+    }
+
+    use wayscriber::draw::TextEffect;
+    let plain = covered_pixels(TextEffect::None);
+    assert!(plain > 0);
+    for effect in [
+        TextEffect::Background {
+            color: None,
+            padding: 5.0,
+        },
+        TextEffect::Outline {
+            color: None,
+            width: 2.0,
+        },
+        TextEffect::Shadow {
+            color: None,
+            offset: 3.0,
+        },
+    ] {
+        assert!(
+            covered_pixels(effect) > plain,
+            "{:?} should draw around the text",
+            effect
+        );
+    }
+}